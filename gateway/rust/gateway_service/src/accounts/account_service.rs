@@ -20,10 +20,41 @@ impl Display for KeyValidationError {
 }
 
 #[async_trait]
-pub trait AccountService {
+pub trait AccountService: Send + Sync {
     async fn verify_user_key(
         &self,
         username: &Username,
         key: &str,
     ) -> Result<(), KeyValidationError>;
+
+    /// Verifies that `key` may act for `username`, and returns the user the key belongs to,
+    /// which differs from `username` when a member acts for an organization
+    async fn authenticate(
+        &self,
+        username: &Username,
+        key: &str,
+    ) -> Result<Username, KeyValidationError> {
+        self.verify_user_key(username, key).await?;
+        Ok(username.clone())
+    }
+}
+
+/// Lets a request share one account service between the functions it calls
+#[async_trait]
+impl<T: AccountService> AccountService for &T {
+    async fn verify_user_key(
+        &self,
+        username: &Username,
+        key: &str,
+    ) -> Result<(), KeyValidationError> {
+        (**self).verify_user_key(username, key).await
+    }
+
+    async fn authenticate(
+        &self,
+        username: &Username,
+        key: &str,
+    ) -> Result<Username, KeyValidationError> {
+        (**self).authenticate(username, key).await
+    }
 }
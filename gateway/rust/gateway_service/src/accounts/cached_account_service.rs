@@ -0,0 +1,121 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+
+use crate::Username;
+
+use super::{account_service::KeyValidationError, AccountService};
+
+/// Remembers which keys the inner account service accepted or rejected, so a request that checks
+/// the same key more than once only asks the inner account service once. Errors other than an
+/// invalid key aren't remembered.
+pub struct CachedAccountService<TAccountService> {
+    inner: TAccountService,
+    /// The user each accepted key belongs to, or `None` for rejected keys, by username and key
+    results: Mutex<HashMap<(String, String), Option<Username>>>,
+}
+
+impl<TAccountService> CachedAccountService<TAccountService> {
+    pub fn new(inner: TAccountService) -> Self {
+        Self {
+            inner,
+            results: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl<TAccountService> AccountService for CachedAccountService<TAccountService>
+where
+    TAccountService: AccountService + Send + Sync,
+{
+    async fn verify_user_key(
+        &self,
+        username: &Username,
+        api_key: &str,
+    ) -> Result<(), KeyValidationError> {
+        self.authenticate(username, api_key).await.map(|_| ())
+    }
+
+    async fn authenticate(
+        &self,
+        username: &Username,
+        api_key: &str,
+    ) -> Result<Username, KeyValidationError> {
+        let key = (username.to_string(), api_key.to_string());
+
+        let cached = self
+            .results
+            .lock()
+            .expect("cache was poisoned")
+            .get(&key)
+            .cloned();
+
+        let result = match cached {
+            Some(Some(user)) => return Ok(user),
+            Some(None) => return Err(KeyValidationError::Invalid),
+            None => self.inner.authenticate(username, api_key).await,
+        };
+
+        let remembered = match &result {
+            Ok(user) => Some(user.clone()),
+            Err(KeyValidationError::Invalid) => None,
+            Err(KeyValidationError::Unknown(_)) => return result,
+        };
+        self.results
+            .lock()
+            .expect("cache was poisoned")
+            .insert(key, remembered);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mocks::MockAccountService;
+
+    use crate::{AccountService, CachedAccountService, KeyValidationError, Username};
+
+    #[tokio::test]
+    async fn asks_the_inner_account_service_once_per_key() {
+        let mut inner = MockAccountService::new();
+        inner
+            .expect_verify_user_key()
+            .times(2)
+            .returning(|_, api_key| match api_key {
+                "key1" => Ok(()),
+                _ => Err(KeyValidationError::Invalid),
+            });
+
+        let service = CachedAccountService::new(inner);
+        let user1: Username = "user1".parse().unwrap();
+
+        for _ in 0..2 {
+            assert!(service.verify_user_key(&user1, "key1").await.is_ok());
+            assert!(matches!(
+                service.verify_user_key(&user1, "key2").await,
+                Err(KeyValidationError::Invalid)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn asks_again_after_an_unknown_error() {
+        let mut inner = MockAccountService::new();
+        inner
+            .expect_verify_user_key()
+            .times(2)
+            .returning(|_, _| Err(KeyValidationError::Unknown("Timeout".into())));
+
+        let service = CachedAccountService::new(inner);
+        let user1: Username = "user1".parse().unwrap();
+
+        for _ in 0..2 {
+            assert!(matches!(
+                service.verify_user_key(&user1, "key1").await,
+                Err(KeyValidationError::Unknown(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+
+use crate::Username;
+
+use super::{account_service::KeyValidationError, AccountService};
+
+/// Accepts every key the inner account service accepts, and asks the fallback about the others,
+/// if there is one.
+pub struct FallbackAccountService<TAccountService, TFallback> {
+    inner: TAccountService,
+    fallback: Option<TFallback>,
+}
+
+impl<TAccountService, TFallback> FallbackAccountService<TAccountService, TFallback> {
+    pub fn new(inner: TAccountService, fallback: Option<TFallback>) -> Self {
+        Self { inner, fallback }
+    }
+}
+
+#[async_trait]
+impl<TAccountService, TFallback> AccountService
+    for FallbackAccountService<TAccountService, TFallback>
+where
+    TAccountService: AccountService + Send + Sync,
+    TFallback: AccountService + Send + Sync,
+{
+    async fn verify_user_key(
+        &self,
+        username: &Username,
+        api_key: &str,
+    ) -> Result<(), KeyValidationError> {
+        match (
+            self.inner.verify_user_key(username, api_key).await,
+            &self.fallback,
+        ) {
+            (Err(KeyValidationError::Invalid), Some(fallback)) => {
+                fallback.verify_user_key(username, api_key).await
+            }
+            (result, _) => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AccountService, FallbackAccountService, KeyValidationError, SingleAccountService, Username,
+    };
+
+    fn single(username: &str, api_key: &str) -> SingleAccountService {
+        SingleAccountService::new(username.parse().unwrap(), api_key.into())
+    }
+
+    #[tokio::test]
+    async fn asks_the_fallback_about_other_keys() {
+        let service =
+            FallbackAccountService::new(single("polywrap", "key1"), Some(single("user1", "key2")));
+        let verify = |username: &str, api_key: &'static str| {
+            let username: Username = username.parse().unwrap();
+            let service = &service;
+            async move { service.verify_user_key(&username, api_key).await }
+        };
+
+        assert!(verify("polywrap", "key1").await.is_ok());
+        assert!(verify("user1", "key2").await.is_ok());
+        assert!(matches!(
+            verify("user1", "key1").await,
+            Err(KeyValidationError::Invalid)
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_accepts_the_inner_keys_without_fallback() {
+        let service = FallbackAccountService::<_, SingleAccountService>::new(
+            single("polywrap", "key1"),
+            None,
+        );
+
+        assert!(service
+            .verify_user_key(&"polywrap".parse().unwrap(), "key1")
+            .await
+            .is_ok());
+        assert!(matches!(
+            service
+                .verify_user_key(&"user1".parse().unwrap(), "key2")
+                .await,
+            Err(KeyValidationError::Invalid)
+        ));
+    }
+}
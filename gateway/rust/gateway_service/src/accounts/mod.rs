@@ -4,8 +4,19 @@ pub use account_service::*;
 mod single_account_service;
 pub use single_account_service::SingleAccountService;
 
+#[cfg(feature = "local")]
 mod allow_all_account_service;
+#[cfg(feature = "local")]
 pub use allow_all_account_service::AllowAllAccountService;
 
 mod remote_account_service;
 pub use remote_account_service::RemoteAccountService;
+
+mod org_account_service;
+pub use org_account_service::OrgAccountService;
+
+mod fallback_account_service;
+pub use fallback_account_service::FallbackAccountService;
+
+mod cached_account_service;
+pub use cached_account_service::CachedAccountService;
@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+
+use crate::{
+    models::{OrgRole, Organization},
+    Repository, RepositoryError, Username,
+};
+
+use super::{account_service::KeyValidationError, AccountService};
+
+/// Accepts every key the inner account service accepts, and for the namespace of an organization
+/// also the own keys of its members with at least `role`.
+pub struct OrgAccountService<TAccountService, TOrgRepo> {
+    inner: TAccountService,
+    org_repo: TOrgRepo,
+    role: OrgRole,
+}
+
+impl<TAccountService, TOrgRepo> OrgAccountService<TAccountService, TOrgRepo> {
+    pub fn new(inner: TAccountService, org_repo: TOrgRepo, role: OrgRole) -> Self {
+        Self {
+            inner,
+            org_repo,
+            role,
+        }
+    }
+}
+
+#[async_trait]
+impl<TAccountService, TOrgRepo> AccountService for OrgAccountService<TAccountService, TOrgRepo>
+where
+    TAccountService: AccountService + Send + Sync,
+    TOrgRepo: Repository<Organization> + Send + Sync,
+{
+    async fn verify_user_key(
+        &self,
+        username: &Username,
+        api_key: &str,
+    ) -> Result<(), KeyValidationError> {
+        self.authenticate(username, api_key).await.map(|_| ())
+    }
+
+    async fn authenticate(
+        &self,
+        username: &Username,
+        api_key: &str,
+    ) -> Result<Username, KeyValidationError> {
+        match self.inner.verify_user_key(username, api_key).await {
+            Err(KeyValidationError::Invalid) => {}
+            result => return result.map(|_| username.clone()),
+        }
+
+        let organization = match self.org_repo.read(&Organization::key(username)).await {
+            // Keys are lowercase, an organization only acts for the namespace with its casing
+            Ok(organization) if organization.name == *username => organization,
+            Ok(_) | Err(RepositoryError::NotFound) => return Err(KeyValidationError::Invalid),
+            Err(e) => return Err(KeyValidationError::Unknown(e.to_string())),
+        };
+
+        // Keys don't say whose they are, so every member who may act is tried
+        for member in organization.members.iter().filter(|m| m.role >= self.role) {
+            match self.inner.verify_user_key(&member.username, api_key).await {
+                Err(KeyValidationError::Invalid) => continue,
+                result => return result.map(|_| member.username.clone()),
+            }
+        }
+
+        Err(KeyValidationError::Invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use crate::{
+        mocks::{MockAccountService, MockOrgRepository},
+        models::{OrgMember, OrgRole, Organization},
+        AccountService, KeyValidationError, OrgAccountService, RepositoryError,
+        SingleAccountService, Username,
+    };
+
+    fn org_repo() -> MockOrgRepository {
+        let mut org_repo = MockOrgRepository::new();
+
+        org_repo
+            .expect_read()
+            .with(eq("org:org1".to_string()))
+            .returning(|_| {
+                let mut organization =
+                    Organization::new("org1".parse().unwrap(), "user1".parse().unwrap());
+                organization
+                    .members
+                    .push(OrgMember::new("user2".parse().unwrap(), OrgRole::Publisher));
+                Ok(organization)
+            });
+        org_repo
+            .expect_read()
+            .returning(|_| Err(RepositoryError::NotFound));
+
+        org_repo
+    }
+
+    fn account_service() -> MockAccountService {
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .returning(
+                |username, api_key| match (username.to_string().as_str(), api_key) {
+                    ("org1", "org_key") | ("user1", "key1") | ("user2", "key2") => Ok(()),
+                    _ => Err(KeyValidationError::Invalid),
+                },
+            );
+
+        account_service
+    }
+
+    #[tokio::test]
+    async fn accepts_members_with_sufficient_role() {
+        let service = OrgAccountService::new(account_service(), org_repo(), OrgRole::Publisher);
+        let org: Username = "org1".parse().unwrap();
+
+        assert!(service.verify_user_key(&org, "key1").await.is_ok());
+        assert!(service.verify_user_key(&org, "key2").await.is_ok());
+        assert!(service.verify_user_key(&org, "org_key").await.is_ok());
+        assert!(matches!(
+            service.verify_user_key(&org, "key3").await,
+            Err(KeyValidationError::Invalid)
+        ));
+    }
+
+    #[tokio::test]
+    async fn authenticates_as_the_member() {
+        let service = OrgAccountService::new(account_service(), org_repo(), OrgRole::Publisher);
+        let org: Username = "org1".parse().unwrap();
+
+        assert_eq!(
+            service.authenticate(&org, "key2").await.unwrap(),
+            "user2".parse::<Username>().unwrap()
+        );
+        assert_eq!(service.authenticate(&org, "org_key").await.unwrap(), org);
+    }
+
+    #[tokio::test]
+    async fn rejects_members_with_insufficient_role() {
+        let service = OrgAccountService::new(account_service(), org_repo(), OrgRole::Maintainer);
+        let org: Username = "org1".parse().unwrap();
+
+        assert!(service.verify_user_key(&org, "key1").await.is_ok());
+        assert!(matches!(
+            service.verify_user_key(&org, "key2").await,
+            Err(KeyValidationError::Invalid)
+        ));
+    }
+
+    #[tokio::test]
+    async fn keys_of_users_stay_their_own() {
+        let service = OrgAccountService::new(
+            SingleAccountService::new("user1".parse().unwrap(), "key1".into()),
+            org_repo(),
+            OrgRole::Publisher,
+        );
+
+        assert!(service
+            .verify_user_key(&"user1".parse().unwrap(), "key1")
+            .await
+            .is_ok());
+        assert!(matches!(
+            service
+                .verify_user_key(&"user3".parse().unwrap(), "key1")
+                .await,
+            Err(KeyValidationError::Invalid)
+        ));
+    }
+
+    #[tokio::test]
+    async fn members_only_act_for_the_name_with_the_org_casing() {
+        let service = OrgAccountService::new(account_service(), org_repo(), OrgRole::Publisher);
+
+        assert!(matches!(
+            service
+                .verify_user_key(&"Org1".parse().unwrap(), "key1")
+                .await,
+            Err(KeyValidationError::Invalid)
+        ));
+    }
+}
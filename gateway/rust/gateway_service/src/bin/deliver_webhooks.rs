@@ -0,0 +1,6 @@
+use lambda_runtime::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    gateway_service::deliver_webhooks().await
+}
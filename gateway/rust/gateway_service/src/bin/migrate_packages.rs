@@ -0,0 +1,45 @@
+use clap::Parser;
+
+/// Copies the packages table from the schema with a single item per package to the schema with
+/// an item per version, see `migrate_packages`
+#[derive(Debug, Parser)]
+struct Args {
+    /// The table with the old schema, keyed only by `id`
+    #[arg(long)]
+    from: String,
+    /// The table with the current schema, keyed by `id` and `item`
+    #[arg(long)]
+    to: String,
+    /// Another DynamoDB endpoint, e.g. `http://localhost:8000` for a local database
+    #[arg(long)]
+    endpoint_url: Option<String>,
+    /// Reads and converts every item without writing any
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    let config = aws_config::load_from_env().await;
+    let mut config = aws_sdk_dynamodb::config::Builder::from(&config);
+    if let Some(endpoint_url) = args.endpoint_url {
+        config = config.endpoint_url(endpoint_url);
+    }
+    let client = aws_sdk_dynamodb::Client::from_conf(config.build());
+
+    let report = gateway_service::migrate_packages(&client, &args.from, &args.to, args.dry_run)
+        .await
+        .expect("Failed to migrate packages");
+
+    println!(
+        "Migrated {} packages and {} other items{}",
+        report.packages,
+        report.entities,
+        if args.dry_run { " (dry run)" } else { "" }
+    );
+    for skipped in &report.skipped {
+        println!("Skipped {}", skipped);
+    }
+}
@@ -1,10 +1,50 @@
 pub const ENV_PACKAGES_TABLE: &str = "PACKAGES_TABLE";
+pub const ENV_LEGACY_PACKAGES_TABLE: &str = "LEGACY_PACKAGES_TABLE";
 pub const ENV_ACCOUNT_SERVICE_URL: &str = "ACCOUNT_SERVICE_URL";
 pub const ENV_WRAP_USER_KEY: &str = "WRAP_USER_KEY";
 pub const ENV_STAGE: &str = "DEPLOYMENT_STAGE";
+pub const ENV_KEY_ID_SECRET: &str = "KEY_ID_SECRET";
+#[cfg(not(feature = "local"))]
+pub const ENV_AUDIT_TABLE: &str = "AUDIT_TABLE";
+pub const ENV_WEBHOOK_QUEUE_URL: &str = "WEBHOOK_QUEUE_URL";
+#[cfg(feature = "local")]
+pub const ENV_STORAGE: &str = "STORAGE";
+#[cfg(feature = "local")]
+pub const IN_MEMORY_STORAGE: &str = "memory";
+pub const ENV_UNPUBLISH_WINDOW_HOURS: &str = "UNPUBLISH_WINDOW_HOURS";
+pub const DEFAULT_UNPUBLISH_WINDOW_HOURS: u64 = 72;
+pub const ENV_IDEMPOTENCY_TTL_HOURS: &str = "IDEMPOTENCY_TTL_HOURS";
+pub const DEFAULT_IDEMPOTENCY_TTL_HOURS: u64 = 24;
+pub const ENV_ALLOWED_URI_AUTHORITIES: &str = "ALLOWED_URI_AUTHORITIES";
+pub const ENV_RESERVED_NAMES: &str = "RESERVED_NAMES";
+pub const DEFAULT_RESERVED_NAMES: &str = "polywrap,admin,wrapscan";
+pub const ENV_RESERVED_NAMES_ALLOWLIST: &str = "RESERVED_NAMES_ALLOWLIST";
+pub const DEFAULT_RESERVED_NAMES_ALLOWLIST: &str = "polywrap";
+pub const ENV_VERIFY_WRAP_MANIFESTS: &str = "VERIFY_WRAP_MANIFESTS";
+pub const ENV_IPFS_GATEWAY_URL: &str = "IPFS_GATEWAY_URL";
+pub const DEFAULT_IPFS_GATEWAY_URL: &str = "https://ipfs.wrappers.io";
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const WRAP_URI_HEADER: &str = "x-wrap-uri";
+pub const CANONICAL_ID_HEADER: &str = "x-wrap-canonical-id";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-wrap-signature";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-wrap-timestamp";
 pub const PACKAGES_TABLE_KEY_NAME: &str = "id";
+pub const PACKAGES_TABLE_SORT_KEY_NAME: &str = "item";
+pub const HEADER_ITEM: &str = "#header";
+pub const VERSION_ITEM_PREFIX: &str = "version#";
+pub const PACKAGES_TABLE_VERSION_NAME: &str = "version_name";
+pub const VERSION_NAME_INDEX: &str = "version_name_index";
+pub const PACKAGES_TABLE_REVISION_NAME: &str = "revision";
+pub const PACKAGES_TABLE_TTL_NAME: &str = "expires_at";
+pub const AUDIT_TABLE_KEY_NAME: &str = "package_id";
+pub const AUDIT_TABLE_SORT_KEY_NAME: &str = "entry_id";
+pub const AUDIT_TABLE_PENDING_NAME: &str = "pending";
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
 pub const POLYWRAP_USERNAME: &str = "polywrap";
 #[cfg(feature = "local")]
-pub const PACKAGES_TABLE_LOCAL: &str = "wraps-table-dev";
+pub const PACKAGES_TABLE_LOCAL: &str = "wraps-table-v2-dev";
+#[cfg(feature = "local")]
+pub const KEY_ID_SECRET_LOCAL: &str = "local-key-id-secret";
+#[cfg(feature = "local")]
+pub const AUDIT_TABLE_LOCAL: &str = "wraps-audit-table-dev";
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::models::AuditEntry;
+
+use super::RepositoryError;
+
+/// A page of the audit entries of a package, oldest first.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    /// The entry id to read the next page after, if there are more entries
+    pub next: Option<String>,
+}
+
+impl AuditPage {
+    /// The page of up to `limit` of all `entries` of a package, after the entry id `after`.
+    /// `entries` are sorted by entry id
+    pub fn of(entries: &[AuditEntry], after: Option<&str>, limit: usize) -> Self {
+        let start = match after {
+            Some(after) => entries.partition_point(|e| e.entry_id().as_str() <= after),
+            None => 0,
+        };
+        let end = entries.len().min(start + limit);
+        let page = entries[start..end].to_vec();
+
+        let next = match end < entries.len() {
+            true => page.last().map(|e| e.entry_id()),
+            false => None,
+        };
+
+        Self {
+            entries: page,
+            next,
+        }
+    }
+}
+
+/// Stores audit entries, which can only be appended. A pending entry can be committed, or removed
+/// if its change wasn't made.
+#[async_trait]
+pub trait AuditRepository {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), RepositoryError>;
+    /// Replaces the pending entry with the same id with `entry`, which isn't pending
+    async fn commit(&self, entry: &AuditEntry) -> Result<(), RepositoryError>;
+    /// Removes the pending entry with the id of `entry`, committed entries are never removed
+    async fn remove_pending(&self, entry: &AuditEntry) -> Result<(), RepositoryError>;
+    /// Lists the entries of a package, oldest first
+    async fn list(&self, package_id: &str) -> Result<Vec<AuditEntry>, RepositoryError>;
+    /// Lists up to `limit` entries of a package after the entry id `after`, oldest first
+    async fn list_page(
+        &self,
+        package_id: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<AuditPage, RepositoryError>;
+}
@@ -1,74 +1,315 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 
 use aws_sdk_dynamodb::error::SdkError;
 use aws_sdk_dynamodb::operation::get_item::{GetItemError, GetItemOutput};
-use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::operation::transact_write_items::TransactWriteItemsError;
+use aws_sdk_dynamodb::types::{AttributeValue, Put, TransactWriteItem};
 use aws_sdk_dynamodb::Client;
+use serde::{de::DeserializeOwned, Serialize};
 
-use crate::models::Package;
+use crate::models::{IdempotencyRecord, Organization, PublisherKeys, WebhookSubscriptions};
 use crate::{constants, debug, Repository, RepositoryError};
 
+/// An entity stored as JSON in the `object` attribute of a single item, keyed by its id.
+/// Packages are stored differently, see `dynamodb_package_repository`
+pub trait StoredEntity: Serialize + DeserializeOwned + Clone + Send + Sync {
+    fn id(&self) -> &str;
+    fn revision(&self) -> u64;
+    fn set_revision(&mut self, revision: u64);
+
+    /// Seconds since the epoch after which DynamoDB deletes the entity
+    fn expires_at(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl StoredEntity for WebhookSubscriptions {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn set_revision(&mut self, revision: u64) {
+        self.revision = revision;
+    }
+}
+
+impl StoredEntity for IdempotencyRecord {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn set_revision(&mut self, revision: u64) {
+        self.revision = revision;
+    }
+
+    fn expires_at(&self) -> Option<u64> {
+        Some(self.expires_at)
+    }
+}
+
+impl StoredEntity for PublisherKeys {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn set_revision(&mut self, revision: u64) {
+        self.revision = revision;
+    }
+}
+
+impl StoredEntity for Organization {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn set_revision(&mut self, revision: u64) {
+        self.revision = revision;
+    }
+}
+
 #[derive(Clone)]
-pub struct PackageRepository {
-    client: Client,
-    table_name: String,
+pub struct DynamoDbRepository {
+    pub(super) client: Client,
+    pub(super) table_name: String,
+    /// The table with the old schema, see `with_legacy_table`
+    pub(super) legacy_table_name: Option<String>,
 }
 
-impl PackageRepository {
+impl DynamoDbRepository {
     pub fn new(client: Client, table_name: String) -> Self {
-        Self { client, table_name }
+        Self {
+            client,
+            table_name,
+            legacy_table_name: None,
+        }
     }
-}
 
-#[async_trait]
-impl Repository<Package> for PackageRepository {
-    async fn read(&self, key: &str) -> Result<Package, RepositoryError> {
+    /// Reads entities and packages that aren't in the table yet from the table with the old
+    /// schema, where each of them is a single `object` item keyed only by `id`. Only reads fall
+    /// back to it: what is read from it is a new entity of the table, written there (with all the
+    /// versions of a package) the first time it changes, until `migrate_packages` copied it.
+    pub fn with_legacy_table(mut self, legacy_table_name: String) -> Self {
+        self.legacy_table_name = Some(legacy_table_name);
+        self
+    }
+
+    /// The item of `id` in the legacy table, `NotFound` if there is no legacy table
+    pub(super) async fn read_legacy_item(
+        &self,
+        id: &str,
+    ) -> Result<HashMap<String, AttributeValue>, RepositoryError> {
+        let legacy_table_name = self
+            .legacy_table_name
+            .as_ref()
+            .ok_or(RepositoryError::NotFound)?;
+
         let response = self
             .client
             .get_item()
-            .table_name(&self.table_name)
+            .table_name(legacy_table_name)
             .key(
                 constants::PACKAGES_TABLE_KEY_NAME,
-                AttributeValue::S(key.to_string()),
+                AttributeValue::S(id.to_string()),
             )
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+        debug!(&response);
+
+        response.item.ok_or(RepositoryError::NotFound)
+    }
+
+    /// The conditional put of `entity` in a transaction, failing if it changed since it was read
+    pub(super) fn put_entity(
+        &self,
+        entity: &impl StoredEntity,
+    ) -> Result<TransactWriteItem, RepositoryError> {
+        let (condition, values) = revision_condition(entity.revision());
+
+        let put = Put::builder()
+            .table_name(&self.table_name)
+            .set_item(Some(to_item(entity)?))
+            .condition_expression(condition)
+            .expression_attribute_names(REVISION_NAME, constants::PACKAGES_TABLE_REVISION_NAME)
+            .set_expression_attribute_values(values)
+            .build();
+
+        Ok(TransactWriteItem::builder().put(put).build())
+    }
+}
+
+#[async_trait]
+impl<T: StoredEntity> Repository<T> for DynamoDbRepository {
+    async fn read(&self, key: &str) -> Result<T, RepositoryError> {
+        let response = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .set_key(Some(item_key(key, constants::HEADER_ITEM)))
             .send()
             .await
             .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
         debug!(&response);
 
-        let item = response.item.ok_or(RepositoryError::NotFound)?;
-        let package_json = item
+        let (item, legacy) = match response.item {
+            Some(item) => (item, false),
+            None => (self.read_legacy_item(key).await?, true),
+        };
+        let entity_json = item
             .get("object")
             .and_then(|v| v.as_s().ok())
-            .ok_or(RepositoryError::NotFound)?;
+            .ok_or_else(|| RepositoryError::Unknown(format!("`{}` has no object", key)))?;
 
-        let package: Package =
-            serde_json::from_str(package_json).map_err(|_| RepositoryError::NotFound)?;
+        let mut entity: T = serde_json::from_str(entity_json)
+            .map_err(|e| RepositoryError::Unknown(e.to_string()))?;
+        // Not in the table yet, so it's written as a new entity
+        if legacy {
+            entity.set_revision(0);
+        }
 
-        Ok(package)
+        Ok(entity)
     }
 
-    async fn update(&self, entity: &Package) -> Result<(), RepositoryError> {
-        let item = serde_json::to_string(entity)
-            .map_err(|_| RepositoryError::Unknown("Failed to serialize package".to_string()))?;
-        debug!(&item);
+    async fn update(&self, entity: &T) -> Result<(), RepositoryError> {
+        let (condition, values) = revision_condition(entity.revision());
 
         self.client
             .put_item()
             .table_name(&self.table_name)
-            .item(
-                constants::PACKAGES_TABLE_KEY_NAME,
-                AttributeValue::S(entity.id.clone()),
-            )
-            .item("object", AttributeValue::S(item))
+            .set_item(Some(to_item(entity)?))
+            .condition_expression(condition)
+            .expression_attribute_names(REVISION_NAME, constants::PACKAGES_TABLE_REVISION_NAME)
+            .set_expression_attribute_values(values)
             .send()
             .await
-            .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+            .map_err(|error| match &error {
+                SdkError::ServiceError(e)
+                    if matches!(e.err(), PutItemError::ConditionalCheckFailedException(_)) =>
+                {
+                    RepositoryError::Conflict
+                }
+                _ => RepositoryError::Unknown(error.to_string()),
+            })?;
+
+        Ok(())
+    }
+
+    async fn update_many(&self, entities: &[T]) -> Result<(), RepositoryError> {
+        let mut items = vec![];
+        for entity in entities {
+            items.push(self.put_entity(entity)?);
+        }
+
+        self.client
+            .transact_write_items()
+            .set_transact_items(Some(items))
+            .send()
+            .await
+            .map_err(|error| match &error {
+                SdkError::ServiceError(e) if is_conditional_check_failure(e.err()) => {
+                    RepositoryError::Conflict
+                }
+                _ => RepositoryError::Unknown(error.to_string()),
+            })?;
 
         Ok(())
     }
 }
 
+pub(super) fn is_conditional_check_failure(error: &TransactWriteItemsError) -> bool {
+    match error {
+        TransactWriteItemsError::TransactionCanceledException(e) => e
+            .cancellation_reasons()
+            .unwrap_or_default()
+            .iter()
+            .any(|reason| reason.code() == Some("ConditionalCheckFailed")),
+        _ => false,
+    }
+}
+
+pub(super) const REVISION_NAME: &str = "#revision";
+const REVISION_VALUE: &str = ":revision";
+
+/// The key of an item of the table: the id of its entity or package, and which item it is
+pub(super) fn item_key(id: &str, item: &str) -> HashMap<String, AttributeValue> {
+    HashMap::from([
+        (
+            constants::PACKAGES_TABLE_KEY_NAME.to_string(),
+            AttributeValue::S(id.to_string()),
+        ),
+        (
+            constants::PACKAGES_TABLE_SORT_KEY_NAME.to_string(),
+            AttributeValue::S(item.to_string()),
+        ),
+    ])
+}
+
+/// Serializes the entity as it will be stored, with its revision incremented
+fn to_item(entity: &impl StoredEntity) -> Result<HashMap<String, AttributeValue>, RepositoryError> {
+    let mut entity = entity.clone();
+    entity.set_revision(entity.revision() + 1);
+
+    let item = serde_json::to_string(&entity)
+        .map_err(|_| RepositoryError::Unknown("Failed to serialize entity".to_string()))?;
+    debug!(&item);
+
+    let mut item = HashMap::from([
+        ("object".to_string(), AttributeValue::S(item)),
+        (
+            constants::PACKAGES_TABLE_REVISION_NAME.to_string(),
+            AttributeValue::N(entity.revision().to_string()),
+        ),
+    ]);
+    item.extend(item_key(entity.id(), constants::HEADER_ITEM));
+
+    if let Some(expires_at) = entity.expires_at() {
+        item.insert(
+            constants::PACKAGES_TABLE_TTL_NAME.to_string(),
+            AttributeValue::N(expires_at.to_string()),
+        );
+    }
+
+    Ok(item)
+}
+
+/// Only writes if the stored revision is still the one that was read.
+/// Entities that were never written, or written before revisions existed, have no revision.
+pub(super) fn revision_condition(
+    revision: u64,
+) -> (String, Option<HashMap<String, AttributeValue>>) {
+    if revision == 0 {
+        return (format!("attribute_not_exists({})", REVISION_NAME), None);
+    }
+
+    (
+        format!("{} = {}", REVISION_NAME, REVISION_VALUE),
+        Some(HashMap::from([(
+            REVISION_VALUE.to_string(),
+            AttributeValue::N(revision.to_string()),
+        )])),
+    )
+}
+
 #[async_trait]
 pub trait DynamoDbClient {
     async fn get_item(
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::Client;
+
+use crate::models::AuditEntry;
+use crate::{constants, debug, AuditPage, AuditRepository, RepositoryError};
+
+const KEY_NAME: &str = "#package_id";
+const KEY_VALUE: &str = ":package_id";
+const PENDING_NAME: &str = "#pending";
+const PENDING_VALUE: &str = ":pending";
+/// Only pending entries can be replaced or removed
+const PENDING_CONDITION: &str = "#pending = :pending";
+
+/// Entries are stored in their own table, keyed by package and sorted by entry id.
+/// Only this repository can append entries, the packages table never stores them.
+/// Entries written before they could be pending have no `pending` attribute, so they can't be
+/// replaced or removed either.
+#[derive(Clone)]
+pub struct DynamoDbAuditRepository {
+    client: Client,
+    table_name: String,
+}
+
+impl DynamoDbAuditRepository {
+    pub fn new(client: Client, table_name: String) -> Self {
+        Self { client, table_name }
+    }
+
+    /// Writes `entry` as a new entry, or over the pending entry with its id
+    async fn put_entry(
+        &self,
+        entry: &AuditEntry,
+        replace_pending: bool,
+    ) -> Result<(), RepositoryError> {
+        let item = serde_json::to_string(entry)
+            .map_err(|_| RepositoryError::Unknown("Failed to serialize entry".to_string()))?;
+        debug!(&item);
+
+        let mut put = self
+            .client
+            .put_item()
+            .table_name(&self.table_name)
+            .item(
+                constants::AUDIT_TABLE_KEY_NAME,
+                AttributeValue::S(entry.package_id.clone()),
+            )
+            .item(
+                constants::AUDIT_TABLE_SORT_KEY_NAME,
+                AttributeValue::S(entry.entry_id()),
+            )
+            .item("object", AttributeValue::S(item))
+            .item(
+                constants::AUDIT_TABLE_PENDING_NAME,
+                AttributeValue::Bool(entry.pending),
+            );
+
+        put = match replace_pending {
+            true => put
+                .condition_expression(PENDING_CONDITION)
+                .expression_attribute_names(PENDING_NAME, constants::AUDIT_TABLE_PENDING_NAME)
+                .expression_attribute_values(PENDING_VALUE, AttributeValue::Bool(true)),
+            false => put.condition_expression(format!(
+                "attribute_not_exists({})",
+                constants::AUDIT_TABLE_SORT_KEY_NAME
+            )),
+        };
+
+        put.send()
+            .await
+            .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditRepository for DynamoDbAuditRepository {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), RepositoryError> {
+        // Existing entries are never overwritten
+        self.put_entry(entry, false).await
+    }
+
+    async fn commit(&self, entry: &AuditEntry) -> Result<(), RepositoryError> {
+        self.put_entry(entry, true).await
+    }
+
+    async fn remove_pending(&self, entry: &AuditEntry) -> Result<(), RepositoryError> {
+        self.client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key(
+                constants::AUDIT_TABLE_KEY_NAME,
+                AttributeValue::S(entry.package_id.clone()),
+            )
+            .key(
+                constants::AUDIT_TABLE_SORT_KEY_NAME,
+                AttributeValue::S(entry.entry_id()),
+            )
+            .condition_expression(PENDING_CONDITION)
+            .expression_attribute_names(PENDING_NAME, constants::AUDIT_TABLE_PENDING_NAME)
+            .expression_attribute_values(PENDING_VALUE, AttributeValue::Bool(true))
+            .send()
+            .await
+            .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn list(&self, package_id: &str) -> Result<Vec<AuditEntry>, RepositoryError> {
+        let mut entries = vec![];
+        let mut start_key: Option<HashMap<String, AttributeValue>> = None;
+
+        loop {
+            let response = self
+                .client
+                .query()
+                .table_name(&self.table_name)
+                .key_condition_expression(format!("{} = {}", KEY_NAME, KEY_VALUE))
+                .expression_attribute_names(KEY_NAME, constants::AUDIT_TABLE_KEY_NAME)
+                .expression_attribute_values(KEY_VALUE, AttributeValue::S(package_id.to_string()))
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+
+            for item in response.items().unwrap_or_default() {
+                entries.push(entry_from_item(item)?);
+            }
+
+            start_key = response.last_evaluated_key().cloned();
+            if start_key.is_none() {
+                return Ok(entries);
+            }
+        }
+    }
+
+    async fn list_page(
+        &self,
+        package_id: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<AuditPage, RepositoryError> {
+        let mut entries = vec![];
+        let mut start_key = after.map(|after| {
+            HashMap::from([
+                (
+                    constants::AUDIT_TABLE_KEY_NAME.to_string(),
+                    AttributeValue::S(package_id.to_string()),
+                ),
+                (
+                    constants::AUDIT_TABLE_SORT_KEY_NAME.to_string(),
+                    AttributeValue::S(after.to_string()),
+                ),
+            ])
+        });
+
+        // One more entry than the page tells whether there is a next page
+        while entries.len() <= limit {
+            let response = self
+                .client
+                .query()
+                .table_name(&self.table_name)
+                .key_condition_expression(format!("{} = {}", KEY_NAME, KEY_VALUE))
+                .expression_attribute_names(KEY_NAME, constants::AUDIT_TABLE_KEY_NAME)
+                .expression_attribute_values(KEY_VALUE, AttributeValue::S(package_id.to_string()))
+                .set_exclusive_start_key(start_key)
+                .limit((limit + 1 - entries.len()) as i32)
+                .send()
+                .await
+                .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+
+            for item in response.items().unwrap_or_default() {
+                entries.push(entry_from_item(item)?);
+            }
+
+            start_key = response.last_evaluated_key().cloned();
+            if start_key.is_none() {
+                break;
+            }
+        }
+
+        Ok(AuditPage::of(&entries, None, limit))
+    }
+}
+
+fn entry_from_item(item: &HashMap<String, AttributeValue>) -> Result<AuditEntry, RepositoryError> {
+    let entry_json = item
+        .get("object")
+        .and_then(|v| v.as_s().ok())
+        .ok_or_else(|| RepositoryError::Unknown("Malformed audit entry".to_string()))?;
+
+    serde_json::from_str(entry_json).map_err(|e| RepositoryError::Unknown(e.to_string()))
+}
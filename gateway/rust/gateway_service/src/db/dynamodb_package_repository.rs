@@ -0,0 +1,898 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::types::{AttributeValue, ConditionCheck, Delete, Put, TransactWriteItem};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::debugging::log_error;
+use crate::models::{Package, Version, VersionName, WebhookSubscriptions};
+use crate::{
+    constants, debug, semver, MoveRepository, Repository, RepositoryError, SemVer, VersionPage,
+    VersionRepository,
+};
+
+use super::dynamodb::{is_conditional_check_failure, item_key, revision_condition, REVISION_NAME};
+use super::DynamoDbRepository;
+
+/// DynamoDB allows at most 100 items in a transaction
+const MAX_TRANSACTION_ITEMS: usize = 100;
+const FIRST_POSITION_NAME: &str = "first_position";
+const NEXT_POSITION_NAME: &str = "next_position";
+const HASH_NAME: &str = "hash";
+const KEY_NAME: &str = "#id";
+const KEY_VALUE: &str = ":id";
+const SORT_KEY_NAME: &str = "#item";
+const FIRST_ITEM_VALUE: &str = ":first";
+const LAST_ITEM_VALUE: &str = ":last";
+const VERSION_NAME: &str = "#version_name";
+const VERSION_VALUE: &str = ":version_name";
+const HASH_ALIAS: &str = "#hash";
+
+struct PackageHeader {
+    /// The package without its versions
+    package: Package,
+    /// The versions of the package are the version items at these positions. Items after them
+    /// were left behind by updates that failed, items before them by removals that failed
+    positions: Range<u64>,
+}
+
+/// A version item of a package
+struct StoredVersion {
+    position: u64,
+    version: Version,
+}
+
+/// Where a version is stored, and a hash of it to tell whether it changed
+struct VersionRef {
+    /// Without build metadata, see `version_index_key`
+    name: VersionName,
+    position: u64,
+    hash: String,
+}
+
+/// A package is stored as a header item, with the package without its versions, and an item per
+/// version, all under the package id. Version items are keyed by the position they were added at,
+/// so they're read in the order they were published, and found by name through the
+/// `version_name_index`. Only the positions the header covers belong to the package, so version
+/// items left behind by a failed update are never read, and are overwritten by the next one.
+#[async_trait]
+impl Repository<Package> for DynamoDbRepository {
+    async fn read(&self, key: &str) -> Result<Package, RepositoryError> {
+        let header = match self.get_header(key).await {
+            // Read from the legacy table with all its versions, until it's written to this table
+            Err(RepositoryError::NotFound) => return self.read_legacy_package(key).await,
+            header => header?,
+        };
+
+        let mut package = header.package;
+        package.versions = self
+            .query_versions(key, header.positions, None)
+            .await?
+            .into_iter()
+            .map(|stored| stored.version)
+            .collect();
+        semver::sort_versions(&mut package.versions);
+
+        Ok(package)
+    }
+
+    async fn update(&self, entity: &Package) -> Result<(), RepositoryError> {
+        self.update_many(std::slice::from_ref(entity)).await
+    }
+
+    async fn update_many(&self, entities: &[Package]) -> Result<(), RepositoryError> {
+        self.write_packages(entities, vec![]).await
+    }
+}
+
+#[async_trait]
+impl MoveRepository for DynamoDbRepository {
+    async fn update_with_subscriptions(
+        &self,
+        packages: &[Package],
+        subscriptions: &[WebhookSubscriptions],
+    ) -> Result<(), RepositoryError> {
+        let mut items = vec![];
+        for entity in subscriptions {
+            items.push(self.put_entity(entity)?);
+        }
+
+        self.write_packages(packages, items).await
+    }
+}
+
+impl DynamoDbRepository {
+    /// Writes the packages and the `required` items in a single transaction
+    async fn write_packages(
+        &self,
+        entities: &[Package],
+        mut required: Vec<TransactWriteItem>,
+    ) -> Result<(), RepositoryError> {
+        // Headers and changed versions have to be written together
+        let mut new_versions = vec![];
+        let mut removed_versions = vec![];
+
+        for package in entities {
+            // The revision of the stored header, 0 if it has to be written as a new header
+            let (header_revision, positions, stored_versions) = match package.revision {
+                0 => (0, 0..0, vec![]),
+                revision => match self.get_header(&package.id).await {
+                    Ok(header) if header.package.revision == revision => {
+                        let stored = self
+                            .query_version_refs(&package.id, header.positions.clone())
+                            .await?;
+                        (revision, header.positions, stored)
+                    }
+                    Ok(_) | Err(RepositoryError::NotFound) => {
+                        return Err(RepositoryError::Conflict)
+                    }
+                    Err(e) => return Err(e),
+                },
+            };
+
+            let mut next_position = positions.end;
+            let mut names = vec![];
+            for version in &package.versions {
+                match find_stored(&stored_versions, &version.name) {
+                    Some(stored) => {
+                        let (hash, item) = to_version_item(&package.id, stored.position, version)?;
+                        if hash != stored.hash {
+                            required.push(put(&self.table_name, item));
+                        }
+                    }
+                    None => {
+                        let (_, item) = to_version_item(&package.id, next_position, version)?;
+                        next_position += 1;
+                        new_versions.push(NewVersion {
+                            package_id: &package.id,
+                            header_revision,
+                            item,
+                        });
+                    }
+                }
+                names.push(&version.name);
+            }
+
+            let removed = stored_versions
+                .iter()
+                .filter(|stored| !names.contains(&&stored.name))
+                .map(|stored| item_key(&package.id, &version_item(stored.position)))
+                .collect::<Vec<_>>();
+
+            // Once every stored version is removed, e.g. by a transfer, none of their positions
+            // belong to the package, so the ones that don't fit are deleted after the update.
+            // Otherwise they have to be deleted with it
+            let first_position = match !removed.is_empty() && removed.len() == stored_versions.len()
+            {
+                true => {
+                    removed_versions.extend(removed);
+                    positions.end
+                }
+                false => {
+                    required.extend(removed.into_iter().map(|key| delete(&self.table_name, key)));
+                    positions.start
+                }
+            };
+
+            required.push(put_header(
+                &self.table_name,
+                package,
+                first_position..next_position,
+                header_revision,
+            )?);
+        }
+
+        if required.len() > MAX_TRANSACTION_ITEMS {
+            return Err(RepositoryError::Unknown(
+                "Too many changed versions to update at once".to_string(),
+            ));
+        }
+
+        let mut items = required;
+        let overflowing_versions = split_off_overflow(&mut new_versions, items.len());
+        items.extend(
+            new_versions
+                .into_iter()
+                .map(|version| put(&self.table_name, version.item)),
+        );
+        let overflowing_removals = split_off_overflow(&mut removed_versions, items.len());
+        items.extend(
+            removed_versions
+                .into_iter()
+                .map(|key| delete(&self.table_name, key)),
+        );
+
+        self.put_overflowing_versions(overflowing_versions).await?;
+
+        self.transact(items).await?;
+
+        // Removed versions are no longer covered by the header, so failing to delete them only
+        // leaves them behind
+        for key in overflowing_removals {
+            let _ = self
+                .client
+                .delete_item()
+                .table_name(&self.table_name)
+                .set_key(Some(key))
+                .send()
+                .await
+                .map_err(log_error);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VersionRepository for DynamoDbRepository {
+    async fn read_header(&self, package_id: &str) -> Result<Package, RepositoryError> {
+        match self.get_header(package_id).await {
+            Err(RepositoryError::NotFound) => self.read_legacy_package(package_id).await,
+            header => Ok(header?.package),
+        }
+    }
+
+    async fn read_version(
+        &self,
+        package_id: &str,
+        version: &VersionName,
+    ) -> Result<Version, RepositoryError> {
+        let header = match self.get_header(package_id).await {
+            Err(RepositoryError::NotFound) => {
+                let package = self.read_legacy_package(package_id).await?;
+                return package
+                    .versions
+                    .into_iter()
+                    .find(|v| v.name == *version)
+                    .ok_or(RepositoryError::NotFound);
+            }
+            header => header?,
+        };
+
+        self.find_version(package_id, version, &header.positions)
+            .await?
+            .map(|stored| stored.version)
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn read_versions(
+        &self,
+        package_id: &str,
+        after: Option<VersionName>,
+        limit: usize,
+    ) -> Result<VersionPage, RepositoryError> {
+        let header = match self.get_header(package_id).await {
+            Err(RepositoryError::NotFound) => {
+                let package = self.read_legacy_package(package_id).await?;
+                return VersionPage::of(&package.versions, after, limit);
+            }
+            header => header?,
+        };
+
+        let start = match after {
+            Some(after) => {
+                self.find_version(package_id, &after, &header.positions)
+                    .await?
+                    .ok_or(RepositoryError::NotFound)?
+                    .position
+                    + 1
+            }
+            None => header.positions.start,
+        };
+
+        // One more version than the page tells whether there is a next page
+        let mut versions = self
+            .query_versions(package_id, start..header.positions.end, Some(limit + 1))
+            .await?
+            .into_iter()
+            .map(|stored| stored.version)
+            .collect::<Vec<_>>();
+
+        let next = match versions.len() > limit {
+            true => {
+                versions.truncate(limit);
+                versions.last().map(|v| v.name.clone())
+            }
+            false => None,
+        };
+
+        Ok(VersionPage { versions, next })
+    }
+
+    async fn update_version(
+        &self,
+        package: &Package,
+        version: &Version,
+    ) -> Result<(), RepositoryError> {
+        // A new package is written with all its versions
+        if package.revision == 0 {
+            let mut package = package.clone();
+            match package.versions.iter_mut().find(|v| v.name == version.name) {
+                Some(stored) => *stored = version.clone(),
+                None => package.versions.push(version.clone()),
+            }
+
+            return self.write_packages(&[package], vec![]).await;
+        }
+
+        let header = match self.get_header(&package.id).await {
+            Ok(header) if header.package.revision == package.revision => header,
+            Ok(_) | Err(RepositoryError::NotFound) => return Err(RepositoryError::Conflict),
+            Err(e) => return Err(e),
+        };
+
+        let positions = header.positions;
+        let (position, positions) = match self
+            .find_version(&package.id, &version.name, &positions)
+            .await?
+        {
+            Some(stored) => (stored.position, positions),
+            None => (positions.end, positions.start..positions.end + 1),
+        };
+
+        let (_, item) = to_version_item(&package.id, position, version)?;
+
+        self.transact(vec![
+            put(&self.table_name, item),
+            put_header(&self.table_name, package, positions, package.revision)?,
+        ])
+        .await
+    }
+}
+
+impl DynamoDbRepository {
+    /// Writes the versions that don't fit into the transaction of an update before it. They're
+    /// after the positions the stored header covers, so they're only read once the update
+    /// succeeded. Each batch is only written if the header of its package is still the one that
+    /// was read, so it never overwrites versions of another update.
+    async fn put_overflowing_versions(
+        &self,
+        versions: Vec<NewVersion<'_>>,
+    ) -> Result<(), RepositoryError> {
+        for batch in batch_by_package(versions) {
+            let mut items = batch
+                .items
+                .into_iter()
+                .map(|item| put(&self.table_name, item))
+                .collect::<Vec<_>>();
+            items.push(check_header(
+                &self.table_name,
+                batch.package_id,
+                batch.header_revision,
+            ));
+
+            self.transact(items).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn transact(&self, items: Vec<TransactWriteItem>) -> Result<(), RepositoryError> {
+        self.client
+            .transact_write_items()
+            .set_transact_items(Some(items))
+            .send()
+            .await
+            .map_err(|error| match &error {
+                SdkError::ServiceError(e) if is_conditional_check_failure(e.err()) => {
+                    RepositoryError::Conflict
+                }
+                _ => RepositoryError::Unknown(error.to_string()),
+            })?;
+
+        Ok(())
+    }
+
+    /// A package that isn't in the table yet, written as a new package the first time it changes
+    async fn read_legacy_package(&self, package_id: &str) -> Result<Package, RepositoryError> {
+        let mut package: Package =
+            parse_object(&self.read_legacy_item(package_id).await?, "object")?;
+        package.revision = 0;
+
+        Ok(package)
+    }
+
+    async fn get_header(&self, package_id: &str) -> Result<PackageHeader, RepositoryError> {
+        let response = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .set_key(Some(item_key(package_id, constants::HEADER_ITEM)))
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+        debug!(&response);
+
+        let item = response.item.ok_or(RepositoryError::NotFound)?;
+
+        parse_header(&item)
+    }
+
+    /// Finds the version `name` among the version items at `positions`
+    async fn find_version(
+        &self,
+        package_id: &str,
+        name: &VersionName,
+        positions: &Range<u64>,
+    ) -> Result<Option<StoredVersion>, RepositoryError> {
+        let mut start_key: Option<HashMap<String, AttributeValue>> = None;
+
+        loop {
+            let response = self
+                .client
+                .query()
+                .table_name(&self.table_name)
+                .index_name(constants::VERSION_NAME_INDEX)
+                .key_condition_expression(format!(
+                    "{} = {} AND {} = {}",
+                    KEY_NAME, KEY_VALUE, VERSION_NAME, VERSION_VALUE
+                ))
+                .expression_attribute_names(KEY_NAME, constants::PACKAGES_TABLE_KEY_NAME)
+                .expression_attribute_names(VERSION_NAME, constants::PACKAGES_TABLE_VERSION_NAME)
+                .expression_attribute_values(KEY_VALUE, AttributeValue::S(package_id.to_string()))
+                .expression_attribute_values(
+                    VERSION_VALUE,
+                    AttributeValue::S(version_index_key(name)),
+                )
+                .consistent_read(true)
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+            debug!(&response);
+
+            // Items a failed update left behind may have the same name
+            for item in response.items().unwrap_or_default() {
+                let position = parse_position(item)?;
+                if positions.contains(&position) {
+                    return Ok(Some(StoredVersion {
+                        position,
+                        version: parse_object(item, "object")?,
+                    }));
+                }
+            }
+
+            start_key = response.last_evaluated_key().cloned();
+            if start_key.is_none() {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Reads up to `limit` versions at `positions`, in the order they were added
+    async fn query_versions(
+        &self,
+        package_id: &str,
+        positions: Range<u64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<StoredVersion>, RepositoryError> {
+        self.query_version_items(package_id, positions, limit, false)
+            .await?
+            .iter()
+            .map(|item| {
+                Ok(StoredVersion {
+                    position: parse_position(item)?,
+                    version: parse_object(item, "object")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Where the versions at `positions` are stored, without reading them
+    async fn query_version_refs(
+        &self,
+        package_id: &str,
+        positions: Range<u64>,
+    ) -> Result<Vec<VersionRef>, RepositoryError> {
+        self.query_version_items(package_id, positions, None, true)
+            .await?
+            .iter()
+            .map(parse_version_ref)
+            .collect()
+    }
+
+    async fn query_version_items(
+        &self,
+        package_id: &str,
+        positions: Range<u64>,
+        limit: Option<usize>,
+        refs_only: bool,
+    ) -> Result<Vec<HashMap<String, AttributeValue>>, RepositoryError> {
+        let mut items = vec![];
+        if positions.is_empty() {
+            return Ok(items);
+        }
+
+        let mut start_key: Option<HashMap<String, AttributeValue>> = None;
+
+        loop {
+            let mut query = self
+                .client
+                .query()
+                .table_name(&self.table_name)
+                .key_condition_expression(format!(
+                    "{} = {} AND {} BETWEEN {} AND {}",
+                    KEY_NAME, KEY_VALUE, SORT_KEY_NAME, FIRST_ITEM_VALUE, LAST_ITEM_VALUE
+                ))
+                .expression_attribute_names(KEY_NAME, constants::PACKAGES_TABLE_KEY_NAME)
+                .expression_attribute_names(SORT_KEY_NAME, constants::PACKAGES_TABLE_SORT_KEY_NAME)
+                .expression_attribute_values(KEY_VALUE, AttributeValue::S(package_id.to_string()))
+                .expression_attribute_values(
+                    FIRST_ITEM_VALUE,
+                    AttributeValue::S(version_item(positions.start)),
+                )
+                .expression_attribute_values(
+                    LAST_ITEM_VALUE,
+                    AttributeValue::S(version_item(positions.end - 1)),
+                )
+                .consistent_read(true)
+                .set_exclusive_start_key(start_key);
+
+            if refs_only {
+                query = query
+                    .projection_expression(format!(
+                        "{}, {}, {}",
+                        SORT_KEY_NAME, VERSION_NAME, HASH_ALIAS
+                    ))
+                    .expression_attribute_names(
+                        VERSION_NAME,
+                        constants::PACKAGES_TABLE_VERSION_NAME,
+                    )
+                    .expression_attribute_names(HASH_ALIAS, HASH_NAME);
+            }
+            if let Some(limit) = limit {
+                query = query.limit((limit - items.len()) as i32);
+            }
+
+            let response = query
+                .send()
+                .await
+                .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+            debug!(&response);
+
+            items.extend(response.items().unwrap_or_default().iter().cloned());
+
+            start_key = response.last_evaluated_key().cloned();
+            if start_key.is_none() || matches!(limit, Some(limit) if items.len() >= limit) {
+                return Ok(items);
+            }
+        }
+    }
+}
+
+/// A version item that the stored header doesn't cover yet
+struct NewVersion<'a> {
+    package_id: &'a str,
+    /// The revision the header of the package was read at
+    header_revision: u64,
+    item: HashMap<String, AttributeValue>,
+}
+
+/// Version items of a package that are written in a transaction, with a check of its header
+struct VersionBatch<'a> {
+    package_id: &'a str,
+    header_revision: u64,
+    items: Vec<HashMap<String, AttributeValue>>,
+}
+
+/// Batches versions of the same package, each batch fits into a transaction with the check of
+/// the header of its package
+fn batch_by_package(versions: Vec<NewVersion<'_>>) -> Vec<VersionBatch<'_>> {
+    let mut batches: Vec<VersionBatch> = vec![];
+
+    for version in versions {
+        match batches.last_mut() {
+            Some(batch)
+                if batch.package_id == version.package_id
+                    && batch.items.len() < MAX_TRANSACTION_ITEMS - 1 =>
+            {
+                batch.items.push(version.item);
+            }
+            _ => batches.push(VersionBatch {
+                package_id: version.package_id,
+                header_revision: version.header_revision,
+                items: vec![version.item],
+            }),
+        }
+    }
+
+    batches
+}
+
+/// Splits off the items that don't fit into a transaction which already has `used` items
+fn split_off_overflow<T>(items: &mut Vec<T>, used: usize) -> Vec<T> {
+    let available = MAX_TRANSACTION_ITEMS.saturating_sub(used);
+
+    match items.len() > available {
+        true => items.split_off(available),
+        false => vec![],
+    }
+}
+
+/// Positions are zero-padded, so version items sort in the order they were added
+fn version_item(position: u64) -> String {
+    format!("{}{:020}", constants::VERSION_ITEM_PREFIX, position)
+}
+
+fn to_json(value: &impl Serialize) -> Result<String, RepositoryError> {
+    serde_json::to_string(value)
+        .map_err(|_| RepositoryError::Unknown("Failed to serialize entity".to_string()))
+}
+
+/// The item of `version` at `position`, and its hash
+fn to_version_item(
+    package_id: &str,
+    position: u64,
+    version: &Version,
+) -> Result<(String, HashMap<String, AttributeValue>), RepositoryError> {
+    let object = to_json(version)?;
+    let hash = hex::encode(&Sha256::digest(object.as_bytes())[..8]);
+
+    let mut item = item_key(package_id, &version_item(position));
+    item.insert("object".to_string(), AttributeValue::S(object));
+    item.insert(HASH_NAME.to_string(), AttributeValue::S(hash.clone()));
+    item.insert(
+        constants::PACKAGES_TABLE_VERSION_NAME.to_string(),
+        AttributeValue::S(version_index_key(&version.name)),
+    );
+
+    Ok((hash, item))
+}
+
+/// Versions that only differ in build metadata are the same version, so they're indexed without it
+fn version_index_key(name: &VersionName) -> String {
+    match name {
+        VersionName::SemVer(semver) => SemVer {
+            build: vec![],
+            ..semver.clone()
+        }
+        .to_string(),
+        VersionName::Latest => name.to_string(),
+    }
+}
+
+/// The stored version that `name` is, compared as versions rather than as strings
+fn find_stored<'a>(
+    stored_versions: &'a [VersionRef],
+    name: &VersionName,
+) -> Option<&'a VersionRef> {
+    stored_versions.iter().find(|stored| stored.name == *name)
+}
+
+/// The header as it will be stored, with the revision incremented, only written if the stored
+/// revision is still `header_revision`
+fn put_header(
+    table_name: &str,
+    package: &Package,
+    positions: Range<u64>,
+    header_revision: u64,
+) -> Result<TransactWriteItem, RepositoryError> {
+    let mut header = package.clone();
+    header.versions = vec![];
+    header.revision = header_revision + 1;
+
+    let mut item = item_key(&header.id, constants::HEADER_ITEM);
+    item.insert("object".to_string(), AttributeValue::S(to_json(&header)?));
+    item.insert(
+        FIRST_POSITION_NAME.to_string(),
+        AttributeValue::N(positions.start.to_string()),
+    );
+    item.insert(
+        NEXT_POSITION_NAME.to_string(),
+        AttributeValue::N(positions.end.to_string()),
+    );
+    item.insert(
+        constants::PACKAGES_TABLE_REVISION_NAME.to_string(),
+        AttributeValue::N(header.revision.to_string()),
+    );
+
+    let (condition, values) = revision_condition(header_revision);
+    let put = Put::builder()
+        .table_name(table_name)
+        .set_item(Some(item))
+        .condition_expression(condition)
+        .expression_attribute_names(REVISION_NAME, constants::PACKAGES_TABLE_REVISION_NAME)
+        .set_expression_attribute_values(values)
+        .build();
+
+    Ok(TransactWriteItem::builder().put(put).build())
+}
+
+/// Fails the transaction if the stored revision of the header isn't `header_revision`
+fn check_header(table_name: &str, package_id: &str, header_revision: u64) -> TransactWriteItem {
+    let (condition, values) = revision_condition(header_revision);
+    let check = ConditionCheck::builder()
+        .table_name(table_name)
+        .set_key(Some(item_key(package_id, constants::HEADER_ITEM)))
+        .condition_expression(condition)
+        .expression_attribute_names(REVISION_NAME, constants::PACKAGES_TABLE_REVISION_NAME)
+        .set_expression_attribute_values(values)
+        .build();
+
+    TransactWriteItem::builder().condition_check(check).build()
+}
+
+fn put(table_name: &str, item: HashMap<String, AttributeValue>) -> TransactWriteItem {
+    let put = Put::builder()
+        .table_name(table_name)
+        .set_item(Some(item))
+        .build();
+
+    TransactWriteItem::builder().put(put).build()
+}
+
+fn delete(table_name: &str, key: HashMap<String, AttributeValue>) -> TransactWriteItem {
+    let delete = Delete::builder()
+        .table_name(table_name)
+        .set_key(Some(key))
+        .build();
+
+    TransactWriteItem::builder().delete(delete).build()
+}
+
+fn parse_string(
+    item: &HashMap<String, AttributeValue>,
+    name: &str,
+) -> Result<String, RepositoryError> {
+    item.get(name)
+        .and_then(|v| v.as_s().ok())
+        .cloned()
+        .ok_or_else(|| RepositoryError::Unknown(format!("Item has no `{}`", name)))
+}
+
+fn parse_number(
+    item: &HashMap<String, AttributeValue>,
+    name: &str,
+) -> Result<u64, RepositoryError> {
+    item.get(name)
+        .and_then(|v| v.as_n().ok())
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| RepositoryError::Unknown(format!("Item has no `{}`", name)))
+}
+
+fn parse_object<T: DeserializeOwned>(
+    item: &HashMap<String, AttributeValue>,
+    name: &str,
+) -> Result<T, RepositoryError> {
+    let json = parse_string(item, name)?;
+
+    serde_json::from_str(&json).map_err(|e| RepositoryError::Unknown(e.to_string()))
+}
+
+fn parse_header(item: &HashMap<String, AttributeValue>) -> Result<PackageHeader, RepositoryError> {
+    Ok(PackageHeader {
+        package: parse_object(item, "object")?,
+        positions: parse_number(item, FIRST_POSITION_NAME)?
+            ..parse_number(item, NEXT_POSITION_NAME)?,
+    })
+}
+
+fn parse_version_ref(
+    item: &HashMap<String, AttributeValue>,
+) -> Result<VersionRef, RepositoryError> {
+    let name = parse_string(item, constants::PACKAGES_TABLE_VERSION_NAME)?;
+
+    Ok(VersionRef {
+        name: name
+            .parse()
+            .map_err(|_| RepositoryError::Unknown(format!("`{}` is not a version name", name)))?,
+        position: parse_position(item)?,
+        hash: parse_string(item, HASH_NAME)?,
+    })
+}
+
+/// The position of a version item, from its sort key
+fn parse_position(item: &HashMap<String, AttributeValue>) -> Result<u64, RepositoryError> {
+    let sort_key = parse_string(item, constants::PACKAGES_TABLE_SORT_KEY_NAME)?;
+
+    sort_key
+        .strip_prefix(constants::VERSION_ITEM_PREFIX)
+        .and_then(|position| position.parse().ok())
+        .ok_or_else(|| RepositoryError::Unknown(format!("`{}` is not a version item", sort_key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_items_sort_after_the_header_in_the_order_they_were_added() {
+        let version = version_item(2);
+
+        assert_eq!(version, "version#00000000000000000002");
+        assert!(constants::HEADER_ITEM < version.as_str());
+        assert!(version < version_item(10));
+        assert_eq!(
+            parse_position(&item_key("user1/package1", &version_item(10))).unwrap(),
+            10
+        );
+    }
+
+    #[test]
+    fn only_versions_that_fit_are_kept_in_the_transaction() {
+        let mut items = (0..10).collect::<Vec<_>>();
+
+        assert_eq!(split_off_overflow(&mut items, 95), vec![5, 6, 7, 8, 9]);
+        assert_eq!(items, vec![0, 1, 2, 3, 4]);
+        assert!(split_off_overflow(&mut items, 0).is_empty());
+        assert_eq!(split_off_overflow(&mut items, 100), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn overflowing_versions_are_batched_by_package() {
+        let version = |package_id, position| NewVersion {
+            package_id,
+            header_revision: 3,
+            item: item_key(package_id, &version_item(position)),
+        };
+
+        let mut versions = (0..150)
+            .map(|i| version("user1/package1", i))
+            .collect::<Vec<_>>();
+        versions.push(version("user1/package2", 0));
+
+        let batches = batch_by_package(versions);
+
+        assert_eq!(
+            batches
+                .iter()
+                .map(|batch| (batch.package_id, batch.items.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("user1/package1", 99),
+                ("user1/package1", 51),
+                ("user1/package2", 1)
+            ]
+        );
+        assert!(batches.iter().all(|batch| batch.header_revision == 3));
+    }
+
+    #[test]
+    fn changed_versions_have_a_different_hash() {
+        let mut version = Version::new("1.0.0".parse().unwrap(), "test/uri1".parse().unwrap());
+        let (hash, item) = to_version_item("user1/package1", 0, &version).unwrap();
+
+        assert_eq!(
+            parse_object::<Version>(&item, "object").unwrap().uri,
+            "test/uri1".parse().unwrap()
+        );
+        assert_eq!(
+            parse_string(&item, constants::PACKAGES_TABLE_VERSION_NAME).unwrap(),
+            "1.0.0"
+        );
+
+        version.uri = "test/uri2".parse().unwrap();
+        let (changed_hash, _) = to_version_item("user1/package1", 0, &version).unwrap();
+
+        assert_ne!(hash, changed_hash);
+    }
+
+    #[test]
+    fn versions_that_only_differ_in_build_metadata_are_the_same_item() {
+        let stored = Version::new("1.0.0+a".parse().unwrap(), "test/uri1".parse().unwrap());
+        let (_, item) = to_version_item("user1/package1", 3, &stored).unwrap();
+
+        assert_eq!(
+            parse_string(&item, constants::PACKAGES_TABLE_VERSION_NAME).unwrap(),
+            "1.0.0"
+        );
+        assert_eq!(
+            version_index_key(&"1.0.0+b".parse().unwrap()),
+            version_index_key(&stored.name)
+        );
+        assert_eq!(
+            version_index_key(&"1.0.0-beta.1+b".parse().unwrap()),
+            "1.0.0-beta.1"
+        );
+
+        let stored_versions = vec![parse_version_ref(&item).unwrap()];
+
+        let found = find_stored(&stored_versions, &"1.0.0+b".parse().unwrap());
+        assert_eq!(found.map(|stored| stored.position), Some(3));
+        assert!(find_stored(&stored_versions, &"1.0.1+a".parse().unwrap()).is_none());
+    }
+}
@@ -0,0 +1,493 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::models::{AuditEntry, Package, Version, VersionName, WebhookSubscriptions};
+use crate::{
+    semver, AuditPage, AuditRepository, MoveRepository, Repository, RepositoryError, StoredEntity,
+    VersionPage, VersionRepository,
+};
+
+/// Keeps everything `DynamoDbRepository` stores in memory, with the same semantics: reads of
+/// missing or expired ids are `NotFound`, writes are `Conflict`s if the entity changed since it
+/// was read, and a transaction can't write the same id twice.
+/// Clones share the same store, so a single repository can back every dependency.
+#[derive(Clone, Default)]
+pub struct InMemoryPackageRepository {
+    /// Entities and packages, keyed by id
+    items: Arc<Mutex<HashMap<String, Item>>>,
+    audit_entries: Arc<Mutex<HashMap<String, Vec<AuditEntry>>>>,
+}
+
+/// A stored entity as JSON, and the seconds since the epoch after which it's gone
+struct Item {
+    json: String,
+    expires_at: Option<u64>,
+}
+
+impl Item {
+    /// DynamoDB deletes expired items eventually, here they're gone right away
+    fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
+
+/// An entity to write: its id, the revision it was read at and how it is stored
+struct Write {
+    id: String,
+    revision: u64,
+    item: Item,
+}
+
+impl Write {
+    fn new<T: Serialize>(
+        id: &str,
+        revision: u64,
+        entity: &T,
+        expires_at: Option<u64>,
+    ) -> Result<Self, RepositoryError> {
+        let json = serde_json::to_string(entity)
+            .map_err(|_| RepositoryError::Unknown("Failed to serialize entity".to_string()))?;
+
+        Ok(Self {
+            id: id.to_string(),
+            revision,
+            item: Item { json, expires_at },
+        })
+    }
+}
+
+impl InMemoryPackageRepository {
+    fn read_json<T: DeserializeOwned>(&self, key: &str) -> Result<T, RepositoryError> {
+        let items = self.items.lock().expect("store was poisoned");
+        let item = items
+            .get(key)
+            .filter(|item| !item.is_expired())
+            .ok_or(RepositoryError::NotFound)?;
+
+        serde_json::from_str(&item.json).map_err(|e| RepositoryError::Unknown(e.to_string()))
+    }
+
+    /// Writes all entities or, if any of them changed since it was read, none of them
+    fn write_all(&self, writes: Vec<Write>) -> Result<(), RepositoryError> {
+        let mut items = self.items.lock().expect("store was poisoned");
+
+        let mut ids = HashSet::new();
+        if let Some(write) = writes.iter().find(|write| !ids.insert(&write.id)) {
+            return Err(RepositoryError::Unknown(format!(
+                "`{}` is written more than once in the transaction",
+                write.id
+            )));
+        }
+
+        for write in &writes {
+            let stored_revision = match items.get(&write.id).filter(|item| !item.is_expired()) {
+                Some(item) => serde_json::from_str::<serde_json::Value>(&item.json)
+                    .map_err(|e| RepositoryError::Unknown(e.to_string()))?["revision"]
+                    .as_u64()
+                    .unwrap_or_default(),
+                None => 0,
+            };
+
+            if stored_revision != write.revision {
+                return Err(RepositoryError::Conflict);
+            }
+        }
+
+        for write in writes {
+            items.insert(write.id, write.item);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: StoredEntity> Repository<T> for InMemoryPackageRepository {
+    async fn read(&self, key: &str) -> Result<T, RepositoryError> {
+        self.read_json(key)
+    }
+
+    async fn update(&self, entity: &T) -> Result<(), RepositoryError> {
+        self.update_many(std::slice::from_ref(entity)).await
+    }
+
+    async fn update_many(&self, entities: &[T]) -> Result<(), RepositoryError> {
+        self.write_all(entity_writes(entities)?)
+    }
+}
+
+#[async_trait]
+impl Repository<Package> for InMemoryPackageRepository {
+    async fn read(&self, key: &str) -> Result<Package, RepositoryError> {
+        let mut package: Package = self.read_json(key)?;
+        // In the order `DynamoDbRepository` reads them
+        semver::sort_versions(&mut package.versions);
+
+        Ok(package)
+    }
+
+    async fn update(&self, entity: &Package) -> Result<(), RepositoryError> {
+        self.update_many(std::slice::from_ref(entity)).await
+    }
+
+    async fn update_many(&self, entities: &[Package]) -> Result<(), RepositoryError> {
+        self.write_all(package_writes(entities)?)
+    }
+}
+
+#[async_trait]
+impl MoveRepository for InMemoryPackageRepository {
+    async fn update_with_subscriptions(
+        &self,
+        packages: &[Package],
+        subscriptions: &[WebhookSubscriptions],
+    ) -> Result<(), RepositoryError> {
+        let mut writes = package_writes(packages)?;
+        writes.extend(entity_writes(subscriptions)?);
+
+        self.write_all(writes)
+    }
+}
+
+fn package_writes(packages: &[Package]) -> Result<Vec<Write>, RepositoryError> {
+    packages
+        .iter()
+        .map(|package| {
+            let mut stored = package.clone();
+            stored.revision += 1;
+            Write::new(&package.id, package.revision, &stored, None)
+        })
+        .collect()
+}
+
+fn entity_writes(entities: &[impl StoredEntity]) -> Result<Vec<Write>, RepositoryError> {
+    entities
+        .iter()
+        .map(|entity| {
+            let mut stored = entity.clone();
+            stored.set_revision(entity.revision() + 1);
+            Write::new(entity.id(), entity.revision(), &stored, entity.expires_at())
+        })
+        .collect()
+}
+
+#[async_trait]
+impl VersionRepository for InMemoryPackageRepository {
+    async fn read_header(&self, package_id: &str) -> Result<Package, RepositoryError> {
+        let mut package: Package = self.read_json(package_id)?;
+        package.versions = vec![];
+
+        Ok(package)
+    }
+
+    async fn update_version(
+        &self,
+        package: &Package,
+        version: &Version,
+    ) -> Result<(), RepositoryError> {
+        let mut stored = package.clone();
+        if package.revision > 0 {
+            stored.versions = match self.read_json::<Package>(&package.id) {
+                Ok(current) => current.versions,
+                Err(RepositoryError::NotFound) => return Err(RepositoryError::Conflict),
+                Err(e) => return Err(e),
+            };
+        }
+
+        match stored.versions.iter_mut().find(|v| v.name == version.name) {
+            Some(existing) => *existing = version.clone(),
+            None => stored.versions.push(version.clone()),
+        }
+
+        self.update(&stored).await
+    }
+
+    async fn read_version(
+        &self,
+        package_id: &str,
+        version: &VersionName,
+    ) -> Result<Version, RepositoryError> {
+        let package: Package = self.read_json(package_id)?;
+
+        package
+            .versions
+            .into_iter()
+            .find(|v| v.name == *version)
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn read_versions(
+        &self,
+        package_id: &str,
+        after: Option<VersionName>,
+        limit: usize,
+    ) -> Result<VersionPage, RepositoryError> {
+        let package: Package = self.read_json(package_id)?;
+
+        VersionPage::of(&package.versions, after, limit)
+    }
+}
+
+#[async_trait]
+impl AuditRepository for InMemoryPackageRepository {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), RepositoryError> {
+        let mut audit_entries = self.audit_entries.lock().expect("store was poisoned");
+        let entries = audit_entries.entry(entry.package_id.clone()).or_default();
+
+        // Existing entries are never overwritten
+        if entries.iter().any(|e| e.entry_id() == entry.entry_id()) {
+            return Err(RepositoryError::Unknown(
+                "Audit entry already exists".to_string(),
+            ));
+        }
+
+        entries.push(entry.clone());
+        entries.sort_by_key(|e| e.entry_id());
+
+        Ok(())
+    }
+
+    async fn commit(&self, entry: &AuditEntry) -> Result<(), RepositoryError> {
+        let mut audit_entries = self.audit_entries.lock().expect("store was poisoned");
+        let pending = audit_entries
+            .get_mut(&entry.package_id)
+            .and_then(|entries| {
+                entries
+                    .iter_mut()
+                    .find(|e| e.entry_id() == entry.entry_id() && e.pending)
+            })
+            .ok_or_else(|| RepositoryError::Unknown("Audit entry is not pending".to_string()))?;
+
+        *pending = entry.clone();
+
+        Ok(())
+    }
+
+    async fn remove_pending(&self, entry: &AuditEntry) -> Result<(), RepositoryError> {
+        let mut audit_entries = self.audit_entries.lock().expect("store was poisoned");
+        let entries = audit_entries.entry(entry.package_id.clone()).or_default();
+
+        match entries
+            .iter()
+            .position(|e| e.entry_id() == entry.entry_id() && e.pending)
+        {
+            Some(position) => {
+                entries.remove(position);
+                Ok(())
+            }
+            None => Err(RepositoryError::Unknown(
+                "Audit entry is not pending".to_string(),
+            )),
+        }
+    }
+
+    async fn list(&self, package_id: &str) -> Result<Vec<AuditEntry>, RepositoryError> {
+        let audit_entries = self.audit_entries.lock().expect("store was poisoned");
+
+        Ok(audit_entries.get(package_id).cloned().unwrap_or_default())
+    }
+
+    async fn list_page(
+        &self,
+        package_id: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<AuditPage, RepositoryError> {
+        let audit_entries = self.audit_entries.lock().expect("store was poisoned");
+        let entries = audit_entries
+            .get(package_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        Ok(AuditPage::of(entries, after, limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{IdempotencyRecord, Organization, Package, Version},
+        Repository, RepositoryError, VersionRepository,
+    };
+
+    use super::InMemoryPackageRepository;
+
+    fn package(name: &str, versions: &[&str]) -> Package {
+        let mut package = Package::new(name.parse().unwrap(), "user1".parse().unwrap());
+        package.versions = versions
+            .iter()
+            .map(|v| Version::new(v.parse().unwrap(), "test/uri1".parse().unwrap()))
+            .collect();
+        package
+    }
+
+    #[tokio::test]
+    async fn reads_what_was_written() {
+        let repo = InMemoryPackageRepository::default();
+
+        assert!(matches!(
+            Repository::<Package>::read(&repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
+
+        repo.update(&package("package1", &["1.0.0"])).await.unwrap();
+
+        let stored: Package = repo.read("user1/package1").await.unwrap();
+        assert_eq!(stored.versions.len(), 1);
+        assert_eq!(stored.revision, 1);
+    }
+
+    #[tokio::test]
+    async fn reads_versions_in_order_of_precedence() {
+        let repo = InMemoryPackageRepository::default();
+
+        repo.update(&package("package1", &["1.10.0", "1.2.0", "1.0.0-beta"]))
+            .await
+            .unwrap();
+
+        let stored: Package = repo.read("user1/package1").await.unwrap();
+        assert_eq!(
+            stored
+                .versions
+                .iter()
+                .map(|v| v.name.to_string())
+                .collect::<Vec<_>>(),
+            ["1.0.0-beta", "1.2.0", "1.10.0"]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_writes_of_changed_entities() {
+        let repo = InMemoryPackageRepository::default();
+        let org = Organization::new("org1".parse().unwrap(), "user1".parse().unwrap());
+
+        repo.update(&org).await.unwrap();
+        // Written again as if it was new
+        assert!(matches!(
+            repo.update(&org).await,
+            Err(RepositoryError::Conflict)
+        ));
+
+        let mut stored: Organization = repo.read(&org.id).await.unwrap();
+        repo.update(&stored).await.unwrap();
+        // Written again at the revision that was already overwritten
+        assert!(matches!(
+            repo.update(&stored).await,
+            Err(RepositoryError::Conflict)
+        ));
+
+        stored.revision += 1;
+        assert!(repo.update(&stored).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn writes_all_or_nothing() {
+        let repo = InMemoryPackageRepository::default();
+        repo.update(&package("package2", &[])).await.unwrap();
+
+        let result = repo
+            .update_many(&[package("package1", &["1.0.0"]), package("package2", &[])])
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::Conflict)));
+        assert!(matches!(
+            Repository::<Package>::read(&repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_writing_an_id_twice_in_one_transaction() {
+        let repo = InMemoryPackageRepository::default();
+
+        let result = repo
+            .update_many(&[package("package1", &["1.0.0"]), package("package1", &[])])
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::Unknown(_))));
+        assert!(matches!(
+            Repository::<Package>::read(&repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn expired_entities_are_gone() {
+        let repo = InMemoryPackageRepository::default();
+        let record = IdempotencyRecord {
+            id: "idempotency:user1:key1".into(),
+            request_hash: "hash1".into(),
+            response: None,
+            locked_until: 0,
+            expires_at: 1,
+            revision: 0,
+        };
+
+        repo.update(&record).await.unwrap();
+
+        assert!(matches!(
+            Repository::<IdempotencyRecord>::read(&repo, &record.id).await,
+            Err(RepositoryError::NotFound)
+        ));
+        // Written again as if it was new
+        assert!(repo.update(&record).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn reads_versions_a_page_at_a_time() {
+        let repo = InMemoryPackageRepository::default();
+        repo.update(&package("package1", &["1.0.0", "1.1.0", "2.0.0"]))
+            .await
+            .unwrap();
+
+        let page = repo.read_versions("user1/package1", None, 2).await.unwrap();
+        assert_eq!(page.versions.len(), 2);
+        assert_eq!(page.next, Some("1.1.0".parse().unwrap()));
+
+        let page = repo
+            .read_versions("user1/package1", page.next, 2)
+            .await
+            .unwrap();
+        assert_eq!(page.versions[0].name, "2.0.0".parse().unwrap());
+        assert_eq!(page.next, None);
+
+        assert!(matches!(
+            repo.read_version("user1/package1", &"3.0.0".parse().unwrap())
+                .await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn updates_a_version_of_the_header() {
+        let repo = InMemoryPackageRepository::default();
+        repo.update(&package("package1", &["1.0.0"])).await.unwrap();
+
+        let header = repo.read_header("user1/package1").await.unwrap();
+        assert!(header.versions.is_empty());
+
+        let version = Version::new("2.0.0".parse().unwrap(), "test/uri2".parse().unwrap());
+        repo.update_version(&header, &version).await.unwrap();
+
+        let stored: Package = repo.read("user1/package1").await.unwrap();
+        assert_eq!(stored.versions.len(), 2);
+        assert_eq!(stored.revision, 2);
+
+        // Written again at the revision that was already overwritten
+        assert!(matches!(
+            repo.update_version(&header, &version).await,
+            Err(RepositoryError::Conflict)
+        ));
+    }
+}
@@ -3,7 +3,8 @@ use aws_sdk_dynamodb::{
     config::Region,
     meta::PKG_VERSION,
     types::{
-        AttributeDefinition, KeySchemaElement, KeyType, ProvisionedThroughput, ScalarAttributeType,
+        AttributeDefinition, KeySchemaElement, KeyType, LocalSecondaryIndex, Projection,
+        ProjectionType, ProvisionedThroughput, ScalarAttributeType,
     },
     Client,
 };
@@ -27,8 +28,6 @@ pub async fn get_dynamodb_client() -> Client {
 pub async fn setup_local_db() {
     println!("Setting up local DynamoDB...");
 
-    let table_name = constants::PACKAGES_TABLE_LOCAL;
-
     let config = make_config(Opt::parse()).await.unwrap();
     let dynamodb_local_config = aws_sdk_dynamodb::config::Builder::from(&config)
         .endpoint_url(
@@ -39,6 +38,38 @@ pub async fn setup_local_db() {
 
     let client = Client::from_conf(dynamodb_local_config);
 
+    create_table(
+        &client,
+        constants::PACKAGES_TABLE_LOCAL,
+        &[
+            (constants::PACKAGES_TABLE_KEY_NAME, KeyType::Hash),
+            (constants::PACKAGES_TABLE_SORT_KEY_NAME, KeyType::Range),
+        ],
+        Some((
+            constants::VERSION_NAME_INDEX,
+            constants::PACKAGES_TABLE_VERSION_NAME,
+        )),
+    )
+    .await;
+    create_table(
+        &client,
+        constants::AUDIT_TABLE_LOCAL,
+        &[
+            (constants::AUDIT_TABLE_KEY_NAME, KeyType::Hash),
+            (constants::AUDIT_TABLE_SORT_KEY_NAME, KeyType::Range),
+        ],
+        None,
+    )
+    .await;
+}
+
+/// Creates a table keyed by `keys`, with a local secondary index `(name, sort key)` when given
+async fn create_table(
+    client: &Client,
+    table_name: &str,
+    keys: &[(&str, KeyType)],
+    index: Option<(&str, &str)>,
+) {
     match client.describe_table().table_name(table_name).send().await {
         Ok(_) => {
             println!("Table `{}` already exists. Skipping.", &table_name);
@@ -49,24 +80,56 @@ pub async fn setup_local_db() {
         }
     }
 
-    let ad = AttributeDefinition::builder()
-        .attribute_name(constants::PACKAGES_TABLE_KEY_NAME)
-        .attribute_type(ScalarAttributeType::S)
-        .build();
-    let ks = KeySchemaElement::builder()
-        .attribute_name(constants::PACKAGES_TABLE_KEY_NAME)
-        .key_type(KeyType::Hash)
-        .build();
+    let mut create_table = client.create_table().table_name(table_name);
+
+    for (name, key_type) in keys {
+        let ad = AttributeDefinition::builder()
+            .attribute_name(*name)
+            .attribute_type(ScalarAttributeType::S)
+            .build();
+        let ks = KeySchemaElement::builder()
+            .attribute_name(*name)
+            .key_type(key_type.clone())
+            .build();
+
+        create_table = create_table.attribute_definitions(ad).key_schema(ks);
+    }
+
+    if let Some((index_name, sort_key)) = index {
+        let ad = AttributeDefinition::builder()
+            .attribute_name(sort_key)
+            .attribute_type(ScalarAttributeType::S)
+            .build();
+        let hash_key = KeySchemaElement::builder()
+            .attribute_name(keys[0].0)
+            .key_type(KeyType::Hash)
+            .build();
+        let range_key = KeySchemaElement::builder()
+            .attribute_name(sort_key)
+            .key_type(KeyType::Range)
+            .build();
+        let lsi = LocalSecondaryIndex::builder()
+            .index_name(index_name)
+            .key_schema(hash_key)
+            .key_schema(range_key)
+            .projection(
+                Projection::builder()
+                    .projection_type(ProjectionType::All)
+                    .build(),
+            )
+            .build();
+
+        create_table = create_table
+            .attribute_definitions(ad)
+            .local_secondary_indexes(lsi);
+    }
+
     let pt = ProvisionedThroughput::builder()
         .read_capacity_units(5)
         .write_capacity_units(5)
         .build();
 
-    client
-        .create_table()
-        .table_name(table_name)
-        .attribute_definitions(ad)
-        .key_schema(ks)
+    create_table
         .provisioned_throughput(pt)
         .send()
         .await
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::put_item::PutItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::Client;
+
+use crate::models::Package;
+use crate::{constants, DynamoDbRepository, Repository, RepositoryError};
+
+/// What a migration copied, and the items it left out with the reason.
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub packages: usize,
+    pub entities: usize,
+    pub skipped: Vec<String>,
+}
+
+/// Copies every item of a table with the old schema, where every entity (including a package with
+/// all its versions) is a single `object` item keyed by `id`, to a table with the current schema.
+/// Ids are made lowercase like `Package::id_of`. Items that already exist in the new table are
+/// skipped, so a migration can be run again. Idempotency records expire within hours and are
+/// not copied.
+pub async fn migrate_packages(
+    client: &Client,
+    from_table: &str,
+    to_table: &str,
+    dry_run: bool,
+) -> Result<MigrationReport, RepositoryError> {
+    let package_repo = DynamoDbRepository::new(client.clone(), to_table.to_string());
+    let mut report = MigrationReport::default();
+    let mut start_key: Option<HashMap<String, AttributeValue>> = None;
+
+    loop {
+        let response = client
+            .scan()
+            .table_name(from_table)
+            .set_exclusive_start_key(start_key)
+            .send()
+            .await
+            .map_err(|error| RepositoryError::Unknown(error.to_string()))?;
+
+        for item in response.items().unwrap_or_default() {
+            let id = item
+                .get(constants::PACKAGES_TABLE_KEY_NAME)
+                .and_then(|v| v.as_s().ok())
+                .cloned()
+                .unwrap_or_default();
+
+            let result = match migrated_id(&id) {
+                None => Err("idempotency records are not migrated".to_string()),
+                Some(MigratedId::Package(new_id)) => {
+                    migrate_package(item, &new_id, &package_repo, dry_run)
+                        .await
+                        .map(|_| report.packages += 1)
+                }
+                Some(MigratedId::Entity(new_id)) => {
+                    migrate_entity(client, to_table, item, &new_id, dry_run)
+                        .await
+                        .map(|_| report.entities += 1)
+                }
+            };
+
+            if let Err(reason) = result {
+                report.skipped.push(format!("`{}`: {}", id, reason));
+            }
+        }
+
+        start_key = response.last_evaluated_key().cloned();
+        if start_key.is_none() {
+            return Ok(report);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum MigratedId {
+    Package(String),
+    Entity(String),
+}
+
+/// Entity ids are prefixed, e.g. `keys:{user}`, so they can never be a package id
+fn migrated_id(id: &str) -> Option<MigratedId> {
+    if id.starts_with("idempotency:") {
+        return None;
+    }
+
+    let new_id = id.to_ascii_lowercase();
+
+    match id.contains(':') || id == "webhooks" {
+        true => Some(MigratedId::Entity(new_id)),
+        false => Some(MigratedId::Package(new_id)),
+    }
+}
+
+async fn migrate_package(
+    item: &HashMap<String, AttributeValue>,
+    new_id: &str,
+    package_repo: &DynamoDbRepository,
+    dry_run: bool,
+) -> Result<(), String> {
+    let package: Package = parse_object(item)?;
+    let package = migrated_package(package, new_id);
+
+    if dry_run {
+        return Ok(());
+    }
+
+    // A new package is only written if its id doesn't exist yet
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => format!("`{}` already exists", new_id),
+        e => e.to_string(),
+    })
+}
+
+/// The package as it is stored under its new id, written as a new package
+fn migrated_package(mut package: Package, new_id: &str) -> Package {
+    package.id = new_id.to_string();
+    package.redirect = package.redirect.map(|id| id.to_ascii_lowercase());
+    package.revision = 0;
+    package
+}
+
+async fn migrate_entity(
+    client: &Client,
+    to_table: &str,
+    item: &HashMap<String, AttributeValue>,
+    new_id: &str,
+    dry_run: bool,
+) -> Result<(), String> {
+    let mut object: serde_json::Value = parse_object(item)?;
+    object["id"] = serde_json::Value::String(new_id.to_string());
+
+    let mut item = item.clone();
+    item.insert(
+        constants::PACKAGES_TABLE_KEY_NAME.to_string(),
+        AttributeValue::S(new_id.to_string()),
+    );
+    item.insert(
+        constants::PACKAGES_TABLE_SORT_KEY_NAME.to_string(),
+        AttributeValue::S(constants::HEADER_ITEM.to_string()),
+    );
+    item.insert("object".to_string(), AttributeValue::S(object.to_string()));
+
+    if dry_run {
+        return Ok(());
+    }
+
+    client
+        .put_item()
+        .table_name(to_table)
+        .set_item(Some(item))
+        .condition_expression(format!(
+            "attribute_not_exists({})",
+            constants::PACKAGES_TABLE_KEY_NAME
+        ))
+        .send()
+        .await
+        .map_err(|error| match &error {
+            SdkError::ServiceError(e)
+                if matches!(e.err(), PutItemError::ConditionalCheckFailedException(_)) =>
+            {
+                format!("`{}` already exists", new_id)
+            }
+            _ => error.to_string(),
+        })?;
+
+    Ok(())
+}
+
+fn parse_object<T: serde::de::DeserializeOwned>(
+    item: &HashMap<String, AttributeValue>,
+) -> Result<T, String> {
+    let json = item
+        .get("object")
+        .and_then(|v| v.as_s().ok())
+        .ok_or("item has no `object`")?;
+
+    serde_json::from_str(json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_made_lowercase() {
+        assert_eq!(
+            migrated_id("Polywrap/Ethereum"),
+            Some(MigratedId::Package("polywrap/ethereum".into()))
+        );
+        assert_eq!(
+            migrated_id("keys:User1"),
+            Some(MigratedId::Entity("keys:user1".into()))
+        );
+        assert_eq!(
+            migrated_id("webhooks"),
+            Some(MigratedId::Entity("webhooks".into()))
+        );
+        assert_eq!(migrated_id("idempotency:user1:Key"), None);
+    }
+
+    #[test]
+    fn packages_keep_their_display_names() {
+        let mut package = Package::new("Ethereum".parse().unwrap(), "Polywrap".parse().unwrap());
+        package.id = "Polywrap/Ethereum".into();
+        package.redirect = Some("Polywrap/Ethereum-V2".into());
+        package.revision = 7;
+
+        let package = migrated_package(package, "polywrap/ethereum");
+
+        assert_eq!(package.id, "polywrap/ethereum");
+        assert_eq!(package.redirect, Some("polywrap/ethereum-v2".into()));
+        assert_eq!(package.name.to_string(), "Ethereum");
+        assert_eq!(package.revision, 0);
+    }
+}
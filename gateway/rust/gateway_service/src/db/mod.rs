@@ -4,5 +4,28 @@ pub use dynamodb::*;
 mod repository;
 pub use repository::*;
 
+mod audit_repository;
+pub use audit_repository::*;
+
+mod dynamodb_audit_repository;
+pub use dynamodb_audit_repository::DynamoDbAuditRepository;
+
+mod version_repository;
+pub use version_repository::*;
+
+mod move_repository;
+pub use move_repository::*;
+
+mod read_package_by_name;
+pub use read_package_by_name::*;
+
+mod dynamodb_package_repository;
+
+mod migrate_packages;
+pub use migrate_packages::*;
+
+mod in_memory_package_repository;
+pub use in_memory_package_repository::*;
+
 #[cfg(feature = "local")]
 pub mod local_db;
@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+
+use crate::models::{Package, WebhookSubscriptions};
+
+use super::RepositoryError;
+
+/// Moves packages together with the entities that are keyed by their ids.
+#[async_trait]
+pub trait MoveRepository {
+    /// Updates the packages and the webhook subscriptions in a single transaction
+    async fn update_with_subscriptions(
+        &self,
+        packages: &[Package],
+        subscriptions: &[WebhookSubscriptions],
+    ) -> Result<(), RepositoryError>;
+}
@@ -0,0 +1,155 @@
+use crate::models::{Package, PackageName, Username, Version, VersionName};
+
+use super::{Repository, RepositoryError, VersionPage, VersionRepository};
+
+/// The number of versions `read_all_versions` reads at once
+const VERSION_PAGE_SIZE: usize = 100;
+
+/// Reads the package `name` of `user`. A package published before ids were lowercase is read
+/// from its id as typed, and returned as a new package under its lowercase id, so the next
+/// change writes it there and a publish never creates a second package with the same name.
+pub async fn read_package_by_name(
+    user: &Username,
+    name: &PackageName,
+    package_repo: &impl Repository<Package>,
+) -> Result<Package, RepositoryError> {
+    let id = Package::id_of(user, name);
+    let legacy_id = Package::legacy_id_of(user, name);
+
+    match package_repo.read(&id).await {
+        Err(RepositoryError::NotFound) if legacy_id != id => {
+            let mut package = package_repo.read(&legacy_id).await?;
+            package.id = id;
+            package.revision = 0;
+            Ok(package)
+        }
+        result => result,
+    }
+}
+
+/// Reads the package `name` of `user` without its versions, see `read_package_by_name`.
+/// A package that isn't stored under its lowercase id yet is read with all its versions
+pub async fn read_header_by_name(
+    user: &Username,
+    name: &PackageName,
+    package_repo: &(impl Repository<Package> + VersionRepository),
+) -> Result<Package, RepositoryError> {
+    let id = Package::id_of(user, name);
+    let legacy_id = Package::legacy_id_of(user, name);
+
+    match package_repo.read_header(&id).await {
+        Err(RepositoryError::NotFound) if legacy_id != id => {
+            let mut package = package_repo.read(&legacy_id).await?;
+            package.id = id;
+            package.revision = 0;
+            Ok(package)
+        }
+        result => result,
+    }
+}
+
+/// Reads the version `name` of a package read with `read_header_by_name`
+pub async fn read_version_of(
+    package: &Package,
+    name: &VersionName,
+    version_repo: &impl VersionRepository,
+) -> Result<Version, RepositoryError> {
+    match package.revision {
+        0 => package
+            .versions
+            .iter()
+            .find(|v| v.name == *name)
+            .cloned()
+            .ok_or(RepositoryError::NotFound),
+        _ => version_repo.read_version(&package.id, name).await,
+    }
+}
+
+/// Reads every version of a package read with `read_header_by_name`, a page at a time
+pub async fn read_all_versions(
+    package: &Package,
+    version_repo: &impl VersionRepository,
+) -> Result<Vec<Version>, RepositoryError> {
+    if package.revision == 0 {
+        return Ok(package.versions.clone());
+    }
+
+    let mut versions = vec![];
+    let mut after = None;
+
+    loop {
+        let page = version_repo
+            .read_versions(&package.id, after, VERSION_PAGE_SIZE)
+            .await?;
+        versions.extend(page.versions);
+
+        match page.next {
+            Some(next) => after = Some(next),
+            None => return Ok(versions),
+        }
+    }
+}
+
+/// Reads a page of the versions of the package `name` of `user`, see `read_package_by_name`
+pub async fn read_versions_by_name(
+    user: &Username,
+    name: &PackageName,
+    after: Option<VersionName>,
+    limit: usize,
+    version_repo: &impl VersionRepository,
+) -> Result<VersionPage, RepositoryError> {
+    let id = Package::id_of(user, name);
+    let legacy_id = Package::legacy_id_of(user, name);
+
+    match version_repo.read_versions(&id, after.clone(), limit).await {
+        Err(RepositoryError::NotFound) if legacy_id != id => {
+            version_repo.read_versions(&legacy_id, after, limit).await
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{Package, Version},
+        InMemoryPackageRepository, Repository,
+    };
+
+    use super::{read_package_by_name, read_versions_by_name};
+
+    #[tokio::test]
+    async fn reads_packages_stored_under_their_typed_id() {
+        let package_repo = InMemoryPackageRepository::default();
+        let mut package = Package::new("Package1".parse().unwrap(), "User1".parse().unwrap());
+        package.id = "User1/Package1".into();
+        package.versions.push(Version::new(
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+        ));
+        package_repo.update(&package).await.unwrap();
+
+        let user = "User1".parse().unwrap();
+        let name = "Package1".parse().unwrap();
+
+        let read = read_package_by_name(&user, &name, &package_repo)
+            .await
+            .unwrap();
+        assert_eq!(read.id, "user1/package1");
+        assert_eq!(read.revision, 0);
+        assert_eq!(read.versions.len(), 1);
+
+        let page = read_versions_by_name(&user, &name, None, 10, &package_repo)
+            .await
+            .unwrap();
+        assert_eq!(page.versions.len(), 1);
+
+        // Written under the lowercase id, which is read from then on
+        package_repo.update(&read).await.unwrap();
+        let read = read_package_by_name(&user, &name, &package_repo)
+            .await
+            .unwrap();
+        assert_eq!(read.id, "user1/package1");
+        assert_eq!(read.revision, 1);
+    }
+}
@@ -5,6 +5,8 @@ use async_trait::async_trait;
 #[derive(Debug, thiserror::Error)]
 pub enum RepositoryError {
     NotFound,
+    /// The entity was changed by someone else since it was read
+    Conflict,
     Unknown(String),
 }
 
@@ -12,6 +14,7 @@ impl Display for RepositoryError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             RepositoryError::NotFound => write!(f, "Not found"),
+            RepositoryError::Conflict => write!(f, "Conflicting update"),
             RepositoryError::Unknown(message) => write!(f, "Unknown error: {}", message),
         }
     }
@@ -21,4 +24,6 @@ impl Display for RepositoryError {
 pub trait Repository<TEntity> {
     async fn read(&self, key: &str) -> Result<TEntity, RepositoryError>;
     async fn update(&self, entity: &TEntity) -> Result<(), RepositoryError>;
+    /// Updates all entities in a single transaction: either every update is applied or none is
+    async fn update_many(&self, entities: &[TEntity]) -> Result<(), RepositoryError>;
 }
@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::models::{Package, Version, VersionName};
+
+use super::RepositoryError;
+
+/// A page of the versions of a package, in the order they were published.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct VersionPage {
+    pub versions: Vec<Version>,
+    /// The version to read the next page after, if there are more versions
+    pub next: Option<VersionName>,
+}
+
+impl VersionPage {
+    /// The page of up to `limit` of all `versions` of a package, after `after`
+    pub fn of(
+        versions: &[Version],
+        after: Option<VersionName>,
+        limit: usize,
+    ) -> Result<Self, RepositoryError> {
+        let start = match after {
+            Some(after) => {
+                versions
+                    .iter()
+                    .position(|v| v.name == after)
+                    .ok_or(RepositoryError::NotFound)?
+                    + 1
+            }
+            None => 0,
+        };
+        let end = versions.len().min(start + limit);
+        let page = versions[start..end].to_vec();
+
+        let next = match end < versions.len() {
+            true => page.last().map(|v| v.name.clone()),
+            false => None,
+        };
+
+        Ok(Self {
+            versions: page,
+            next,
+        })
+    }
+}
+
+/// Reads and writes versions without reading or rewriting the whole package.
+#[async_trait]
+pub trait VersionRepository {
+    /// Reads the package without its versions. A package that isn't stored yet, like one read from
+    /// a legacy table, has revision 0 and is read with all its versions, since writing it writes them
+    async fn read_header(&self, package_id: &str) -> Result<Package, RepositoryError>;
+    async fn read_version(
+        &self,
+        package_id: &str,
+        version: &VersionName,
+    ) -> Result<Version, RepositoryError>;
+    /// Reads up to `limit` versions published after `after`, or from the first version.
+    /// `after` has to be a version of the package
+    async fn read_versions(
+        &self,
+        package_id: &str,
+        after: Option<VersionName>,
+        limit: usize,
+    ) -> Result<VersionPage, RepositoryError>;
+    /// Writes the package read with `read_header` and adds `version` to it, or replaces the version
+    /// with the same name. The other versions aren't rewritten, so the versions of `package` are
+    /// ignored unless it's written as a new package (revision 0). Like `Repository::update`, it's
+    /// only written if it wasn't changed since it was read
+    async fn update_version(
+        &self,
+        package: &Package,
+        version: &Version,
+    ) -> Result<(), RepositoryError>;
+}
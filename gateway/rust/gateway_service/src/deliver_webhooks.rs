@@ -0,0 +1,215 @@
+use std::time::Duration;
+
+use aws_lambda_events::sqs::{BatchItemFailure, SqsBatchResponse, SqsEvent, SqsMessage};
+use futures::future::join_all;
+use lambda_runtime::{run, service_fn, Error, LambdaEvent};
+
+use crate::{
+    constants,
+    models::WebhookSubscriptions,
+    setup_logging,
+    webhooks::{deliver_queued, HttpWebhookDelivery, QueuedDelivery, WebhookDelivery},
+    DynamoDbRepository, Repository,
+};
+
+/// How long the first retry of a failed delivery waits, every later retry waits 4 times longer
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// The longest SQS can hide a message for
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(12 * 60 * 60);
+const RECEIVE_COUNT_ATTRIBUTE: &str = "ApproximateReceiveCount";
+
+/// Delivers the events publishes queued, see `notify_subscribers`. Messages that couldn't be
+/// delivered are reported back and hidden for `retry_delay`, so SQS receives them again with
+/// exponential backoff until the queue moves them to its dead-letter queue.
+pub async fn deliver_webhooks() -> Result<(), Error> {
+    setup_logging();
+
+    let config = aws_config::load_from_env().await;
+    let table_name =
+        std::env::var(constants::ENV_PACKAGES_TABLE).expect("ENV_PACKAGES_TABLE not set");
+    let queue_url =
+        std::env::var(constants::ENV_WEBHOOK_QUEUE_URL).expect("ENV_WEBHOOK_QUEUE_URL not set");
+
+    let mut webhook_repo =
+        DynamoDbRepository::new(aws_sdk_dynamodb::Client::new(&config), table_name);
+    if let Ok(legacy_table_name) = std::env::var(constants::ENV_LEGACY_PACKAGES_TABLE) {
+        webhook_repo = webhook_repo.with_legacy_table(legacy_table_name);
+    }
+    let webhook_repo = &webhook_repo;
+    let delivery = &HttpWebhookDelivery::new();
+    let sqs_client = &aws_sdk_sqs::Client::new(&config);
+    let queue_url = queue_url.as_str();
+
+    run(service_fn(move |event: LambdaEvent<SqsEvent>| async move {
+        let failed = deliver_messages(event.payload, webhook_repo, delivery).await;
+        delay_retries(&failed, sqs_client, queue_url).await;
+
+        Ok::<_, Error>(SqsBatchResponse {
+            batch_item_failures: failed
+                .into_iter()
+                .map(|message| BatchItemFailure {
+                    item_identifier: message.message_id.unwrap_or_default(),
+                })
+                .collect(),
+        })
+    }))
+    .await
+}
+
+/// Delivers every message at the same time and returns the ones that failed
+async fn deliver_messages(
+    event: SqsEvent,
+    webhook_repo: &impl Repository<WebhookSubscriptions>,
+    delivery: &impl WebhookDelivery,
+) -> Vec<SqsMessage> {
+    let deliveries = event.records.into_iter().map(|message| async move {
+        let queued = message
+            .body
+            .as_deref()
+            .ok_or_else(|| "Message has no body".to_string())
+            .and_then(|body| {
+                serde_json::from_str::<QueuedDelivery>(body).map_err(|e| e.to_string())
+            });
+
+        let result = match queued {
+            Ok(queued) => deliver_queued(&queued, webhook_repo, delivery)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+
+        result.map_err(|e| {
+            eprintln!("Failed to deliver webhook {:?}: {}", message.message_id, e);
+            message
+        })
+    });
+
+    join_all(deliveries)
+        .await
+        .into_iter()
+        .filter_map(Result::err)
+        .collect()
+}
+
+/// Hides failed messages until their next attempt is due. A message that can't be hidden is
+/// received again after the queue's `VisibilityTimeout`
+async fn delay_retries(failed: &[SqsMessage], sqs_client: &aws_sdk_sqs::Client, queue_url: &str) {
+    let changes = failed.iter().filter_map(|message| {
+        let receipt_handle = message.receipt_handle.as_ref()?;
+        let receive_count = message
+            .attributes
+            .get(RECEIVE_COUNT_ATTRIBUTE)
+            .and_then(|count| count.parse().ok())
+            .unwrap_or(1);
+
+        Some(async move {
+            let result = sqs_client
+                .change_message_visibility()
+                .queue_url(queue_url)
+                .receipt_handle(receipt_handle)
+                .visibility_timeout(retry_delay(receive_count).as_secs() as i32)
+                .send()
+                .await;
+
+            if let Err(e) = result {
+                eprintln!(
+                    "Failed to delay the retry of webhook {:?}: {}",
+                    message.message_id, e
+                );
+            }
+        })
+    });
+
+    join_all(changes).await;
+}
+
+/// How long a message that failed its `receive_count`th attempt waits for the next one
+fn retry_delay(receive_count: u32) -> Duration {
+    4u32.checked_pow(receive_count.saturating_sub(1))
+        .and_then(|factor| INITIAL_RETRY_DELAY.checked_mul(factor))
+        .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aws_lambda_events::sqs::{SqsEvent, SqsMessage};
+
+    use crate::{
+        mocks::MockWebhookDelivery,
+        models::{WebhookSubscription, WebhookSubscriptions},
+        InMemoryPackageRepository, Repository,
+    };
+
+    use super::{deliver_messages, retry_delay};
+
+    fn message(id: &str, body: String) -> SqsMessage {
+        SqsMessage {
+            message_id: Some(id.into()),
+            body: Some(body),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_messages_that_were_not_delivered() {
+        let webhook_repo = InMemoryPackageRepository::default();
+        let mut delivery = MockWebhookDelivery::new();
+
+        let subscriptions = ["https://indexer.example.com", "https://cdn.example.com"]
+            .map(|url| WebhookSubscription::new(url.into(), "secret".into()));
+        webhook_repo
+            .update(&WebhookSubscriptions {
+                id: "webhooks".into(),
+                subscriptions: subscriptions.to_vec(),
+                revision: 0,
+            })
+            .await
+            .unwrap();
+
+        delivery
+            .expect_deliver()
+            .times(2)
+            .returning(|url, _, _, _| match url {
+                "https://indexer.example.com" => Ok(()),
+                _ => Err("Service unavailable".into()),
+            });
+
+        let body = |subscription: &WebhookSubscription| {
+            format!(
+                r#"{{"scope_key":"webhooks","subscription_id":"{}","body":"{{}}"}}"#,
+                subscription.id
+            )
+        };
+        let event = SqsEvent {
+            records: vec![
+                message("1", body(&subscriptions[0])),
+                message("2", body(&subscriptions[1])),
+                message("3", "not a delivery".into()),
+            ],
+        };
+
+        let failed = deliver_messages(event, &webhook_repo, &delivery).await;
+
+        assert_eq!(
+            failed
+                .iter()
+                .map(|message| message.message_id.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("2"), Some("3")]
+        );
+    }
+
+    #[test]
+    fn retries_wait_longer_after_every_attempt() {
+        assert_eq!(
+            (1..=5).map(retry_delay).collect::<Vec<_>>(),
+            [30, 120, 480, 1920, 7680].map(Duration::from_secs)
+        );
+        // Receive counts start at 1, 0 waits as long as the first retry
+        assert_eq!(retry_delay(0), Duration::from_secs(30));
+        assert_eq!(retry_delay(8), Duration::from_secs(12 * 60 * 60));
+        assert_eq!(retry_delay(u32::MAX), Duration::from_secs(12 * 60 * 60));
+    }
+}
@@ -0,0 +1,30 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum FetchError {
+    NotFound,
+    /// The fetcher can't fetch URIs of this authority
+    UnsupportedUri(String),
+    /// The URI points to an internal address
+    Forbidden(String),
+    /// The file is larger than the given number of bytes
+    TooLarge(usize),
+    Unknown(String),
+}
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::NotFound => write!(f, "Not found"),
+            FetchError::UnsupportedUri(authority) => {
+                write!(
+                    f,
+                    "URIs with the `{}` authority can't be fetched",
+                    authority
+                )
+            }
+            FetchError::Forbidden(e) => write!(f, "{}", e),
+            FetchError::TooLarge(max_size) => write!(f, "File is larger than {} bytes", max_size),
+            FetchError::Unknown(e) => write!(f, "Fetch error: {}", e),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+use crate::models::WrapUri;
+
+use super::{FetchError, WrapFetcher};
+
+/// Fetches with the first fetcher, falling back to the second for URIs the first doesn't support.
+pub struct FallbackWrapFetcher<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> FallbackWrapFetcher<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+#[async_trait]
+impl<A, B> WrapFetcher for FallbackWrapFetcher<A, B>
+where
+    A: WrapFetcher + Sync,
+    B: WrapFetcher + Sync,
+{
+    async fn fetch_manifest(&self, uri: &WrapUri) -> Result<Vec<u8>, FetchError> {
+        match self.first.fetch_manifest(uri).await {
+            Err(FetchError::UnsupportedUri(_)) => self.second.fetch_manifest(uri).await,
+            result => result,
+        }
+    }
+}
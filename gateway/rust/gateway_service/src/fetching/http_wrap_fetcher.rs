@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use http::StatusCode;
+
+use crate::{
+    http_utils::{check_public_url, public_client_builder},
+    models::{UriAuthority, WrapUri},
+};
+
+use super::{FetchError, WrapFetcher};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Manifests are small, a larger response is not read any further
+pub(super) const MAX_MANIFEST_SIZE: usize = 1024 * 1024;
+
+/// Fetches wraps of `http` and `https` URIs from the server they point to.
+/// Servers at internal addresses can't be fetched from, see `public_client_builder`.
+pub struct HttpWrapFetcher {
+    client: reqwest::Client,
+}
+
+impl HttpWrapFetcher {
+    pub fn new() -> Self {
+        Self {
+            client: public_client_builder()
+                .build()
+                .expect("Failed to build the HTTP client"),
+        }
+    }
+}
+
+impl Default for HttpWrapFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WrapFetcher for HttpWrapFetcher {
+    async fn fetch_manifest(&self, uri: &WrapUri) -> Result<Vec<u8>, FetchError> {
+        let authority = UriAuthority::of(uri);
+
+        if !matches!(authority, UriAuthority::Http | UriAuthority::Https) {
+            return Err(FetchError::UnsupportedUri(authority.to_string()));
+        }
+
+        let url = format!("{}://{}", authority, manifest_path(uri.path()));
+        check_public_url(&url).map_err(FetchError::Forbidden)?;
+
+        fetch_file(&self.client, &url).await
+    }
+}
+
+pub(super) fn manifest_path(path: &str) -> String {
+    format!("{}/wrap.info", path.trim_end_matches('/'))
+}
+
+/// Reads the file in chunks, and stops once it's larger than `MAX_MANIFEST_SIZE`
+pub(super) async fn fetch_file(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, FetchError> {
+    let mut response = client
+        .get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| FetchError::Unknown(e.to_string()))?;
+
+    match response.status() {
+        StatusCode::NOT_FOUND => Err(FetchError::NotFound),
+        status if status.is_success() => {
+            if matches!(response.content_length(), Some(length) if length > MAX_MANIFEST_SIZE as u64)
+            {
+                return Err(FetchError::TooLarge(MAX_MANIFEST_SIZE));
+            }
+
+            let mut file = vec![];
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| FetchError::Unknown(e.to_string()))?
+            {
+                if file.len() + chunk.len() > MAX_MANIFEST_SIZE {
+                    return Err(FetchError::TooLarge(MAX_MANIFEST_SIZE));
+                }
+                file.extend_from_slice(&chunk);
+            }
+
+            Ok(file)
+        }
+        status => Err(FetchError::Unknown(format!(
+            "{} responded with {}",
+            url, status
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fetching::{FetchError, HttpWrapFetcher, WrapFetcher};
+
+    #[tokio::test]
+    async fn does_not_fetch_from_internal_addresses() {
+        let fetcher = HttpWrapFetcher::new();
+
+        for uri in [
+            "wrap://https/localhost/wrap",
+            "wrap://http/169.254.169.254/latest",
+            "wrap://http/10.0.0.1:8080/wrap",
+        ] {
+            let result = fetcher.fetch_manifest(&uri.parse().unwrap()).await;
+
+            assert!(matches!(result, Err(FetchError::Forbidden(_))), "{}", uri);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use crate::models::WrapUri;
+
+use super::{FetchError, WrapFetcher};
+
+/// Serves manifests from memory, keyed by URI.
+#[derive(Default)]
+pub struct InMemoryWrapFetcher {
+    manifests: HashMap<String, Vec<u8>>,
+}
+
+impl InMemoryWrapFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_manifest(mut self, uri: &str, manifest: Vec<u8>) -> Self {
+        let uri: WrapUri = uri.parse().unwrap();
+        self.manifests.insert(uri.to_string(), manifest);
+        self
+    }
+}
+
+#[async_trait]
+impl WrapFetcher for InMemoryWrapFetcher {
+    async fn fetch_manifest(&self, uri: &WrapUri) -> Result<Vec<u8>, FetchError> {
+        self.manifests
+            .get(&uri.to_string())
+            .cloned()
+            .ok_or(FetchError::NotFound)
+    }
+}
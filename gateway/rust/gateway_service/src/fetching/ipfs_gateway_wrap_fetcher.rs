@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+
+use crate::models::{UriAuthority, WrapUri};
+
+use super::{
+    http_wrap_fetcher::{fetch_file, manifest_path},
+    FetchError, WrapFetcher,
+};
+
+/// Fetches wraps of `ipfs` URIs through an IPFS HTTP gateway, e.g. `https://ipfs.io`.
+pub struct IpfsGatewayWrapFetcher {
+    gateway_url: String,
+    client: reqwest::Client,
+}
+
+impl IpfsGatewayWrapFetcher {
+    pub fn new(gateway_url: String) -> Self {
+        Self {
+            gateway_url: gateway_url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl WrapFetcher for IpfsGatewayWrapFetcher {
+    async fn fetch_manifest(&self, uri: &WrapUri) -> Result<Vec<u8>, FetchError> {
+        let authority = UriAuthority::of(uri);
+
+        if authority != UriAuthority::Ipfs {
+            return Err(FetchError::UnsupportedUri(authority.to_string()));
+        }
+
+        let url = format!("{}/ipfs/{}", self.gateway_url, manifest_path(uri.path()));
+
+        fetch_file(&self.client, &url).await
+    }
+}
@@ -0,0 +1,19 @@
+pub mod error;
+pub use error::*;
+
+mod wrap_fetcher;
+pub use wrap_fetcher::*;
+
+mod http_wrap_fetcher;
+pub use http_wrap_fetcher::HttpWrapFetcher;
+
+mod ipfs_gateway_wrap_fetcher;
+pub use ipfs_gateway_wrap_fetcher::IpfsGatewayWrapFetcher;
+
+mod fallback_wrap_fetcher;
+pub use fallback_wrap_fetcher::FallbackWrapFetcher;
+
+#[cfg(test)]
+mod in_memory_wrap_fetcher;
+#[cfg(test)]
+pub use in_memory_wrap_fetcher::InMemoryWrapFetcher;
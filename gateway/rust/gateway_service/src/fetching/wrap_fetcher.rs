@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+
+use crate::models::WrapUri;
+
+use super::FetchError;
+
+/// Fetches the content a WRAP URI points to.
+#[async_trait]
+pub trait WrapFetcher {
+    /// Fetches the msgpack encoded `wrap.info` of the wrap
+    async fn fetch_manifest(&self, uri: &WrapUri) -> Result<Vec<u8>, FetchError>;
+}
@@ -0,0 +1,166 @@
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{verify_user_key, ApiError},
+    models::{
+        AuditAction, AuditContext, KeyIdSecret, Package, PackageName, Username,
+        WebhookSubscriptions,
+    },
+    transferring::accept_transfer as move_package,
+    AccountService, AuditRepository, MoveRepository, Repository,
+};
+
+use super::{audit::audited, request_transfer::transfer_error};
+
+/// Completes a transfer, authenticated as the recipient rather than the owner
+#[allow(clippy::too_many_arguments)]
+pub async fn accept_transfer(
+    user: String,
+    package: String,
+    to: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package> + Repository<WebhookSubscriptions> + MoveRepository,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<String, ApiError> {
+    debug!(&user, &package, &to, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let recipient: Username = to
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&recipient, &api_key, &account_service).await?;
+
+    debug_println!("Moving {:?} to {:?}", &package_name, &recipient);
+
+    // Recorded under both ids, so the history of either id shows where the package went
+    let old_id = Package::id_of(&username, &package_name);
+    let new_id = Package::id_of(&recipient, &package_name);
+    let context = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    );
+    let details = Some(format!("{} -> {}", old_id, new_id));
+    let entries = vec![
+        context.change(old_id, AuditAction::Transfer, details.clone()),
+        context.change(new_id, AuditAction::Transfer, details),
+    ];
+
+    let package = audited(
+        &audit_repo,
+        entries.clone(),
+        async {
+            move_package(
+                &username,
+                &package_name,
+                &recipient,
+                package_repo,
+                &audit_repo,
+            )
+            .await
+            .map_err(log_error)
+            .map_err(transfer_error)
+        },
+        |_| entries,
+    )
+    .await?;
+
+    Ok(package.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::{
+        functions::accept_transfer,
+        mocks::MockAccountService,
+        models::{AuditAction, KeyIdSecret, Username},
+        AuditRepository, InMemoryPackageRepository, KeyValidationError, Package, Repository,
+    };
+
+    #[tokio::test]
+    async fn requires_key_of_recipient() {
+        let package_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("user2".parse::<Username>().unwrap()), eq("key1"))
+            .return_once(|_, _| Err(KeyValidationError::Invalid));
+
+        let result = accept_transfer(
+            "user1".into(),
+            "package1".into(),
+            "user2".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            package_repo,
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn returns_new_id() {
+        let package_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("user2".parse::<Username>().unwrap()), eq("key2"))
+            .return_once(|_, _| Ok(()));
+
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.pending_transfer = Some("user2".parse().unwrap());
+        package_repo.update(&package).await.unwrap();
+
+        let audit_repo = InMemoryPackageRepository::default();
+
+        let result = accept_transfer(
+            "user1".into(),
+            "package1".into(),
+            "user2".into(),
+            "key2".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            package_repo,
+            audit_repo.clone(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result, Ok("user2/package1".to_string()));
+
+        for id in ["user1/package1", "user2/package1"] {
+            let entries = audit_repo.list(id).await.unwrap();
+
+            assert_eq!(entries.len(), 1, "{}", id);
+            assert_eq!(entries[0].action, AuditAction::Transfer);
+            assert_eq!(entries[0].username, "user2".parse::<Username>().unwrap());
+            assert_eq!(
+                entries[0].details,
+                Some("user1/package1 -> user2/package1".into())
+            );
+        }
+    }
+}
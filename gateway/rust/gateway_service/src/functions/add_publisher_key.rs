@@ -0,0 +1,76 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{PublisherKey, PublisherKeys, Username},
+    signing::{self, SigningError},
+    AccountService, Repository,
+};
+
+pub async fn add_publisher_key(
+    user: String,
+    public_key: String,
+    api_key: String,
+    key_repo: impl Repository<PublisherKeys>,
+    account_service: impl AccountService,
+) -> Result<PublisherKey, ApiError> {
+    debug!(&user, &public_key, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Adding publisher key of {}", &username);
+
+    signing::add_publisher_key(&username, &public_key, key_repo)
+        .await
+        .map_err(log_error)
+        .map_err(|e| match e {
+            e @ SigningError::InvalidPublicKey => ApiError::bad_request(e),
+            e @ SigningError::Conflict => ApiError::new(StatusCode::CONFLICT, e),
+            e => internal_server_error(e).into(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::{
+        functions::add_publisher_key,
+        mocks::MockAccountService,
+        models::{PublisherKeys, Username},
+        InMemoryPackageRepository, KeyValidationError, Repository,
+    };
+
+    #[tokio::test]
+    async fn forbids_adding_keys_of_other_users() {
+        let key_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("user1".parse::<Username>().unwrap()), eq("key2"))
+            .return_once(|_, _| Err(KeyValidationError::Invalid));
+
+        let result = add_publisher_key(
+            "user1".into(),
+            "6oLoCwVqSJX1NDJj5P0O6N0SQVP1BmHX9o2tUw0LfXw=".into(),
+            "key2".into(),
+            key_repo.clone(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::UNAUTHORIZED));
+        assert!(Repository::<PublisherKeys>::read(&key_repo, "keys:user1")
+            .await
+            .is_err());
+    }
+}
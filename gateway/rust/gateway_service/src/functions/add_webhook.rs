@@ -0,0 +1,120 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    http_utils::{check_public_url, internal_server_error, verify_user_key, ApiError},
+    models::WebhookSubscriptions,
+    webhooks::{add_subscription, WebhookError},
+    AccountService, Repository,
+};
+
+use super::webhooks::{parse_webhook_scope, WebhookInfo};
+
+const MIN_SECRET_LENGTH: usize = 16;
+const MAX_SECRET_LENGTH: usize = 256;
+
+pub async fn add_webhook(
+    user_and_package: Option<(String, String)>,
+    url: String,
+    secret: String,
+    api_key: String,
+    webhook_repo: impl Repository<WebhookSubscriptions>,
+    account_service: impl AccountService,
+) -> Result<WebhookInfo, ApiError> {
+    debug!(&user_and_package, &url, &api_key);
+
+    let (username, scope) = parse_webhook_scope(user_and_package)?;
+
+    check_public_url(&url).map_err(ApiError::bad_request)?;
+
+    if secret.len() < MIN_SECRET_LENGTH || secret.len() > MAX_SECRET_LENGTH {
+        return Err(ApiError::bad_request(format!(
+            "Webhook secret must be between {} and {} characters",
+            MIN_SECRET_LENGTH, MAX_SECRET_LENGTH
+        )));
+    }
+
+    verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Adding webhook {} to {:?}", &url, &scope);
+
+    let subscription = add_subscription(&scope, url, secret, webhook_repo)
+        .await
+        .map_err(|e| match e {
+            WebhookError::Conflict => StatusCode::CONFLICT,
+            e => internal_server_error(e),
+        })?;
+
+    Ok(subscription.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::{
+        functions::add_webhook,
+        mocks::MockAccountService,
+        models::{Username, WebhookSubscriptions},
+        InMemoryPackageRepository, Repository,
+    };
+
+    #[tokio::test]
+    async fn registry_webhooks_require_registry_account() {
+        let webhook_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("polywrap".parse::<Username>().unwrap()), eq("key1"))
+            .return_once(|_, _| Ok(()));
+
+        let webhook = add_webhook(
+            None,
+            "https://indexer.example.com/hook".into(),
+            "0123456789abcdef".into(),
+            "key1".into(),
+            webhook_repo.clone(),
+            account_service,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(webhook.url, "https://indexer.example.com/hook");
+
+        let subscriptions: WebhookSubscriptions = webhook_repo.read("webhooks").await.unwrap();
+        assert_eq!(subscriptions.subscriptions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn forbids_invalid_url_and_short_secret() {
+        for (url, secret) in [
+            ("ftp://example.com/hook", "0123456789abcdef"),
+            ("example.com/hook", "0123456789abcdef"),
+            (
+                "http://169.254.169.254/latest/meta-data",
+                "0123456789abcdef",
+            ),
+            ("http://localhost:8080/hook", "0123456789abcdef"),
+            ("http://[fd00:ec2::254]/hook", "0123456789abcdef"),
+            ("https://example.com/hook", "secret"),
+        ] {
+            let mut account_service = MockAccountService::new();
+
+            account_service.expect_verify_user_key().never();
+
+            let result = add_webhook(
+                Some(("user1".into(), "package1".into())),
+                url.into(),
+                secret.into(),
+                "key1".into(),
+                InMemoryPackageRepository::default(),
+                account_service,
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::future::Future;
+
+use axum::http::StatusCode;
+
+use crate::{
+    debugging::log_error,
+    http_utils::{internal_server_error, ApiError},
+    models::AuditEntry,
+    AuditRepository,
+};
+
+/// Every change is recorded, so its entries are stored as pending before `change` makes it, and
+/// a change whose entries can't be stored isn't made. Once it's made, the entries `made` returns
+/// for its result are committed in their place, see `commit_audit`. The entries of a rejected
+/// change are removed, see `reject_audit`.
+pub(super) async fn audited<T>(
+    audit_repo: &impl AuditRepository,
+    entries: impl IntoIterator<Item = AuditEntry>,
+    change: impl Future<Output = Result<T, ApiError>>,
+    made: impl FnOnce(&T) -> Vec<AuditEntry>,
+) -> Result<T, ApiError> {
+    let pending = begin_audit(audit_repo, entries).await?;
+
+    match change.await {
+        Ok(result) => {
+            commit_audit(audit_repo, pending, made(&result)).await?;
+            Ok(result)
+        }
+        Err(error) => Err(reject_audit(audit_repo, pending, error).await),
+    }
+}
+
+/// Stores the entries of a change as pending, before it's made. Fails if one of them can't be
+/// stored, the ones that were stored are removed then.
+pub(super) async fn begin_audit(
+    audit_repo: &impl AuditRepository,
+    entries: impl IntoIterator<Item = AuditEntry>,
+) -> Result<Vec<AuditEntry>, StatusCode> {
+    let mut pending = vec![];
+
+    for entry in entries {
+        let entry = AuditEntry {
+            pending: true,
+            ..entry
+        };
+        // e.g. a batch that publishes a version twice, which fails anyway
+        if pending.iter().any(|p| is_same_entry(p, &entry)) {
+            continue;
+        }
+
+        if let Err(e) = audit_repo.append(&entry).await {
+            remove_pending(audit_repo, &pending).await;
+
+            return Err(internal_server_error(format!(
+                "Failed to record audit entry {:?}: {}",
+                entry, e
+            )));
+        }
+        pending.push(entry);
+    }
+
+    Ok(pending)
+}
+
+/// Commits the entries of a change that was made in place of the pending entries with the same
+/// ids. Pending entries that aren't committed, e.g. of a change that turned out to change nothing,
+/// are removed. Fails if an entry can't be committed: the change is already made by then, so its
+/// entry stays pending, a retry with the same idempotency key replays the response.
+pub(super) async fn commit_audit(
+    audit_repo: &impl AuditRepository,
+    pending: Vec<AuditEntry>,
+    made: Vec<AuditEntry>,
+) -> Result<(), StatusCode> {
+    let mut failures = vec![];
+
+    for entry in &made {
+        let entry = AuditEntry {
+            pending: false,
+            ..entry.clone()
+        };
+
+        if let Err(e) = audit_repo.commit(&entry).await {
+            failures.push(format!("{:?}: {}", entry, e));
+        }
+    }
+
+    let uncommitted = pending
+        .into_iter()
+        .filter(|p| !made.iter().any(|e| is_same_entry(p, e)))
+        .collect::<Vec<_>>();
+    remove_pending(audit_repo, &uncommitted).await;
+
+    match failures.is_empty() {
+        true => Ok(()),
+        false => Err(internal_server_error(format!(
+            "Failed to record audit entries {}",
+            failures.join(", ")
+        ))),
+    }
+}
+
+/// Removes the pending entries of a change that failed with `error`, and returns it. After a
+/// server error it isn't known whether the change was made, so its entries stay pending.
+pub(super) async fn reject_audit(
+    audit_repo: &impl AuditRepository,
+    pending: Vec<AuditEntry>,
+    error: ApiError,
+) -> ApiError {
+    if !error.status.is_server_error() {
+        remove_pending(audit_repo, &pending).await;
+    }
+
+    error
+}
+
+fn is_same_entry(a: &AuditEntry, b: &AuditEntry) -> bool {
+    a.package_id == b.package_id && a.entry_id() == b.entry_id()
+}
+
+/// A pending entry that can't be removed only stays pending
+async fn remove_pending(audit_repo: &impl AuditRepository, pending: &[AuditEntry]) {
+    for entry in pending {
+        let _ = audit_repo.remove_pending(entry).await.map_err(log_error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use axum::http::StatusCode;
+
+    use crate::{
+        http_utils::ApiError,
+        models::{AuditAction, AuditContext, AuditEntry},
+        AuditPage, AuditRepository, InMemoryPackageRepository, RepositoryError,
+    };
+
+    use super::audited;
+
+    struct FailingAuditRepository;
+
+    #[async_trait]
+    impl AuditRepository for FailingAuditRepository {
+        async fn append(&self, _entry: &AuditEntry) -> Result<(), RepositoryError> {
+            Err(RepositoryError::Unknown("unavailable".into()))
+        }
+
+        async fn commit(&self, _entry: &AuditEntry) -> Result<(), RepositoryError> {
+            Err(RepositoryError::Unknown("unavailable".into()))
+        }
+
+        async fn remove_pending(&self, _entry: &AuditEntry) -> Result<(), RepositoryError> {
+            Err(RepositoryError::Unknown("unavailable".into()))
+        }
+
+        async fn list(&self, _package_id: &str) -> Result<Vec<AuditEntry>, RepositoryError> {
+            Ok(vec![])
+        }
+
+        async fn list_page(
+            &self,
+            _package_id: &str,
+            _after: Option<&str>,
+            _limit: usize,
+        ) -> Result<AuditPage, RepositoryError> {
+            Ok(AuditPage::of(&[], None, 0))
+        }
+    }
+
+    fn context() -> AuditContext {
+        AuditContext::new("user1".parse().unwrap(), "key1".into(), "request1".into())
+    }
+
+    fn entry(context: &AuditContext, details: &str) -> AuditEntry {
+        context.change(
+            "user1/package1".into(),
+            AuditAction::UpdateMetadata,
+            Some(details.into()),
+        )
+    }
+
+    #[tokio::test]
+    async fn does_not_make_a_change_that_cannot_be_recorded() {
+        let mut made = false;
+
+        let result = audited(
+            &FailingAuditRepository,
+            [entry(&context(), "before")],
+            async {
+                made = true;
+                Ok(())
+            },
+            |_| vec![],
+        )
+        .await;
+
+        assert_eq!(
+            result.map_err(|e| e.status),
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        );
+        assert!(!made);
+    }
+
+    #[tokio::test]
+    async fn commits_entries_of_changes_that_were_made() {
+        let audit_repo = InMemoryPackageRepository::default();
+        let context = context();
+
+        audited(
+            &audit_repo,
+            [entry(&context, "before")],
+            async {
+                // Pending while the change is made
+                let entries = audit_repo.list("user1/package1").await.unwrap();
+                assert!(entries[0].pending);
+                Ok::<_, ApiError>(())
+            },
+            |_| vec![entry(&context, "after")],
+        )
+        .await
+        .unwrap();
+
+        let entries = audit_repo.list("user1/package1").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(!entries[0].pending);
+        assert_eq!(entries[0].details, Some("after".into()));
+    }
+
+    #[tokio::test]
+    async fn removes_entries_of_rejected_changes_only() {
+        let audit_repo = InMemoryPackageRepository::default();
+
+        for (status, kept) in [
+            (StatusCode::CONFLICT, 0),
+            (StatusCode::INTERNAL_SERVER_ERROR, 1),
+        ] {
+            let result = audited(
+                &audit_repo,
+                [entry(&context(), "before")],
+                async { Err::<(), _>(ApiError::from(status)) },
+                |_| vec![],
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(status));
+            assert_eq!(audit_repo.list("user1/package1").await.unwrap().len(), kept);
+        }
+
+        // It isn't known whether the change was made
+        assert!(audit_repo.list("user1/package1").await.unwrap()[0].pending);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{
+    debug,
+    debugging::log_error,
+    http_utils::{internal_server_error, ApiError},
+    models::{Package, PackageName, Username},
+    AuditPage, AuditRepository,
+};
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Lists the audit entries of a package a page at a time, oldest first
+pub async fn audit_log(
+    user: String,
+    package: String,
+    after: Option<String>,
+    limit: Option<usize>,
+    audit_repo: impl AuditRepository,
+) -> Result<AuditPage, ApiError> {
+    debug!(&user, &package, &after, &limit);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ApiError::bad_request(format!(
+            "Limit must be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+
+    let page = audit_repo
+        .list_page(
+            &Package::id_of(&username, &package_name),
+            after.as_deref(),
+            limit,
+        )
+        .await
+        .map_err(internal_server_error)?;
+
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use crate::{
+        functions::audit_log,
+        models::{AuditAction, AuditContext},
+        AuditPage, AuditRepository, InMemoryPackageRepository,
+    };
+
+    #[tokio::test]
+    async fn lists_audit_entries_of_package() {
+        let entry = AuditContext::new("user1".parse().unwrap(), "key1".into(), "request1".into())
+            .entry(
+                "user1/package1".into(),
+                AuditAction::OverwriteLatest,
+                "latest".parse().unwrap(),
+                Some("test/uri1".parse().unwrap()),
+                Some("test/uri2".parse().unwrap()),
+            );
+        let other = AuditContext::new("user1".parse().unwrap(), "key1".into(), "request2".into())
+            .entry(
+                "user1/package2".into(),
+                AuditAction::OverwriteLatest,
+                "latest".parse().unwrap(),
+                None,
+                Some("test/uri3".parse().unwrap()),
+            );
+
+        let audit_repo = InMemoryPackageRepository::default();
+        audit_repo.append(&entry).await.unwrap();
+        audit_repo.append(&other).await.unwrap();
+
+        let result = audit_log("user1".into(), "package1".into(), None, None, audit_repo).await;
+
+        assert_eq!(
+            result.map_err(|e| e.status),
+            Ok(AuditPage {
+                entries: vec![entry],
+                next: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_page_of_audit_entries() {
+        let context = AuditContext::new("user1".parse().unwrap(), "key1".into(), "request1".into());
+        let audit_repo = InMemoryPackageRepository::default();
+        let mut entries = vec![];
+        for created_on in 1..=3 {
+            let mut entry =
+                context.change("user1/package1".into(), AuditAction::UpdateMetadata, None);
+            entry.created_on = created_on;
+            audit_repo.append(&entry).await.unwrap();
+            entries.push(entry);
+        }
+
+        let result = audit_log(
+            "user1".into(),
+            "package1".into(),
+            Some(entries[0].entry_id()),
+            Some(1),
+            audit_repo,
+        )
+        .await;
+
+        assert_eq!(
+            result.map_err(|e| e.status),
+            Ok(AuditPage {
+                entries: vec![entries[1].clone()],
+                next: Some(entries[1].entry_id()),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_limit() {
+        for limit in [0, 101] {
+            let result = audit_log(
+                "user1".into(),
+                "package1".into(),
+                None,
+                Some(limit),
+                InMemoryPackageRepository::default(),
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{AuditAction, AuditContext, KeyIdSecret, Organization, ReservedNames, Username},
+    organizations::{create_organization as create, OrgError},
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+
+/// Authenticated with the key of the namespace itself, which is what the organization replaces.
+/// Organizations are stored by their lowercase name, so the name is made lowercase before the
+/// key is checked: an account whose name only differs in casing can't claim the namespace.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_organization(
+    org: String,
+    owner: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    reserved_names: ReservedNames,
+    org_repo: impl Repository<Organization>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<Organization, ApiError> {
+    debug!(&org, &owner, &api_key);
+
+    let org: Username = org
+        .to_ascii_lowercase()
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let owner: Username = owner
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&org, &api_key, &account_service).await?;
+
+    debug_println!("Creating organization {:?} owned by {:?}", &org, &owner);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Organization::key(&org),
+        AuditAction::CreateOrganization,
+        Some(owner.to_string()),
+    );
+
+    let organization = audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            create(&org, &owner, &reserved_names, org_repo)
+                .await
+                .map_err(log_error)
+                .map_err(org_error)
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(organization)
+}
+
+pub(super) fn org_error(error: OrgError) -> ApiError {
+    match error {
+        OrgError::OrganizationNotFound => StatusCode::NOT_FOUND.into(),
+        e @ OrgError::MemberNotFound => ApiError::new(StatusCode::NOT_FOUND, e),
+        e @ OrgError::LastOwner => ApiError::bad_request(e),
+        e @ OrgError::ReservedName(_) => ApiError::new(StatusCode::FORBIDDEN, e),
+        e @ (OrgError::OrganizationExists | OrgError::ConfusableName(_) | OrgError::Conflict) => {
+            ApiError::new(StatusCode::CONFLICT, e)
+        }
+        OrgError::RepositoryError(e) => internal_server_error(e).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::{
+        functions::create_organization,
+        mocks::MockAccountService,
+        models::{KeyIdSecret, Organization, ReservedNames, Username},
+        InMemoryPackageRepository, KeyValidationError, Repository,
+    };
+
+    #[tokio::test]
+    async fn requires_key_of_namespace() {
+        let org_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("org1".parse::<Username>().unwrap()), eq("key1"))
+            .return_once(|_, _| Err(KeyValidationError::Invalid));
+
+        let result = create_organization(
+            "org1".into(),
+            "user1".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            Default::default(),
+            org_repo.clone(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(
+            result.map(|_| ()).map_err(|e| e.status),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert!(Repository::<Organization>::read(&org_repo, "org:org1")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn cannot_create_twice() {
+        let org_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .return_once(|_, _| Ok(()));
+
+        org_repo
+            .update(&Organization::new(
+                "org1".parse().unwrap(),
+                "user2".parse().unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let result = create_organization(
+            "org1".into(),
+            "user1".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            Default::default(),
+            org_repo.clone(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(
+            result.map(|_| ()).map_err(|e| e.status),
+            Err(StatusCode::CONFLICT)
+        );
+
+        let org: Organization = org_repo.read("org:org1").await.unwrap();
+        assert_eq!(org.members.len(), 1);
+        assert_eq!(org.members[0].username, "user2".parse().unwrap());
+    }
+
+    #[tokio::test]
+    async fn requires_key_of_lowercase_namespace() {
+        let org_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("org1".parse::<Username>().unwrap()), eq("key1"))
+            .return_once(|_, _| Err(KeyValidationError::Invalid));
+
+        let result = create_organization(
+            "Org1".into(),
+            "user1".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            Default::default(),
+            org_repo.clone(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(
+            result.map(|_| ()).map_err(|e| e.status),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert!(Repository::<Organization>::read(&org_repo, "org:org1")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn forbids_reserved_names() {
+        let org_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .return_once(|_, _| Ok(()));
+
+        let result = create_organization(
+            "Admin".into(),
+            "user1".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            ReservedNames::new("polywrap,admin", "polywrap"),
+            org_repo.clone(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(
+            result.map(|_| ()).map_err(|e| e.status),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert!(Repository::<Organization>::read(&org_repo, "org:admin")
+            .await
+            .is_err());
+    }
+}
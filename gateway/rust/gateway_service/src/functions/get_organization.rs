@@ -0,0 +1,24 @@
+use crate::{
+    debug,
+    debugging::log_error,
+    http_utils::ApiError,
+    models::{Organization, Username},
+    organizations::get_organization as get,
+    Repository,
+};
+
+use super::create_organization::org_error;
+
+pub async fn get_organization(
+    org: String,
+    org_repo: impl Repository<Organization>,
+) -> Result<Organization, ApiError> {
+    debug!(&org);
+
+    let org: Username = org
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    get(&org, &org_repo).await.map_err(org_error)
+}
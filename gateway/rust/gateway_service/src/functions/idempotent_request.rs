@@ -0,0 +1,180 @@
+use std::{future::Future, time::Duration};
+
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    idempotency::{begin_request, complete_request, IdempotencyError, IdempotentRequest},
+    models::{IdempotencyRecord, StoredResponse, Username},
+    AccountService, Repository,
+};
+
+/// Runs a request once per idempotency key of the user, retries of the same request get its response again.
+/// Only requests with a valid API key claim a key, so nobody else can use up the keys of a user.
+/// Conflicts and server errors aren't stored, the key is released so a retry runs the request again,
+/// which is safe since publishing a version that is already stored changes nothing.
+#[allow(clippy::too_many_arguments)]
+pub async fn idempotent_request(
+    user: String,
+    idempotency_key: String,
+    request_hash: String,
+    ttl: Duration,
+    api_key: &str,
+    account_service: &impl AccountService,
+    idempotency_repo: impl Repository<IdempotencyRecord>,
+    request: impl Future<Output = Result<StoredResponse, ApiError>>,
+) -> Result<StoredResponse, ApiError> {
+    debug!(&user, &idempotency_key, &request_hash);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    verify_user_key(&username, api_key, account_service).await?;
+
+    let id = IdempotencyRecord::key(&username, &idempotency_key);
+
+    let begun = begin_request(&id, &request_hash, ttl, &idempotency_repo)
+        .await
+        .map_err(log_error)
+        .map_err(|e| match e {
+            e @ IdempotencyError::KeyReused => ApiError::new(StatusCode::UNPROCESSABLE_ENTITY, e),
+            e @ IdempotencyError::InProgress => ApiError::new(StatusCode::CONFLICT, e),
+            IdempotencyError::RepositoryError(e) => internal_server_error(e).into(),
+        })?;
+
+    if let IdempotentRequest::Replay(response) = begun {
+        debug_println!("Replaying response of idempotency key {}", &idempotency_key);
+        return Ok(response);
+    }
+
+    let response = request.await.unwrap_or_else(StoredResponse::from);
+
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let stored_response = match status {
+        status if status.is_server_error() => None,
+        StatusCode::CONFLICT | StatusCode::UNAUTHORIZED => None,
+        _ => Some(response.clone()),
+    };
+
+    // The request already ran, if its outcome can't be stored a retry runs it again once the lease expired
+    if let Err(e) = complete_request(&id, stored_response, &idempotency_repo).await {
+        eprintln!(
+            "Failed to store the response of an idempotent request: {}",
+            e
+        );
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::http::StatusCode;
+
+    use crate::{
+        functions::idempotent_request,
+        http_utils::ApiError,
+        models::{IdempotencyRecord, StoredResponse},
+        InMemoryPackageRepository, Repository, SingleAccountService,
+    };
+
+    const TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn account_service() -> SingleAccountService {
+        SingleAccountService::new("user1".parse().unwrap(), "api_key1".into())
+    }
+
+    #[tokio::test]
+    async fn stores_client_errors_except_conflicts_and_failed_authentication() {
+        for (error, is_stored) in [
+            (ApiError::bad_request("Invalid version"), true),
+            (ApiError::from(StatusCode::INTERNAL_SERVER_ERROR), false),
+            (ApiError::from(StatusCode::BAD_GATEWAY), false),
+            (ApiError::from(StatusCode::CONFLICT), false),
+            (ApiError::from(StatusCode::UNAUTHORIZED), false),
+        ] {
+            let status = error.status.as_u16();
+            let idempotency_repo = InMemoryPackageRepository::default();
+
+            let response = idempotent_request(
+                "user1".into(),
+                "key1".into(),
+                "hash1".into(),
+                TTL,
+                "api_key1",
+                &account_service(),
+                idempotency_repo.clone(),
+                async { Err::<StoredResponse, _>(error) },
+            )
+            .await;
+
+            assert_eq!(response.map(|r| r.status), Ok(status));
+
+            // Records that aren't stored expire right away, freeing the key for a retry
+            let record: Option<IdempotencyRecord> =
+                idempotency_repo.read("idempotency:user1:key1").await.ok();
+            assert_eq!(
+                record.and_then(|r| r.response).map(|r| r.status),
+                is_stored.then_some(status)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_a_request_again_after_a_server_error() {
+        let idempotency_repo = InMemoryPackageRepository::default();
+
+        for (response, expected) in [
+            (Err(ApiError::from(StatusCode::BAD_GATEWAY)), 502),
+            (Ok(StoredResponse::empty(StatusCode::OK)), 200),
+            (Err(ApiError::from(StatusCode::BAD_GATEWAY)), 200),
+        ] {
+            let response = idempotent_request(
+                "user1".into(),
+                "key1".into(),
+                "hash1".into(),
+                TTL,
+                "api_key1",
+                &account_service(),
+                idempotency_repo.clone(),
+                async { response },
+            )
+            .await;
+
+            assert_eq!(response.map(|r| r.status), Ok(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn only_authenticated_requests_claim_a_key() {
+        let idempotency_repo = InMemoryPackageRepository::default();
+
+        let response = idempotent_request(
+            "user1".into(),
+            "key1".into(),
+            "hash1".into(),
+            TTL,
+            "api_key2",
+            &account_service(),
+            idempotency_repo.clone(),
+            async { panic!("The request must not run") },
+        )
+        .await;
+
+        assert_eq!(
+            response.map_err(|e| e.status),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert!(
+            Repository::<IdempotencyRecord>::read(&idempotency_repo, "idempotency:user1:key1")
+                .await
+                .is_err()
+        );
+    }
+}
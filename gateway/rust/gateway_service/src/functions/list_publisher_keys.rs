@@ -0,0 +1,27 @@
+use crate::{
+    debug,
+    debugging::log_error,
+    http_utils::{internal_server_error, ApiError},
+    models::{PublisherKey, PublisherKeys, Username},
+    signing::get_publisher_keys,
+    Repository,
+};
+
+/// The keys are public, so anyone can verify the signatures of a user's versions
+pub async fn list_publisher_keys(
+    user: String,
+    key_repo: impl Repository<PublisherKeys>,
+) -> Result<Vec<PublisherKey>, ApiError> {
+    debug!(&user);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let keys = get_publisher_keys(&username, &key_repo)
+        .await
+        .map_err(internal_server_error)?;
+
+    Ok(keys)
+}
@@ -0,0 +1,132 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug,
+    debugging::log_error,
+    http_utils::{internal_server_error, ApiError},
+    models::{PackageName, Username, VersionName},
+    read_versions_by_name, RepositoryError, VersionPage, VersionRepository,
+};
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Lists the versions of a package a page at a time, without reading the whole package
+pub async fn list_versions(
+    user: String,
+    package: String,
+    after: Option<String>,
+    limit: Option<usize>,
+    version_repo: &impl VersionRepository,
+) -> Result<VersionPage, ApiError> {
+    debug!(&user, &package, &after, &limit);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let after: Option<VersionName> = after
+        .map(|after| after.parse())
+        .transpose()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ApiError::bad_request(format!(
+            "Limit must be between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
+    }
+
+    let page = read_versions_by_name(&username, &package_name, after, limit, version_repo)
+        .await
+        .map_err(|e| match e {
+            RepositoryError::NotFound => ApiError::from(StatusCode::NOT_FOUND),
+            e => internal_server_error(e).into(),
+        })?;
+
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use crate::{
+        functions::list_versions,
+        models::{Package, Version},
+        InMemoryPackageRepository, Repository, VersionPage,
+    };
+
+    #[tokio::test]
+    async fn lists_page_of_versions() {
+        let version_repo = InMemoryPackageRepository::default();
+
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = ["1.0.0", "1.1.0", "1.2.0"]
+            .iter()
+            .enumerate()
+            .map(|(i, version)| {
+                Version::new(
+                    version.parse().unwrap(),
+                    format!("test/uri{}", i).parse().unwrap(),
+                )
+            })
+            .collect();
+        version_repo.update(&package).await.unwrap();
+
+        let result = list_versions(
+            "User1".into(),
+            "package1".into(),
+            Some("1.0.0".into()),
+            Some(1),
+            &version_repo,
+        )
+        .await;
+
+        assert_eq!(
+            result.map_err(|e| e.status),
+            Ok(VersionPage {
+                versions: vec![package.versions[1].clone()],
+                next: Some("1.1.0".parse().unwrap()),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_page() {
+        for (after, limit) in [(Some("^1.0.0"), None), (None, Some(0)), (None, Some(101))] {
+            let result = list_versions(
+                "user1".into(),
+                "package1".into(),
+                after.map(String::from),
+                limit,
+                &InMemoryPackageRepository::default(),
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+        }
+    }
+
+    #[tokio::test]
+    async fn package_not_found() {
+        let result = list_versions(
+            "user1".into(),
+            "package1".into(),
+            None,
+            None,
+            &InMemoryPackageRepository::default(),
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::NOT_FOUND));
+    }
+}
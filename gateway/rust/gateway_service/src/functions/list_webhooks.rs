@@ -0,0 +1,28 @@
+use crate::{
+    debug,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::WebhookSubscriptions,
+    webhooks::list_subscriptions,
+    AccountService, Repository,
+};
+
+use super::webhooks::{parse_webhook_scope, WebhookInfo};
+
+pub async fn list_webhooks(
+    user_and_package: Option<(String, String)>,
+    api_key: String,
+    webhook_repo: impl Repository<WebhookSubscriptions>,
+    account_service: impl AccountService,
+) -> Result<Vec<WebhookInfo>, ApiError> {
+    debug!(&user_and_package, &api_key);
+
+    let (username, scope) = parse_webhook_scope(user_and_package)?;
+
+    verify_user_key(&username, &api_key, &account_service).await?;
+
+    let subscriptions = list_subscriptions(&scope, &webhook_repo)
+        .await
+        .map_err(internal_server_error)?;
+
+    Ok(subscriptions.into_iter().map(WebhookInfo::from).collect())
+}
@@ -9,3 +9,86 @@ pub use latest_version_info::latest_version_info;
 
 mod package_info;
 pub use package_info::package_info;
+
+mod set_dist_tag;
+pub use set_dist_tag::set_dist_tag;
+
+mod remove_dist_tag;
+pub use remove_dist_tag::remove_dist_tag;
+
+mod yank;
+pub use yank::yank;
+
+mod unpublish;
+pub use unpublish::unpublish;
+
+mod update_metadata;
+pub use update_metadata::update_metadata;
+
+mod update_policy;
+pub use update_policy::update_policy;
+
+mod promote_latest;
+pub use promote_latest::promote_latest;
+
+mod publish_batch;
+pub use publish_batch::*;
+
+mod audit;
+
+mod audit_log;
+pub use audit_log::audit_log;
+
+mod list_versions;
+pub use list_versions::list_versions;
+
+mod webhooks;
+pub use webhooks::WebhookInfo;
+
+mod add_webhook;
+pub use add_webhook::add_webhook;
+
+mod remove_webhook;
+pub use remove_webhook::remove_webhook;
+
+mod list_webhooks;
+pub use list_webhooks::list_webhooks;
+
+mod add_publisher_key;
+pub use add_publisher_key::add_publisher_key;
+
+mod remove_publisher_key;
+pub use remove_publisher_key::remove_publisher_key;
+
+mod list_publisher_keys;
+pub use list_publisher_keys::list_publisher_keys;
+
+mod idempotent_request;
+pub use idempotent_request::idempotent_request;
+
+mod request_transfer;
+pub use request_transfer::request_transfer;
+
+mod cancel_transfer;
+pub use cancel_transfer::cancel_transfer;
+
+mod accept_transfer;
+pub use accept_transfer::accept_transfer;
+
+mod create_organization;
+pub use create_organization::create_organization;
+
+mod get_organization;
+pub use get_organization::get_organization;
+
+mod set_member_role;
+pub use set_member_role::set_member_role;
+
+mod remove_member;
+pub use remove_member::remove_member;
+
+mod set_redirect;
+pub use set_redirect::set_redirect;
+
+mod remove_redirect;
+pub use remove_redirect::remove_redirect;
@@ -4,7 +4,7 @@ use crate::{
     debug,
     http_utils::internal_server_error,
     models::{Package, PackageName, Username},
-    resolving::{get_package, GetPackageError},
+    resolving::{get_package, GetPackageError, Resolved},
     Repository,
 };
 
@@ -12,7 +12,7 @@ pub async fn package_info(
     user: String,
     package: String,
     package_repo: &impl Repository<Package>,
-) -> Result<String, StatusCode> {
+) -> Result<Resolved<String>, StatusCode> {
     debug!(&user, &package);
 
     let username: Username = user.parse().map_err(internal_server_error)?;
@@ -26,65 +26,41 @@ pub async fn package_info(
             GetPackageError::RepositoryError(e) => internal_server_error(e),
         })?;
 
-    Ok(serde_json::to_string_pretty(&package).map_err(internal_server_error)?)
+    let info = serde_json::to_string_pretty(&package).map_err(internal_server_error)?;
+
+    Ok(Resolved {
+        canonical_id: package.id,
+        value: info,
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use async_trait::async_trait;
-    use mockall::{mock, predicate::eq};
-
-    use crate::{functions::package_info, Package, Repository, RepositoryError, Version};
-
-    mock! {
-      PackageRepository {}
-        #[async_trait]
-        impl Repository<Package> for PackageRepository {
-            async fn read(&self, key: &str) -> Result<Package, RepositoryError>;
-            async fn update(&self, entity: &Package) -> Result<(), RepositoryError>;
-        }
-    }
+    use crate::{functions::package_info, InMemoryPackageRepository, Package, Repository, Version};
 
     #[tokio::test]
     async fn can_get_package_info() {
-        let mut package_repo = MockPackageRepository::new();
-
-        let package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![
-                Version {
-                    name: "1.0.0".into(),
-                    uri: "test/uri0".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "1.0.1".into(),
-                    uri: "test/uri1".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "1.0.2".into(),
-                    uri: "test/uri2".parse().unwrap(),
-                    created_on: 0,
-                },
-            ],
-            created_on: 0,
-        };
+        let package_repo = InMemoryPackageRepository::default();
 
-        {
-            let package = package.clone();
-            package_repo
-                .expect_read()
-                .with(eq("user1/package1".to_string()))
-                .return_once(move |_| Ok(package));
-        }
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = ["1.0.0", "1.0.1", "1.0.2"]
+            .iter()
+            .enumerate()
+            .map(|(i, version)| {
+                Version::new(
+                    version.parse().unwrap(),
+                    format!("test/uri{}", i).parse().unwrap(),
+                )
+            })
+            .collect();
+        package_repo.update(&package).await.unwrap();
 
         let result = package_info("user1".into(), "package1".into(), &package_repo)
             .await
             .unwrap();
 
-        assert_eq!(result, serde_json::to_string_pretty(&package).unwrap());
+        let stored: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(result.canonical_id, "user1/package1");
+        assert_eq!(result.value, serde_json::to_string_pretty(&stored).unwrap());
     }
 }
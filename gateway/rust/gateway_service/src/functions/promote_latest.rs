@@ -0,0 +1,174 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AuditAction, AuditContext, DistTag, KeyIdSecret, Package, PackageName, Username,
+        VersionName, WrapUri,
+    },
+    publishing::{promote_latest_version, PromoteError},
+    AccountService, AuditRepository, Repository, SemVer,
+};
+
+use super::audit::audited;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn promote_latest(
+    user: String,
+    package: String,
+    version: String,
+    tag: Option<String>,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &version, &tag, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let version: SemVer = version.parse().map_err(log_error).map_err(|_| {
+        ApiError::bad_request("Only an exact version (e.g. 1.2.3) can be promoted to")
+    })?;
+
+    let tag: Option<DistTag> = tag
+        .map(|tag| tag.parse())
+        .transpose()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!(
+        "Promoting latest version of {:?} to {}",
+        &package_name,
+        &version
+    );
+
+    // The URI stays the same, only the version it is published under changes
+    let context = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    );
+    let package_id = Package::id_of(&username, &package_name);
+    let entry = |version: VersionName, uri: Option<WrapUri>| {
+        context.entry(
+            package_id.clone(),
+            AuditAction::Promote,
+            version,
+            uri.clone(),
+            uri,
+        )
+    };
+
+    audited(
+        &audit_repo,
+        [entry(VersionName::SemVer(version.clone()), None)],
+        async {
+            promote_latest_version(&username, &package_name, version, tag, package_repo)
+                .await
+                .map_err(log_error)
+                .map_err(|e| match e {
+                    PromoteError::PackageNotFound => StatusCode::NOT_FOUND.into(),
+                    e @ (PromoteError::NotLatestOnly
+                    | PromoteError::PrereleaseVersion
+                    | PromoteError::UnpublishedVersionName) => ApiError::bad_request(e),
+                    e @ PromoteError::Conflict => ApiError::new(StatusCode::CONFLICT, e),
+                    e @ PromoteError::ConfusableName(_) => ApiError::new(StatusCode::CONFLICT, e),
+                    PromoteError::RepositoryError(e) => internal_server_error(e).into(),
+                })
+        },
+        |promoted| vec![entry(promoted.name.clone(), Some(promoted.uri.clone()))],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::{
+        functions::promote_latest,
+        mocks::MockAccountService,
+        models::{KeyIdSecret, Username},
+        InMemoryPackageRepository, KeyValidationError, Package, Repository, Version, VersionName,
+    };
+
+    #[tokio::test]
+    async fn forbids_promoting_with_invalid_key() {
+        let package_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            VersionName::Latest,
+            "test/uri1".parse().unwrap(),
+        )];
+        package_repo.update(&package).await.unwrap();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("user1".parse::<Username>().unwrap()), eq("key1"))
+            .return_once(|_, _| Err(KeyValidationError::Invalid));
+
+        let result = promote_latest(
+            "user1".into(),
+            "package1".into(),
+            "1.0.0".into(),
+            None,
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            package_repo.clone(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::UNAUTHORIZED));
+
+        let stored: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(stored.versions[0].name, VersionName::Latest);
+    }
+
+    #[tokio::test]
+    async fn forbids_promoting_to_non_exact_versions() {
+        for version in ["1", "^1.0.0", "latest"] {
+            let mut account_service = MockAccountService::new();
+
+            account_service.expect_verify_user_key().never();
+
+            let result = promote_latest(
+                "user1".into(),
+                "package1".into(),
+                version.into(),
+                None,
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                InMemoryPackageRepository::default(),
+                InMemoryPackageRepository::default(),
+                account_service,
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+        }
+    }
+}
@@ -1,143 +1,677 @@
 use axum::http::StatusCode;
+use serde::Serialize;
 
 use crate::{
-    accounts::KeyValidationError,
     debug, debug_println,
     debugging::log_error,
+    fetching::WrapFetcher,
     get_username_package_and_version,
-    models::Package,
-    publishing::{publish_package, PublishError},
-    AccountService, Repository, http_utils::internal_server_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AllowedUriAuthorities, AuditAction, AuditContext, KeyIdSecret, Package, PublisherKeys,
+        ReservedNames, VersionName, VersionSelector, WebhookSubscriptions, WrapUri,
+    },
+    publishing::{publish_package, PublishError, PublishOptions, PublishedVersion},
+    signing::{get_publisher_keys, signed_message, verify_publish_signature},
+    webhooks::{notify_subscribers, PublishEvent, WebhookQueue},
+    AccountService, AuditRepository, Repository, VersionRepository,
 };
 
+use super::audit::{begin_audit, commit_audit, reject_audit};
+
+/// What a publish changed, or would change in a dry run.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum PublishChange {
+    NewPackage {
+        version: VersionName,
+        uri: WrapUri,
+    },
+    NewVersion {
+        version: VersionName,
+        uri: WrapUri,
+    },
+    OverwriteLatest {
+        previous_uri: WrapUri,
+        uri: WrapUri,
+    },
+    /// The version was already published with the same URI
+    NoOp,
+}
+
+impl From<&PublishedVersion> for PublishChange {
+    fn from(published: &PublishedVersion) -> Self {
+        let version = published.version.name.clone();
+        let uri = published.version.uri.clone();
+
+        match (&published.previous_uri, published.new_package) {
+            (Some(previous_uri), _) => PublishChange::OverwriteLatest {
+                previous_uri: previous_uri.clone(),
+                uri,
+            },
+            (None, true) => PublishChange::NewPackage { version, uri },
+            (None, false) => PublishChange::NewVersion { version, uri },
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn publish(
     user: String,
     package_and_version: String,
     uri: String,
+    options: PublishOptions,
     api_key: String,
-    package_repo: impl Repository<Package>,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    allowed_authorities: AllowedUriAuthorities,
+    reserved_names: ReservedNames,
+    wrap_fetcher: Option<impl WrapFetcher>,
+    package_repo: impl Repository<Package> + VersionRepository,
+    key_repo: impl Repository<PublisherKeys>,
+    webhook_repo: impl Repository<WebhookSubscriptions>,
+    audit_repo: impl AuditRepository,
     account_service: impl AccountService,
-) -> Result<(), StatusCode> {
+    webhook_queue: impl WebhookQueue,
+) -> Result<PublishChange, ApiError> {
     debug!(&user, &package_and_version, &uri, &api_key);
 
-    let (username, package_name, version_name) =
+    let (username, package_name, version) =
         get_username_package_and_version(user, &package_and_version)?;
 
+    let version_name = match version {
+        VersionSelector::Latest => VersionName::Latest,
+        VersionSelector::Exact(version) => VersionName::SemVer(version),
+        _ => {
+            return Err(ApiError::bad_request(
+                "Only an exact version (e.g. 1.2.3) can be published",
+            ))
+        }
+    };
+
     let uri = uri
         .parse()
         .map_err(log_error)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(ApiError::bad_request)?;
+
+    allowed_authorities
+        .check(&uri)
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    if let Some(metadata) = &options.metadata {
+        metadata.validate().map_err(ApiError::bad_request)?;
+    }
 
-    debug_println!("Verifying API key: {:?}", &api_key);
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
 
-    account_service
-        .verify_user_key(&username, &api_key)
+    let keys = get_publisher_keys(&username, &key_repo)
         .await
-        .map_err(log_error)
-        .map_err(|e| match e {
-            KeyValidationError::Invalid => StatusCode::UNAUTHORIZED,
-            KeyValidationError::Unknown(e) => internal_server_error(e)
-        })?;
+        .map_err(internal_server_error)?;
+    verify_publish_signature(
+        &keys,
+        &signed_message(&username, &package_name, &version_name, &uri),
+        options.signature.as_ref(),
+    )
+    .map_err(log_error)
+    .map_err(|e| ApiError::new(StatusCode::UNAUTHORIZED, e))?;
 
     debug_println!("Publishing package: {:?}", &package_name);
 
-    publish_package(&username, &package_name, version_name, uri, package_repo)
-        .await
-        .map_err(log_error)
-        .map_err(|e| match e {
-            PublishError::InvalidVersionFormat => StatusCode::BAD_REQUEST,
-            PublishError::DuplicateVersionName => StatusCode::BAD_REQUEST,
-            // If the version name and URI are the same, then we can just return OK since nothing needs to be change.
-            PublishError::DuplicateVersionNameAndUri => StatusCode::OK,
-            PublishError::LatestVersionNotAllowed => StatusCode::BAD_REQUEST,
-            PublishError::RepositoryError(e) => internal_server_error(e),
-        })?;
-
-    Ok(())
+    let dry_run = options.dry_run;
+
+    let context = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    );
+    let package_id = Package::id_of(&username, &package_name);
+    // A dry run changes nothing, so it isn't recorded
+    let pending = match dry_run {
+        true => vec![],
+        false => {
+            let entry = context.entry(
+                package_id.clone(),
+                AuditAction::Publish,
+                version_name.clone(),
+                None,
+                Some(uri.clone()),
+            );
+            begin_audit(&audit_repo, [entry]).await?
+        }
+    };
+
+    let published = match publish_package(
+        &username,
+        &package_name,
+        version_name,
+        uri,
+        options,
+        &reserved_names,
+        package_repo,
+        wrap_fetcher.as_ref(),
+    )
+    .await
+    .map_err(log_error)
+    {
+        Ok(published) => Ok(published),
+        // If the version name and URI are the same, then we can just return OK since nothing needs to be change.
+        Err(PublishError::DuplicateVersionNameAndUri) => {
+            commit_audit(&audit_repo, pending, vec![]).await?;
+            return Ok(PublishChange::NoOp);
+        }
+        Err(
+            PublishError::DuplicateVersionName
+            | PublishError::UnpublishedVersionName
+            | PublishError::LatestVersionNotAllowed,
+        ) => Err(StatusCode::BAD_REQUEST.into()),
+        Err(
+            e @ (PublishError::VersionNotMonotonic
+            | PublishError::MajorVersionNotAllowed
+            | PublishError::PrereleaseNotAllowed(_)
+            | PublishError::UriAuthorityNotAllowed(_)
+            | PublishError::ManifestNotFound(_)
+            | PublishError::InvalidManifest(_)
+            | PublishError::PackageMoved(_)
+            | PublishError::BatchTooLarge(_)),
+        ) => Err(ApiError::bad_request(e)),
+        Err(e @ (PublishError::ConfusableName(_) | PublishError::MetadataNotApplied)) => {
+            Err(ApiError::new(StatusCode::CONFLICT, e))
+        }
+        Err(e @ PublishError::ReservedName(_)) => Err(ApiError::new(StatusCode::FORBIDDEN, e)),
+        Err(e @ PublishError::ManifestUnavailable(_)) => {
+            Err(ApiError::new(StatusCode::BAD_GATEWAY, e))
+        }
+        Err(PublishError::Conflict) => Err(StatusCode::CONFLICT.into()),
+        Err(PublishError::RepositoryError(e)) => Err(internal_server_error(e).into()),
+    };
+
+    let published = match published {
+        Ok(published) => published,
+        Err(e) => return Err(reject_audit(&audit_repo, pending, e).await),
+    };
+
+    let change = PublishChange::from(&published);
+
+    if dry_run {
+        return Ok(change);
+    }
+
+    let action = match published.previous_uri {
+        Some(_) => AuditAction::OverwriteLatest,
+        None => AuditAction::Publish,
+    };
+    let entry = context.entry(
+        package_id,
+        action,
+        published.version.name.clone(),
+        published.previous_uri,
+        Some(published.version.uri.clone()),
+    );
+    // Subscribers are notified of the stored version even if it couldn't be recorded
+    let audit_result = commit_audit(&audit_repo, pending, vec![entry]).await;
+
+    let event = PublishEvent::new(username, package_name, published.version);
+
+    // The version is already published, subscribers that can't be notified don't fail the request
+    if let Err(e) = notify_subscribers(&event, &webhook_repo, &webhook_queue).await {
+        eprintln!("Failed to notify webhook subscribers: {}", e);
+    }
+
+    audit_result?;
+
+    Ok(change)
 }
 
 #[cfg(test)]
 mod tests {
-    use async_trait::async_trait;
-    use mockall::{mock, predicate::eq};
+    use axum::http::StatusCode;
+    use base64::Engine;
+    use ed25519_dalek::{Signer, SigningKey};
+    use mockall::predicate::eq;
 
     use crate::{
+        fetching::InMemoryWrapFetcher,
         functions::publish,
-        models::{Package, Username},
-        AccountService, KeyValidationError, Repository, RepositoryError, Version,
+        mocks::{MockAccountService, MockWebhookQueue},
+        models::{
+            AuditAction, KeyIdSecret, Package, PackageMetadata, PublisherKey, PublisherKeys,
+            Username, VersionName, VersionSignature, WebhookSubscription, WebhookSubscriptions,
+        },
+        publishing::PublishOptions,
+        AuditRepository, InMemoryPackageRepository, Repository, RepositoryError, Version,
     };
 
-    mock! {
-      PackageRepository {}
-        #[async_trait]
-        impl Repository<Package> for PackageRepository {
-            async fn read(&self, key: &str) -> Result<Package, RepositoryError>;
-            async fn update(&self, entity: &Package) -> Result<(), RepositoryError>;
-        }
-    }
+    use super::PublishChange;
 
-    mock! {
-        AccountService {}
-        #[async_trait]
-        impl AccountService for AccountService {
-            async fn verify_user_key(&self, username: &Username, api_key: &str) -> Result<(), KeyValidationError>;
-        }
+    async fn repo_with_package() -> InMemoryPackageRepository {
+        let repo = InMemoryPackageRepository::default();
+
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+        )];
+        repo.update(&package).await.unwrap();
+
+        repo
     }
 
     #[tokio::test]
     async fn publish_version() {
-        let package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![Version {
-                name: "1.0.0".into(),
-                uri: "test/uri1".parse().unwrap(),
-                created_on: 0,
-            }],
-            created_on: 0,
-        };
-
-        let new_version = Version {
-            name: "2.0.0".into(),
-            uri: "test/uri2".parse().unwrap(),
-            created_on: 0,
-        };
-
-        let mut package_repo = MockPackageRepository::new();
+        let repo = repo_with_package().await;
         let mut account_service = MockAccountService::new();
+        let mut webhook_queue = MockWebhookQueue::new();
+
+        repo.update(&WebhookSubscriptions {
+            id: "webhooks:user1/package1".into(),
+            subscriptions: vec![WebhookSubscription::new(
+                "https://example.com/hook".into(),
+                "secret".into(),
+            )],
+            revision: 0,
+        })
+        .await
+        .unwrap();
 
         account_service
             .expect_verify_user_key()
-            .with(eq(package.user.clone()), eq("key1"))
+            .with(eq("user1".parse::<Username>().unwrap()), eq("key1"))
             .return_once(|_, _| Ok(()));
 
-        {
-            let package = package.clone();
-            package_repo
-                .expect_read()
-                .with(eq("user1/package1".to_string()))
-                .return_once(move |_| Ok(package));
-        }
-        {
-            let package = package.clone();
-            package_repo
-                .expect_update()
-                .withf(move |p| {
-                    p.id == package.id && p.versions.len() == 2 && p.versions[1] == new_version
-                })
-                .return_once(move |_| Ok(()));
-        }
+        webhook_queue
+            .expect_enqueue()
+            .withf(|body, subscriptions| {
+                body.contains(r#""version":"2.0.0""#)
+                    && subscriptions.len() == 1
+                    && subscriptions[0].1.url == "https://example.com/hook"
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
 
         publish(
             "user1".into(),
             "package1@2.0.0".into(),
             "test/uri2".parse().unwrap(),
+            Default::default(),
             "key1".into(),
-            package_repo,
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            "test".parse().unwrap(),
+            Default::default(),
+            None::<InMemoryWrapFetcher>,
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
             account_service,
+            webhook_queue,
         )
         .await
         .unwrap();
+
+        let package: Package = repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.versions.len(), 2);
+        assert_eq!(package.versions[1].name, "2.0.0".parse().unwrap());
+        assert_eq!(package.versions[1].uri, "test/uri2".parse().unwrap());
+
+        let entries = repo.list("user1/package1").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::Publish);
+        assert_eq!(entries[0].username, "user1".parse().unwrap());
+        assert_ne!(entries[0].key_id, "key1");
+        assert_eq!(entries[0].previous_uri, None);
+        assert_eq!(entries[0].new_uri, Some("test/uri2".parse().unwrap()));
+        assert_eq!(entries[0].request_id, "request1");
+    }
+
+    #[tokio::test]
+    async fn forbids_publishing_non_exact_versions() {
+        for package_and_version in [
+            "package1@1.0.0a",
+            "package1@^1.0.0",
+            "package1@1",
+            "package1@beta",
+        ] {
+            let repo = InMemoryPackageRepository::default();
+            let mut account_service = MockAccountService::new();
+
+            account_service.expect_verify_user_key().never();
+
+            let result = publish(
+                "user1".into(),
+                package_and_version.into(),
+                "test/uri2".parse().unwrap(),
+                Default::default(),
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                "test".parse().unwrap(),
+                Default::default(),
+                None::<InMemoryWrapFetcher>,
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                repo,
+                account_service,
+                MockWebhookQueue::new(),
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+        }
+    }
+
+    #[tokio::test]
+    async fn forbids_publishing_invalid_metadata() {
+        let repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service.expect_verify_user_key().never();
+
+        let result = publish(
+            "user1".into(),
+            "package1@1.0.0".into(),
+            "test/uri1".parse().unwrap(),
+            PublishOptions {
+                metadata: Some(PackageMetadata {
+                    license: Some("Not-A-License".into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            "test".parse().unwrap(),
+            Default::default(),
+            None::<InMemoryWrapFetcher>,
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            repo,
+            account_service,
+            MockWebhookQueue::new(),
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+    }
+
+    #[tokio::test]
+    async fn forbids_publishing_uris_the_registry_does_not_accept() {
+        for uri in ["fs/./build", "ipfs/QmNotACid"] {
+            let repo = InMemoryPackageRepository::default();
+            let mut account_service = MockAccountService::new();
+
+            account_service.expect_verify_user_key().never();
+
+            let result = publish(
+                "user1".into(),
+                "package1@1.0.0".into(),
+                uri.into(),
+                Default::default(),
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                Default::default(),
+                Default::default(),
+                None::<InMemoryWrapFetcher>,
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                repo,
+                account_service,
+                MockWebhookQueue::new(),
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+        }
+    }
+
+    #[tokio::test]
+    async fn forbids_publishing_uris_without_manifest() {
+        let repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .return_once(|_, _| Ok(()));
+
+        let result = publish(
+            "user1".into(),
+            "package1@1.0.0".into(),
+            "test/uri1".parse().unwrap(),
+            Default::default(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            "test".parse().unwrap(),
+            Default::default(),
+            Some(InMemoryWrapFetcher::new()),
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            account_service,
+            MockWebhookQueue::new(),
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+        assert!(matches!(
+            Repository::<Package>::read(&repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn requires_valid_signature_of_publishers_with_keys() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let publisher_key = PublisherKey::new(
+            base64::engine::general_purpose::STANDARD
+                .encode(signing_key.verifying_key().as_bytes()),
+        );
+        let signature = |message: &str| VersionSignature {
+            key_id: publisher_key.id.clone(),
+            signature: base64::engine::general_purpose::STANDARD
+                .encode(signing_key.sign(message.as_bytes()).to_bytes()),
+            origin: None,
+        };
+
+        for signature in [
+            None,
+            // Signed for another version
+            Some(signature("user1\npackage1\n2.0.0\nwrap://test/uri1")),
+        ] {
+            let repo = InMemoryPackageRepository::default();
+            let mut account_service = MockAccountService::new();
+
+            account_service
+                .expect_verify_user_key()
+                .return_once(|_, _| Ok(()));
+            repo.update(&PublisherKeys {
+                id: "keys:user1".into(),
+                keys: vec![publisher_key.clone()],
+                revision: 0,
+            })
+            .await
+            .unwrap();
+
+            let result = publish(
+                "user1".into(),
+                "package1@1.0.0".into(),
+                "test/uri1".parse().unwrap(),
+                PublishOptions {
+                    signature,
+                    ..Default::default()
+                },
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                "test".parse().unwrap(),
+                Default::default(),
+                None::<InMemoryWrapFetcher>,
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                account_service,
+                MockWebhookQueue::new(),
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(StatusCode::UNAUTHORIZED));
+            assert!(matches!(
+                Repository::<Package>::read(&repo, "user1/package1").await,
+                Err(RepositoryError::NotFound)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn dry_run_describes_change_without_publishing() {
+        for (package_and_version, expected_change) in [
+            (
+                "package1@2.0.0",
+                PublishChange::NewVersion {
+                    version: "2.0.0".parse().unwrap(),
+                    uri: "test/uri1".parse().unwrap(),
+                },
+            ),
+            ("package1@1.0.0", PublishChange::NoOp),
+        ] {
+            let repo = repo_with_package().await;
+            let mut account_service = MockAccountService::new();
+
+            account_service
+                .expect_verify_user_key()
+                .return_once(|_, _| Ok(()));
+
+            let change = publish(
+                "user1".into(),
+                package_and_version.into(),
+                "test/uri1".parse().unwrap(),
+                PublishOptions {
+                    dry_run: true,
+                    ..Default::default()
+                },
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                "test".parse().unwrap(),
+                Default::default(),
+                None::<InMemoryWrapFetcher>,
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                account_service,
+                MockWebhookQueue::new(),
+            )
+            .await;
+
+            assert_eq!(change, Ok(expected_change));
+
+            let package: Package = repo.read("user1/package1").await.unwrap();
+            assert_eq!(package.versions.len(), 1);
+            assert!(repo.list("user1/package1").await.unwrap().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn latest_version_can_be_omitted_or_named() {
+        for package_and_version in ["package1", "package1@", "package1@latest"] {
+            let repo = InMemoryPackageRepository::default();
+            let mut account_service = MockAccountService::new();
+
+            account_service
+                .expect_verify_user_key()
+                .return_once(|_, _| Ok(()));
+
+            let change = publish(
+                "user1".into(),
+                package_and_version.into(),
+                "test/uri1".parse().unwrap(),
+                PublishOptions {
+                    dry_run: true,
+                    ..Default::default()
+                },
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                "test".parse().unwrap(),
+                Default::default(),
+                None::<InMemoryWrapFetcher>,
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                repo,
+                account_service,
+                MockWebhookQueue::new(),
+            )
+            .await;
+
+            assert_eq!(
+                change,
+                Ok(PublishChange::NewPackage {
+                    version: VersionName::Latest,
+                    uri: "test/uri1".parse().unwrap(),
+                }),
+                "{}",
+                package_and_version
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn republishing_latest_with_the_same_uri_is_a_no_op() {
+        let repo = InMemoryPackageRepository::default();
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            VersionName::Latest,
+            "test/uri1".parse().unwrap(),
+        )];
+        repo.update(&package).await.unwrap();
+
+        for dry_run in [true, false] {
+            let mut account_service = MockAccountService::new();
+            let mut webhook_queue = MockWebhookQueue::new();
+
+            account_service
+                .expect_verify_user_key()
+                .return_once(|_, _| Ok(()));
+            webhook_queue.expect_enqueue().never();
+
+            let change = publish(
+                "user1".into(),
+                "package1@latest".into(),
+                "test/uri1".parse().unwrap(),
+                PublishOptions {
+                    dry_run,
+                    ..Default::default()
+                },
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                "test".parse().unwrap(),
+                Default::default(),
+                None::<InMemoryWrapFetcher>,
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                account_service,
+                webhook_queue,
+            )
+            .await;
+
+            assert_eq!(change, Ok(PublishChange::NoOp), "dry run: {}", dry_run);
+        }
+
+        let package: Package = repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+        assert!(repo.list("user1/package1").await.unwrap().is_empty());
     }
 }
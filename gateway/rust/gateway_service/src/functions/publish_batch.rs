@@ -0,0 +1,387 @@
+use std::collections::BTreeSet;
+
+use axum::http::StatusCode;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    fetching::WrapFetcher,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AllowedUriAuthorities, AuditAction, AuditContext, KeyIdSecret, Package, PublisherKeys,
+        ReservedNames, Username, VersionName, VersionSignature, WebhookSubscriptions,
+    },
+    publishing::{self, BatchEntry, PublishError},
+    signing::{get_publisher_keys, signed_message, verify_publish_signature},
+    webhooks::{notify_subscribers, PublishEvent, WebhookQueue},
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::{begin_audit, commit_audit, reject_audit};
+
+/// DynamoDB transactions are limited to 100 items, a batch writes one per package and one per version
+const MAX_BATCH_ITEMS: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct BatchEntryBody {
+    pub package: String,
+    pub version: String,
+    pub uri: String,
+    #[serde(default)]
+    pub allow_major: bool,
+    #[serde(default)]
+    pub signature: Option<VersionSignature>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BatchPublishResponse {
+    pub published: bool,
+    pub results: Vec<BatchEntryResult>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BatchEntryResult {
+    pub package: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<PublishError>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn publish_batch(
+    user: String,
+    entries: Vec<BatchEntryBody>,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    allowed_authorities: AllowedUriAuthorities,
+    reserved_names: ReservedNames,
+    wrap_fetcher: Option<impl WrapFetcher>,
+    package_repo: impl Repository<Package>,
+    key_repo: impl Repository<PublisherKeys>,
+    webhook_repo: impl Repository<WebhookSubscriptions>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+    webhook_queue: impl WebhookQueue,
+) -> Result<BatchPublishResponse, ApiError> {
+    debug!(&user, &entries, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    if entries.is_empty() {
+        return Err(ApiError::bad_request(
+            "A batch must contain at least 1 entry",
+        ));
+    }
+
+    let entries = entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            parse_entry(entry, &allowed_authorities).map_err(|e| format!("Entry {}: {}", index, e))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ApiError::bad_request)?;
+
+    let packages = entries
+        .iter()
+        .map(|entry| Package::id_of(&username, &entry.package_name))
+        .collect::<BTreeSet<_>>();
+    let items = packages.len() + entries.len();
+    if items > MAX_BATCH_ITEMS {
+        return Err(ApiError::bad_request(format!(
+            "A batch can write at most {} items, one per package and one per version, this one writes {}",
+            MAX_BATCH_ITEMS, items
+        )));
+    }
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    let keys = get_publisher_keys(&username, &key_repo)
+        .await
+        .map_err(internal_server_error)?;
+    for (index, entry) in entries.iter().enumerate() {
+        let message = signed_message(
+            &username,
+            &entry.package_name,
+            &VersionName::SemVer(entry.version.clone()),
+            &entry.uri,
+        );
+        verify_publish_signature(&keys, &message, entry.signature.as_ref())
+            .map_err(log_error)
+            .map_err(|e| {
+                ApiError::new(StatusCode::UNAUTHORIZED, format!("Entry {}: {}", index, e))
+            })?;
+    }
+
+    debug_println!("Publishing batch of {} versions", entries.len());
+
+    let audit_context = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    );
+    let audit_entry = |entry: &BatchEntry, version: VersionName| {
+        audit_context.entry(
+            Package::id_of(&username, &entry.package_name),
+            AuditAction::Publish,
+            version,
+            None,
+            Some(entry.uri.clone()),
+        )
+    };
+    let pending = begin_audit(
+        &audit_repo,
+        entries
+            .iter()
+            .map(|entry| audit_entry(entry, VersionName::SemVer(entry.version.clone())))
+            .collect::<Vec<_>>(),
+    )
+    .await?;
+
+    let result = match publishing::publish_batch(
+        &username,
+        &entries,
+        &reserved_names,
+        package_repo,
+        wrap_fetcher.as_ref(),
+    )
+    .await
+    .map_err(log_error)
+    .map_err(|e| match e {
+        PublishError::Conflict => ApiError::new(StatusCode::CONFLICT, e),
+        e @ PublishError::BatchTooLarge(_) => ApiError::bad_request(e),
+        e => internal_server_error(e).into(),
+    }) {
+        Ok(result) => result,
+        Err(e) => return Err(reject_audit(&audit_repo, pending, e).await),
+    };
+
+    // Nothing is stored unless the whole batch was published
+    let published = entries
+        .iter()
+        .zip(&result.results)
+        .filter(|_| result.published)
+        .filter_map(|(entry, result)| Some((entry, result.as_ref().ok()?)));
+
+    // Subscribers are notified of the stored versions even if they couldn't be recorded
+    let audit_result = commit_audit(
+        &audit_repo,
+        pending,
+        published
+            .clone()
+            .map(|(entry, version)| audit_entry(entry, version.name.clone()))
+            .collect(),
+    )
+    .await;
+
+    if result.published {
+        let notifications = published.map(|(entry, version)| {
+            let event = PublishEvent::new(
+                username.clone(),
+                entry.package_name.clone(),
+                version.clone(),
+            );
+            let webhook_repo = &webhook_repo;
+            let webhook_queue = &webhook_queue;
+
+            async move { notify_subscribers(&event, webhook_repo, webhook_queue).await }
+        });
+
+        for result in join_all(notifications).await {
+            if let Err(e) = result {
+                eprintln!("Failed to notify webhook subscribers: {}", e);
+            }
+        }
+    }
+
+    audit_result?;
+
+    Ok(BatchPublishResponse {
+        published: result.published,
+        results: entries
+            .into_iter()
+            .zip(result.results)
+            .map(|(entry, result)| BatchEntryResult {
+                package: entry.package_name.to_string(),
+                version: entry.version.to_string(),
+                error: result.err(),
+            })
+            .collect(),
+    })
+}
+
+fn parse_entry(
+    entry: BatchEntryBody,
+    allowed_authorities: &AllowedUriAuthorities,
+) -> Result<BatchEntry, String> {
+    let uri = entry.uri.parse().map_err(|e| format!("{}", e))?;
+    allowed_authorities
+        .check(&uri)
+        .map_err(|e| format!("{}", e))?;
+
+    Ok(BatchEntry {
+        package_name: entry.package.parse().map_err(|e| format!("{}", e))?,
+        version: entry.version.parse().map_err(|e| format!("{}", e))?,
+        uri,
+        allow_major: entry.allow_major,
+        signature: entry.signature,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use crate::{
+        fetching::InMemoryWrapFetcher,
+        functions::publish_batch,
+        mocks::{MockAccountService, MockWebhookQueue},
+        models::{KeyIdSecret, Package, WebhookSubscription, WebhookSubscriptions},
+        AuditRepository, InMemoryPackageRepository, KeyValidationError, Repository,
+    };
+
+    use super::BatchEntryBody;
+
+    fn entry(package: &str, version: &str) -> BatchEntryBody {
+        BatchEntryBody {
+            package: package.into(),
+            version: version.into(),
+            uri: "test/uri1".into(),
+            allow_major: false,
+            signature: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn publish_batch_of_new_packages() {
+        let repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+        let mut webhook_queue = MockWebhookQueue::new();
+
+        account_service
+            .expect_verify_user_key()
+            .return_once(|_, _| Ok(()));
+
+        repo.update(&WebhookSubscriptions {
+            id: "webhooks".into(),
+            subscriptions: vec![WebhookSubscription::new(
+                "https://indexer.example.com".into(),
+                "secret".into(),
+            )],
+            revision: 0,
+        })
+        .await
+        .unwrap();
+        webhook_queue
+            .expect_enqueue()
+            .withf(|body, subscriptions| {
+                body.contains(r#""version":"1.0.0""#)
+                    && subscriptions.len() == 1
+                    && subscriptions[0].1.url == "https://indexer.example.com"
+            })
+            .times(2)
+            .returning(|_, _| Ok(()));
+
+        let result = publish_batch(
+            "user1".into(),
+            vec![entry("package1", "1.0.0"), entry("package2", "1.0.0")],
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            "test".parse().unwrap(),
+            Default::default(),
+            None::<InMemoryWrapFetcher>,
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            account_service,
+            webhook_queue,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.published);
+        assert!(result.results.iter().all(|result| result.error.is_none()));
+
+        for id in ["user1/package1", "user1/package2"] {
+            let package: Package = repo.read(id).await.unwrap();
+            assert_eq!(package.versions.len(), 1);
+
+            let entries = repo.list(id).await.unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].request_id, "request1");
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_batch_with_invalid_entry() {
+        let repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service.expect_verify_user_key().never();
+
+        let result = publish_batch(
+            "user1".into(),
+            vec![entry("package1", "1.0.0"), entry("package2", "^1.0.0")],
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            "test".parse().unwrap(),
+            Default::default(),
+            None::<InMemoryWrapFetcher>,
+            repo.clone(),
+            repo.clone(),
+            repo.clone(),
+            repo,
+            account_service,
+            MockWebhookQueue::new(),
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+    }
+
+    #[tokio::test]
+    async fn rejects_batch_with_too_many_items() {
+        // 50 packages with one version each fit, the 51st package doesn't
+        for (packages, status) in [(50, None), (51, Some(StatusCode::BAD_REQUEST))] {
+            let entries = (0..packages)
+                .map(|i| entry(&format!("package{}", i), "1.0.0"))
+                .collect();
+            let repo = InMemoryPackageRepository::default();
+            let mut account_service = MockAccountService::new();
+            account_service
+                .expect_verify_user_key()
+                .returning(|_, _| Err(KeyValidationError::Invalid));
+
+            let result = publish_batch(
+                "user1".into(),
+                entries,
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                "test".parse().unwrap(),
+                Default::default(),
+                None::<InMemoryWrapFetcher>,
+                repo.clone(),
+                repo.clone(),
+                repo.clone(),
+                repo,
+                account_service,
+                MockWebhookQueue::new(),
+            )
+            .await;
+
+            // Batches that fit get as far as checking the key
+            let expected = status.unwrap_or(StatusCode::UNAUTHORIZED);
+            assert_eq!(result.map_err(|e| e.status), Err(expected));
+        }
+    }
+}
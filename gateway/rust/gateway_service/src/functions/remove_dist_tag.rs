@@ -0,0 +1,79 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{AuditAction, AuditContext, DistTag, KeyIdSecret, Package, PackageName, Username},
+    tagging::{remove_dist_tag as remove_package_dist_tag, DistTagError},
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn remove_dist_tag(
+    user: String,
+    package: String,
+    tag: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &tag, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let tag: DistTag = tag
+        .parse()
+        .map_err(log_error)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Removing dist-tag {} from {:?}", &tag, &package_name);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Package::id_of(&username, &package_name),
+        AuditAction::RemoveDistTag,
+        Some(tag.to_string()),
+    );
+
+    audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            remove_package_dist_tag(&username, &package_name, &tag, package_repo)
+                .await
+                .map_err(log_error)
+                .map_err(|e| match e {
+                    DistTagError::PackageNotFound => ApiError::from(StatusCode::NOT_FOUND),
+                    DistTagError::VersionNotFound => StatusCode::NOT_FOUND.into(),
+                    DistTagError::TagNotFound => StatusCode::NOT_FOUND.into(),
+                    DistTagError::Conflict => StatusCode::CONFLICT.into(),
+                    e @ DistTagError::ConfusableName(_) => ApiError::new(StatusCode::CONFLICT, e),
+                    DistTagError::RepositoryError(e) => internal_server_error(e).into(),
+                })
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{verify_user_key, ApiError},
+    models::{AuditAction, AuditContext, KeyIdSecret, Organization, Username},
+    organizations::remove_member as remove,
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+use super::create_organization::org_error;
+
+/// `account_service` has to only accept owners of the organization
+#[allow(clippy::too_many_arguments)]
+pub async fn remove_member(
+    org: String,
+    username: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    org_repo: impl Repository<Organization>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&org, &username, &api_key);
+
+    let org: Username = org
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let username: Username = username
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&org, &api_key, &account_service).await?;
+
+    debug_println!("Removing {:?} from {:?}", &username, &org);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Organization::key(&org),
+        AuditAction::RemoveMember,
+        Some(username.to_string()),
+    );
+
+    audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            remove(&org, &username, org_repo)
+                .await
+                .map_err(log_error)
+                .map_err(org_error)
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{PublisherKeys, Username},
+    signing::{self, SigningError},
+    AccountService, Repository,
+};
+
+pub async fn remove_publisher_key(
+    user: String,
+    id: String,
+    api_key: String,
+    key_repo: impl Repository<PublisherKeys>,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &id, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Removing publisher key {} of {}", &id, &username);
+
+    signing::remove_publisher_key(&username, &id, key_repo)
+        .await
+        .map_err(log_error)
+        .map_err(|e| match e {
+            SigningError::UnknownKey(_) => StatusCode::NOT_FOUND.into(),
+            e @ SigningError::Conflict => ApiError::new(StatusCode::CONFLICT, e),
+            e => internal_server_error(e).into(),
+        })
+}
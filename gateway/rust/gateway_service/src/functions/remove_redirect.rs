@@ -0,0 +1,65 @@
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{verify_user_key, ApiError},
+    models::{AuditAction, AuditContext, KeyIdSecret, Package, PackageName, Username},
+    redirecting::remove_redirect as remove,
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+use super::set_redirect::redirect_error;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn remove_redirect(
+    user: String,
+    package: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Removing redirect of {:?}", &package_name);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Package::id_of(&username, &package_name),
+        AuditAction::RemoveRedirect,
+        None,
+    );
+
+    audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            remove(&username, &package_name, package_repo)
+                .await
+                .map_err(log_error)
+                .map_err(redirect_error)
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::WebhookSubscriptions,
+    webhooks::{remove_subscription, WebhookError},
+    AccountService, Repository,
+};
+
+use super::webhooks::parse_webhook_scope;
+
+pub async fn remove_webhook(
+    user_and_package: Option<(String, String)>,
+    id: String,
+    api_key: String,
+    webhook_repo: impl Repository<WebhookSubscriptions>,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user_and_package, &id, &api_key);
+
+    let (username, scope) = parse_webhook_scope(user_and_package)?;
+
+    verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Removing webhook {} from {:?}", &id, &scope);
+
+    remove_subscription(&scope, &id, webhook_repo)
+        .await
+        .map_err(log_error)
+        .map_err(|e| match e {
+            WebhookError::SubscriptionNotFound => StatusCode::NOT_FOUND,
+            WebhookError::Conflict => StatusCode::CONFLICT,
+            e => internal_server_error(e),
+        })?;
+
+    Ok(())
+}
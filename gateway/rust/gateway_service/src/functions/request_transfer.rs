@@ -0,0 +1,85 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{AuditAction, AuditContext, KeyIdSecret, Package, PackageName, Username},
+    transferring::{request_transfer as offer_package, TransferError},
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn request_transfer(
+    user: String,
+    package: String,
+    to: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &to, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let to: Username = to
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Offering {:?} to {:?}", &package_name, &to);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Package::id_of(&username, &package_name),
+        AuditAction::RequestTransfer,
+        Some(to.to_string()),
+    );
+
+    audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            offer_package(&username, &package_name, &to, package_repo)
+                .await
+                .map_err(log_error)
+                .map_err(transfer_error)
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub(super) fn transfer_error(error: TransferError) -> ApiError {
+    match error {
+        TransferError::PackageNotFound => StatusCode::NOT_FOUND.into(),
+        e @ TransferError::TransferNotFound => ApiError::new(StatusCode::NOT_FOUND, e),
+        e @ TransferError::SameUser => ApiError::bad_request(e),
+        e @ (TransferError::TargetExists(_) | TransferError::Conflict) => {
+            ApiError::new(StatusCode::CONFLICT, e)
+        }
+        e @ TransferError::ConfusableName(_) => ApiError::new(StatusCode::CONFLICT, e),
+        TransferError::RepositoryError(e) => internal_server_error(e).into(),
+    }
+}
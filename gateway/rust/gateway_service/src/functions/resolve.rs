@@ -2,238 +2,239 @@ use axum::http::StatusCode;
 
 use crate::{
     debug, debug_println, get_username_package_and_version,
+    http_utils::ApiError,
     models::{Package, WrapUri},
     resolve_package,
-    resolving::ResolveError,
-    Repository,
+    resolving::{ResolveError, Resolved},
+    Repository, VersionRepository,
 };
 
 pub async fn resolve(
     user: String,
     package_and_version: String,
     file_path: String,
-    package_repo: &impl Repository<Package>,
-) -> Result<WrapUri, StatusCode> {
+    include_prerelease: bool,
+    package_repo: &(impl Repository<Package> + VersionRepository),
+) -> Result<Resolved<WrapUri>, ApiError> {
     debug!(&user, &package_and_version, &file_path);
 
-    let (username, package_name, version_name) =
+    let (username, package_name, version) =
         get_username_package_and_version(user, &package_and_version)?;
 
     match file_path.as_str() {
         "wrap.info" => {}
         _ => {
             debug_println!("Invalid file path: {:?}", &file_path);
-            return Err(StatusCode::NOT_FOUND);
+            return Err(StatusCode::NOT_FOUND.into());
         }
     }
 
-    let uri = resolve_package(&username, &package_name, version_name, package_repo)
-        .await
-        .map_err(|e| {
-            debug_println!("Error resolving package: {}", &e);
-            match e {
-                ResolveError::PackageNotFound => StatusCode::NOT_FOUND,
-                ResolveError::VersionNotFound => StatusCode::NOT_FOUND,
-                ResolveError::RepositoryError(e) => {
-                    eprintln!("INTERNAL_SERVER_ERROR resolving package: {:?}", e);
-                    StatusCode::INTERNAL_SERVER_ERROR
-                }
+    let resolved = resolve_package(
+        &username,
+        &package_name,
+        &version,
+        include_prerelease,
+        package_repo,
+    )
+    .await
+    .map_err(|e| {
+        debug_println!("Error resolving package: {}", &e);
+        match e {
+            ResolveError::PackageNotFound => StatusCode::NOT_FOUND,
+            ResolveError::VersionNotFound => StatusCode::NOT_FOUND,
+            ResolveError::RepositoryError(e) => {
+                eprintln!("INTERNAL_SERVER_ERROR resolving package: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
             }
-        })?;
+        }
+    })?;
 
-    Ok(uri)
+    Ok(resolved)
 }
 
 #[cfg(test)]
 mod tests {
-    use async_trait::async_trait;
     use axum::http::StatusCode;
-    use mockall::{mock, predicate::eq};
 
-    use crate::{functions::resolve, Package, Repository, RepositoryError, Version};
+    use crate::{functions::resolve, InMemoryPackageRepository, Package, Repository, Version};
+
+    async fn repo_with_versions(versions: &[&str]) -> InMemoryPackageRepository {
+        let package_repo = InMemoryPackageRepository::default();
+
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = versions
+            .iter()
+            .enumerate()
+            .map(|(i, version)| {
+                let mut version = Version::new(
+                    version.parse().unwrap(),
+                    format!("test/uri{}", i).parse().unwrap(),
+                );
+                version.created_on = 0;
+                version
+            })
+            .collect();
+        package_repo.update(&package).await.unwrap();
 
-    mock! {
-      PackageRepository {}
-        #[async_trait]
-        impl Repository<Package> for PackageRepository {
-            async fn read(&self, key: &str) -> Result<Package, RepositoryError>;
-            async fn update(&self, entity: &Package) -> Result<(), RepositoryError>;
-        }
+        package_repo
     }
 
     #[tokio::test]
     async fn can_resolve_latest_version() {
-        let mut package_repo = MockPackageRepository::new();
-
-        let package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![
-                Version {
-                    name: "1.0.0".into(),
-                    uri: "test/uri0".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "1.0.1".into(),
-                    uri: "test/uri1".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "1.0.2".into(),
-                    uri: "test/uri2".parse().unwrap(),
-                    created_on: 0,
-                },
-            ],
-            created_on: 0,
-        };
-
-        package_repo
-            .expect_read()
-            .with(eq("user1/package1".to_string()))
-            .return_once(move |_| Ok(package));
+        let package_repo = repo_with_versions(&["1.0.0", "1.0.1", "1.0.2"]).await;
 
         let result = resolve(
             "user1".into(),
             "package1".into(),
             "wrap.info".into(),
+            false,
             &package_repo,
         )
         .await
         .unwrap();
 
-        assert_eq!(result, "test/uri2".parse().unwrap());
+        assert_eq!(result.value, "test/uri2".parse().unwrap());
     }
 
     #[tokio::test]
     async fn can_resolve_specific_version() {
-        let mut package_repo = MockPackageRepository::new();
-
-        let package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![
-                Version {
-                    name: "1.0.0".into(),
-                    uri: "test/uri0".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "1.0.1".into(),
-                    uri: "test/uri1".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "1.0.2".into(),
-                    uri: "test/uri2".parse().unwrap(),
-                    created_on: 0,
-                },
-            ],
-            created_on: 0,
-        };
-
-        package_repo
-            .expect_read()
-            .with(eq("user1/package1".to_string()))
-            .return_once(move |_| Ok(package));
+        let package_repo = repo_with_versions(&["1.0.0", "1.0.1", "1.0.2"]).await;
 
         let result = resolve(
             "user1".into(),
             "package1@1.0.1".into(),
             "wrap.info".into(),
+            false,
             &package_repo,
         )
         .await
         .unwrap();
 
-        assert_eq!(result, "test/uri1".parse().unwrap());
+        assert_eq!(result.value, "test/uri1".parse().unwrap());
     }
 
     #[tokio::test]
-    async fn resolve_package_not_found() {
-        let mut package_repo = MockPackageRepository::new();
+    async fn can_resolve_version_range() {
+        let package_repo = repo_with_versions(&["1.0.0", "1.1.0", "2.0.0"]).await;
 
-        package_repo
-            .expect_read()
-            .with(eq("user1/package1".to_string()))
-            .return_once(move |_| Err(RepositoryError::NotFound));
+        let result = resolve(
+            "user1".into(),
+            "package1@>=1.0.0 <2.0.0".into(),
+            "wrap.info".into(),
+            false,
+            &package_repo,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.value, "test/uri1".parse().unwrap());
+    }
+
+    #[tokio::test]
+    async fn can_resolve_dist_tag() {
+        let package_repo = repo_with_versions(&["1.0.0", "2.0.0-beta.1"]).await;
+
+        let mut package: Package = package_repo.read("user1/package1").await.unwrap();
+        package.dist_tags = [("beta".parse().unwrap(), "2.0.0-beta.1".parse().unwrap())].into();
+        package_repo.update(&package).await.unwrap();
+
+        let resolve = |package_and_version: &str| {
+            resolve(
+                "user1".into(),
+                package_and_version.into(),
+                "wrap.info".into(),
+                false,
+                &package_repo,
+            )
+        };
+
+        assert_eq!(
+            resolve("package1@beta").await.map(|r| r.value),
+            Ok("test/uri1".parse().unwrap())
+        );
+        assert_eq!(
+            resolve("package1@latest").await.map(|r| r.value),
+            Ok("test/uri0".parse().unwrap())
+        );
+        assert_eq!(
+            resolve("package1@next").await.map_err(|e| e.status),
+            Err(StatusCode::NOT_FOUND)
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_version_range_returns_bad_request() {
+        let package_repo = repo_with_versions(&["1.0.0"]).await;
+
+        let result = resolve(
+            "user1".into(),
+            "package1@^1.2-beta".into(),
+            "wrap.info".into(),
+            false,
+            &package_repo,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+    }
+
+    #[tokio::test]
+    async fn resolve_package_not_found() {
+        let package_repo = InMemoryPackageRepository::default();
 
         let result = resolve(
             "user1".into(),
             "package1".into(),
             "some/path".into(),
+            false,
             &package_repo,
         )
         .await;
 
-        assert!(matches!(result, Err(StatusCode::NOT_FOUND)));
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::NOT_FOUND));
     }
 
     #[tokio::test]
     async fn resolve_version_not_found() {
-        let mut package_repo = MockPackageRepository::new();
-
-        let package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![Version {
-                name: "1.0.0".into(),
-                uri: "test/uri1".parse().unwrap(),
-                created_on: 0,
-            }],
-            created_on: 0,
-        };
-
-        package_repo
-            .expect_read()
-            .with(eq("user1/package1".to_string()))
-            .return_once(move |_| Ok(package));
+        let package_repo = repo_with_versions(&["1.0.0"]).await;
 
         let result = resolve(
             "user1".into(),
             "package1@1.0.1".into(),
             "some/path".into(),
+            false,
             &package_repo,
         )
         .await;
 
-        assert!(matches!(result, Err(StatusCode::NOT_FOUND)));
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::NOT_FOUND));
     }
 
     #[tokio::test]
     async fn invalid_package_name_returns_bad_request1() {
-        let mut package_repo = MockPackageRepository::new();
-
-        package_repo.expect_read().times(0);
-
         let result = resolve(
             "user1".into(),
             "pack!age1@1.0.0".into(),
             "some/path".into(),
-            &package_repo,
+            false,
+            &InMemoryPackageRepository::default(),
         )
         .await;
 
-        assert!(matches!(result, Err(StatusCode::BAD_REQUEST)));
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
     }
 
     #[tokio::test]
     async fn invalid_package_name_returns_bad_request2() {
-        let mut package_repo = MockPackageRepository::new();
-
-        package_repo.expect_read().times(0);
-
         let result = resolve(
             "user1".into(),
             "pack age1@1.0.0".into(),
             "some/path".into(),
-            &package_repo,
+            false,
+            &InMemoryPackageRepository::default(),
         )
         .await;
 
-        assert!(matches!(result, Err(StatusCode::BAD_REQUEST)));
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
     }
 }
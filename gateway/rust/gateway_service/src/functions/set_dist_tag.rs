@@ -0,0 +1,212 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AuditAction, AuditContext, AuditEntry, DistTag, KeyIdSecret, Package, PackageName,
+        Username, VersionName,
+    },
+    tagging::{set_dist_tag as set_package_dist_tag, DistTagError},
+    AccountService, AuditRepository, Repository, SemVer,
+};
+
+use super::audit::audited;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn set_dist_tag(
+    user: String,
+    package: String,
+    tag: String,
+    version: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &tag, &version, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let tag: DistTag = tag
+        .parse()
+        .map_err(log_error)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let version: SemVer = version
+        .parse()
+        .map_err(log_error)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!(
+        "Setting dist-tag {} to {} for {:?}",
+        &tag,
+        &version,
+        &package_name
+    );
+
+    let entry = AuditEntry {
+        version: Some(VersionName::SemVer(version.clone())),
+        ..AuditContext::new(
+            authenticated_user,
+            key_id_secret.key_id(&api_key),
+            request_id,
+        )
+        .change(
+            Package::id_of(&username, &package_name),
+            AuditAction::SetDistTag,
+            Some(tag.to_string()),
+        )
+    };
+
+    audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            set_package_dist_tag(
+                &username,
+                &package_name,
+                tag.clone(),
+                version.clone(),
+                package_repo,
+            )
+            .await
+            .map_err(log_error)
+            .map_err(|e| match e {
+                DistTagError::PackageNotFound => ApiError::from(StatusCode::NOT_FOUND),
+                DistTagError::VersionNotFound => StatusCode::BAD_REQUEST.into(),
+                DistTagError::TagNotFound => StatusCode::NOT_FOUND.into(),
+                DistTagError::Conflict => StatusCode::CONFLICT.into(),
+                e @ DistTagError::ConfusableName(_) => ApiError::new(StatusCode::CONFLICT, e),
+                DistTagError::RepositoryError(e) => internal_server_error(e).into(),
+            })
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::{
+        functions::set_dist_tag,
+        mocks::MockAccountService,
+        models::{KeyIdSecret, Package, Username},
+        InMemoryPackageRepository, KeyValidationError, Repository, Version,
+    };
+
+    async fn repo_with_package() -> InMemoryPackageRepository {
+        let package_repo = InMemoryPackageRepository::default();
+
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            "1.0.0-beta.1".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+        )];
+        package_repo.update(&package).await.unwrap();
+
+        package_repo
+    }
+
+    #[tokio::test]
+    async fn set_dist_tag_for_published_version() {
+        let package_repo = repo_with_package().await;
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("user1".parse::<Username>().unwrap()), eq("key1"))
+            .return_once(|_, _| Ok(()));
+
+        set_dist_tag(
+            "user1".into(),
+            "package1".into(),
+            "beta".into(),
+            "1.0.0-beta.1".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            package_repo.clone(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await
+        .unwrap();
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(
+            package.dist_tags.get(&"beta".parse().unwrap()),
+            Some(&"1.0.0-beta.1".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn set_dist_tag_requires_valid_key() {
+        let package_repo = repo_with_package().await;
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .return_once(|_, _| Err(KeyValidationError::Invalid));
+
+        let result = set_dist_tag(
+            "user1".into(),
+            "package1".into(),
+            "beta".into(),
+            "1.0.0-beta.1".into(),
+            "key2".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            package_repo.clone(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::UNAUTHORIZED));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert!(package.dist_tags.is_empty());
+    }
+
+    #[tokio::test]
+    async fn set_dist_tag_rejects_version_like_tags() {
+        let mut account_service = MockAccountService::new();
+
+        account_service.expect_verify_user_key().never();
+
+        let result = set_dist_tag(
+            "user1".into(),
+            "package1".into(),
+            "1.x".into(),
+            "1.0.0".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            InMemoryPackageRepository::default(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+    }
+}
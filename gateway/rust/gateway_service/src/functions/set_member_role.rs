@@ -0,0 +1,67 @@
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{verify_user_key, ApiError},
+    models::{AuditAction, AuditContext, KeyIdSecret, OrgRole, Organization, Username},
+    organizations::set_member_role as set_role,
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+use super::create_organization::org_error;
+
+/// `account_service` has to only accept owners of the organization
+#[allow(clippy::too_many_arguments)]
+pub async fn set_member_role(
+    org: String,
+    username: String,
+    role: OrgRole,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    org_repo: impl Repository<Organization>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<Organization, ApiError> {
+    debug!(&org, &username, &role, &api_key);
+
+    let org: Username = org
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let username: Username = username
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&org, &api_key, &account_service).await?;
+
+    debug_println!("Making {:?} a {} of {:?}", &username, role, &org);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Organization::key(&org),
+        AuditAction::SetMemberRole,
+        Some(format!("{} {}", username, role)),
+    );
+
+    let organization = audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            set_role(&org, &username, role, org_repo)
+                .await
+                .map_err(log_error)
+                .map_err(org_error)
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(organization)
+}
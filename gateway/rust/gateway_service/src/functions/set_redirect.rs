@@ -0,0 +1,144 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AuditAction, AuditContext, KeyIdSecret, Package, PackageName, ReservedNames, Username,
+    },
+    redirecting::{set_redirect as redirect, RedirectError},
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+
+/// `to` is the id of the target package, e.g. `user/package`
+#[allow(clippy::too_many_arguments)]
+pub async fn set_redirect(
+    user: String,
+    package: String,
+    to: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    reserved_names: ReservedNames,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &to, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let (target_user, target_name) = to
+        .split_once('/')
+        .ok_or_else(|| ApiError::bad_request("Target must be a package id, e.g. `user/package`"))?;
+
+    let target_user: Username = target_user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let target_name: PackageName = target_name
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Redirecting {:?} to {}", &package_name, &to);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Package::id_of(&username, &package_name),
+        AuditAction::SetRedirect,
+        Some(Package::id_of(&target_user, &target_name)),
+    );
+
+    audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            redirect(
+                &username,
+                &package_name,
+                &target_user,
+                &target_name,
+                &reserved_names,
+                package_repo,
+            )
+            .await
+            .map_err(log_error)
+            .map_err(redirect_error)
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub(super) fn redirect_error(error: RedirectError) -> ApiError {
+    match error {
+        e @ (RedirectError::TargetNotFound | RedirectError::RedirectNotFound) => {
+            ApiError::new(StatusCode::NOT_FOUND, e)
+        }
+        e @ (RedirectError::RedirectLoop | RedirectError::TooManyRedirects) => {
+            ApiError::bad_request(e)
+        }
+        e @ (RedirectError::PackageHasVersions
+        | RedirectError::VersionConflict(_)
+        | RedirectError::Conflict) => ApiError::new(StatusCode::CONFLICT, e),
+        e @ RedirectError::ReservedName(_) => ApiError::new(StatusCode::FORBIDDEN, e),
+        e @ RedirectError::ConfusableName(_) => ApiError::new(StatusCode::CONFLICT, e),
+        RedirectError::RepositoryError(e) => internal_server_error(e).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use crate::{
+        functions::set_redirect, mocks::MockAccountService, models::KeyIdSecret,
+        InMemoryPackageRepository,
+    };
+
+    #[tokio::test]
+    async fn target_must_be_package_id() {
+        for to in ["package1", "user2/", "user2/package 1"] {
+            let mut account_service = MockAccountService::new();
+
+            account_service.expect_verify_user_key().never();
+
+            let result = set_redirect(
+                "user1".into(),
+                "package1".into(),
+                to.into(),
+                "key1".into(),
+                "request1".into(),
+                &KeyIdSecret::new("secret1"),
+                Default::default(),
+                InMemoryPackageRepository::default(),
+                InMemoryPackageRepository::default(),
+                account_service,
+            )
+            .await;
+
+            assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+        }
+    }
+}
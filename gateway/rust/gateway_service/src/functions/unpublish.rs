@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    get_username_package_and_version,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AuditAction, AuditContext, KeyIdSecret, Package, VersionName, VersionSelector, WrapUri,
+    },
+    publishing::{unpublish_version, UnpublishError},
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn unpublish(
+    user: String,
+    package_and_version: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    unpublish_window: Duration,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package_and_version, &api_key);
+
+    let (username, package_name, version) =
+        get_username_package_and_version(user, &package_and_version)?;
+
+    let VersionSelector::Exact(version) = version else {
+        return Err(ApiError::bad_request(
+            "Only an exact version (e.g. 1.2.3) can be unpublished",
+        ));
+    };
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Unpublishing version {} of {:?}", &version, &package_name);
+
+    let context = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    );
+    let package_id = Package::id_of(&username, &package_name);
+    let entry = |uri: Option<WrapUri>| {
+        context.entry(
+            package_id.clone(),
+            AuditAction::Unpublish,
+            VersionName::SemVer(version.clone()),
+            uri,
+            None,
+        )
+    };
+
+    audited(
+        &audit_repo,
+        [entry(None)],
+        async {
+            unpublish_version(
+                &username,
+                &package_name,
+                &version,
+                unpublish_window,
+                package_repo,
+            )
+            .await
+            .map_err(log_error)
+            .map_err(|e| match e {
+                UnpublishError::PackageNotFound => StatusCode::NOT_FOUND.into(),
+                UnpublishError::VersionNotFound => StatusCode::NOT_FOUND.into(),
+                UnpublishError::UnpublishWindowExpired => ApiError::new(
+                    StatusCode::FORBIDDEN,
+                    format!(
+                        "Versions can only be unpublished within {} hours of publishing, yank it instead",
+                        unpublish_window.as_secs() / 3600
+                    ),
+                ),
+                e @ UnpublishError::Conflict => ApiError::new(StatusCode::CONFLICT, e),
+                e @ UnpublishError::ConfusableName(_) => ApiError::new(StatusCode::CONFLICT, e),
+                UnpublishError::RepositoryError(e) => internal_server_error(e).into(),
+            })
+        },
+        |uri| vec![entry(Some(uri.clone()))],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::{
+        functions::unpublish,
+        mocks::MockAccountService,
+        models::{KeyIdSecret, Package},
+        AuditRepository, InMemoryPackageRepository, Repository, Version,
+    };
+
+    #[tokio::test]
+    async fn unpublish_after_window_is_forbidden() {
+        let package_repo = InMemoryPackageRepository::default();
+        let audit_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        let mut version = Version::new("1.0.0".parse().unwrap(), "test/uri1".parse().unwrap());
+        version.created_on = 0;
+        package.versions = vec![version];
+        package_repo.update(&package).await.unwrap();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq(package.user.clone()), eq("key1"))
+            .return_once(|_, _| Ok(()));
+
+        let result = unpublish(
+            "user1".into(),
+            "package1@1.0.0".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            Duration::from_secs(72 * 60 * 60),
+            package_repo.clone(),
+            audit_repo.clone(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::FORBIDDEN));
+
+        let stored: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(stored.versions.len(), 1);
+        assert!(audit_repo.list("user1/package1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unpublish_requires_exact_version() {
+        let mut account_service = MockAccountService::new();
+
+        account_service.expect_verify_user_key().never();
+
+        let result = unpublish(
+            "user1".into(),
+            "package1@^1.0.0".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            Duration::from_secs(72 * 60 * 60),
+            InMemoryPackageRepository::default(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+    }
+}
@@ -0,0 +1,78 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AuditAction, AuditContext, KeyIdSecret, Package, PackageMetadata, PackageName, Username,
+    },
+    publishing::{update_package_metadata, UpdateMetadataError},
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn update_metadata(
+    user: String,
+    package: String,
+    metadata: PackageMetadata,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &metadata, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    metadata.validate().map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Updating metadata of {:?}", &package_name);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Package::id_of(&username, &package_name),
+        AuditAction::UpdateMetadata,
+        serde_json::to_string(&metadata).ok(),
+    );
+
+    audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            update_package_metadata(&username, &package_name, metadata.clone(), package_repo)
+                .await
+                .map_err(log_error)
+                .map_err(|e| match e {
+                    UpdateMetadataError::PackageNotFound => ApiError::from(StatusCode::NOT_FOUND),
+                    UpdateMetadataError::Conflict => StatusCode::CONFLICT.into(),
+                    e @ UpdateMetadataError::ConfusableName(_) => {
+                        ApiError::new(StatusCode::CONFLICT, e)
+                    }
+                    UpdateMetadataError::RepositoryError(e) => internal_server_error(e).into(),
+                })
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AuditAction, AuditContext, KeyIdSecret, Package, PackageName, PublishPolicy, Username,
+    },
+    publishing::{update_publish_policy, UpdatePolicyError},
+    AccountService, AuditRepository, Repository,
+};
+
+use super::audit::audited;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn update_policy(
+    user: String,
+    package: String,
+    policy: PublishPolicy,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &policy, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    policy.validate().map_err(ApiError::bad_request)?;
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Updating policy of {:?}", &package_name);
+
+    let entry = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    )
+    .change(
+        Package::id_of(&username, &package_name),
+        AuditAction::UpdatePolicy,
+        serde_json::to_string(&policy).ok(),
+    );
+
+    audited(
+        &audit_repo,
+        [entry.clone()],
+        async {
+            update_publish_policy(&username, &package_name, policy.clone(), package_repo)
+                .await
+                .map_err(log_error)
+                .map_err(|e| match e {
+                    UpdatePolicyError::PackageNotFound => ApiError::from(StatusCode::NOT_FOUND),
+                    UpdatePolicyError::Conflict => StatusCode::CONFLICT.into(),
+                    e @ UpdatePolicyError::ConfusableName(_) => {
+                        ApiError::new(StatusCode::CONFLICT, e)
+                    }
+                    UpdatePolicyError::RepositoryError(e) => internal_server_error(e).into(),
+                })
+        },
+        |_| vec![entry],
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use serde::Serialize;
+
+use crate::{
+    constants,
+    debugging::log_error,
+    http_utils::ApiError,
+    models::{Username, WebhookScope, WebhookSubscription},
+};
+
+/// A subscription as shown to clients, without its secret.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct WebhookInfo {
+    pub id: String,
+    pub url: String,
+    pub created_on: u128,
+}
+
+impl From<WebhookSubscription> for WebhookInfo {
+    fn from(subscription: WebhookSubscription) -> Self {
+        Self {
+            id: subscription.id,
+            url: subscription.url,
+            created_on: subscription.created_on,
+        }
+    }
+}
+
+/// Returns the scope of a webhook route and the user who has to authenticate to manage it.
+/// Registry-wide subscriptions can only be managed by the registry's own account.
+pub(super) fn parse_webhook_scope(
+    user_and_package: Option<(String, String)>,
+) -> Result<(Username, WebhookScope), ApiError> {
+    let Some((user, package)) = user_and_package else {
+        let registry_user = constants::POLYWRAP_USERNAME
+            .parse()
+            .expect("Registry username is valid");
+
+        return Ok((registry_user, WebhookScope::Registry));
+    };
+
+    let user: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    Ok((user.clone(), WebhookScope::Package { user, package }))
+}
@@ -0,0 +1,224 @@
+use axum::http::StatusCode;
+
+use crate::{
+    debug, debug_println,
+    debugging::log_error,
+    http_utils::{internal_server_error, verify_user_key, ApiError},
+    models::{
+        AuditAction, AuditContext, KeyIdSecret, Package, PackageName, Username, VersionName,
+        WrapUri,
+    },
+    yanking::{yank_version, YankError},
+    AccountService, AuditRepository, Repository, SemVer,
+};
+
+use super::audit::audited;
+
+const MAX_YANK_REASON_LENGTH: usize = 500;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn yank(
+    user: String,
+    package: String,
+    version: String,
+    reason: String,
+    api_key: String,
+    request_id: String,
+    key_id_secret: &KeyIdSecret,
+    package_repo: impl Repository<Package>,
+    audit_repo: impl AuditRepository,
+    account_service: impl AccountService,
+) -> Result<(), ApiError> {
+    debug!(&user, &package, &version, &reason, &api_key);
+
+    let username: Username = user
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let package_name: PackageName = package
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let version: SemVer = version
+        .parse()
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
+
+    let reason = reason.trim().to_string();
+    if reason.is_empty() || reason.len() > MAX_YANK_REASON_LENGTH {
+        return Err(ApiError::bad_request(format!(
+            "Yank reason must be between 1 and {} characters",
+            MAX_YANK_REASON_LENGTH
+        )));
+    }
+
+    let authenticated_user = verify_user_key(&username, &api_key, &account_service).await?;
+
+    debug_println!("Yanking version {} of {:?}", &version, &package_name);
+
+    let context = AuditContext::new(
+        authenticated_user,
+        key_id_secret.key_id(&api_key),
+        request_id,
+    );
+    let package_id = Package::id_of(&username, &package_name);
+    let entry = |uri: Option<WrapUri>| {
+        context.entry(
+            package_id.clone(),
+            AuditAction::Yank,
+            VersionName::SemVer(version.clone()),
+            uri.clone(),
+            uri,
+        )
+    };
+
+    audited(
+        &audit_repo,
+        [entry(None)],
+        async {
+            yank_version(&username, &package_name, &version, reason, package_repo)
+                .await
+                .map_err(log_error)
+                .map_err(|e| match e {
+                    YankError::PackageNotFound => ApiError::from(StatusCode::NOT_FOUND),
+                    YankError::VersionNotFound => StatusCode::NOT_FOUND.into(),
+                    YankError::Conflict => StatusCode::CONFLICT.into(),
+                    e @ YankError::ConfusableName(_) => ApiError::new(StatusCode::CONFLICT, e),
+                    YankError::RepositoryError(e) => internal_server_error(e).into(),
+                })
+        },
+        |uri| vec![entry(Some(uri.clone()))],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use mockall::predicate::eq;
+
+    use crate::{
+        functions::yank,
+        mocks::MockAccountService,
+        mocks::MockPackageRepository,
+        models::{AuditAction, KeyIdSecret, Package, Username},
+        AuditRepository, InMemoryPackageRepository, Repository, RepositoryError, Version,
+    };
+
+    fn published_package() -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+        )];
+        package
+    }
+
+    #[tokio::test]
+    async fn yank_published_version() {
+        let package_repo = InMemoryPackageRepository::default();
+        let audit_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        package_repo.update(&published_package()).await.unwrap();
+
+        account_service
+            .expect_verify_user_key()
+            .with(eq("user1".parse::<Username>().unwrap()), eq("key1"))
+            .return_once(|_, _| Ok(()));
+
+        yank(
+            "user1".into(),
+            "package1".into(),
+            "1.0.0".into(),
+            " Broken build ".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            package_repo.clone(),
+            audit_repo.clone(),
+            account_service,
+        )
+        .await
+        .unwrap();
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert!(matches!(&package.versions[0].yanked, Some(yank) if yank.reason == "Broken build"));
+
+        let entries = audit_repo.list("user1/package1").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::Yank);
+        assert_eq!(entries[0].version, Some("1.0.0".parse().unwrap()));
+        assert_eq!(entries[0].new_uri, Some("test/uri1".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn yank_requires_a_reason() {
+        let package_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        package_repo.update(&published_package()).await.unwrap();
+
+        account_service.expect_verify_user_key().never();
+
+        let result = yank(
+            "user1".into(),
+            "package1".into(),
+            "1.0.0".into(),
+            "  ".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            package_repo.clone(),
+            InMemoryPackageRepository::default(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::BAD_REQUEST));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.versions[0].yanked, None);
+    }
+
+    #[tokio::test]
+    async fn yank_returns_conflict_on_concurrent_update() {
+        let mut package_repo = MockPackageRepository::new();
+        let audit_repo = InMemoryPackageRepository::default();
+        let mut account_service = MockAccountService::new();
+
+        account_service
+            .expect_verify_user_key()
+            .return_once(|_, _| Ok(()));
+
+        package_repo
+            .expect_read()
+            .with(eq("user1/package1".to_string()))
+            .return_once(|_| Ok(published_package()));
+        // Someone else changed the package since it was read
+        package_repo
+            .expect_update()
+            .return_once(|_| Err(RepositoryError::Conflict));
+
+        let result = yank(
+            "user1".into(),
+            "package1".into(),
+            "1.0.0".into(),
+            "Broken build".into(),
+            "key1".into(),
+            "request1".into(),
+            &KeyIdSecret::new("secret1"),
+            package_repo,
+            audit_repo.clone(),
+            account_service,
+        )
+        .await;
+
+        assert_eq!(result.map_err(|e| e.status), Err(StatusCode::CONFLICT));
+        assert!(audit_repo.list("user1/package1").await.unwrap().is_empty());
+    }
+}
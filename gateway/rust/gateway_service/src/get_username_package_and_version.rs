@@ -1,26 +1,29 @@
-use reqwest::StatusCode;
-
 use crate::{
     debugging::log_error,
     extract_package_and_version,
-    models::{PackageName, Username},
+    http_utils::ApiError,
+    models::{PackageName, Username, VersionSelector},
 };
 
 pub fn get_username_package_and_version(
     username: String,
     package_and_version: &str,
-) -> Result<(Username, PackageName, Option<&str>), StatusCode> {
+) -> Result<(Username, PackageName, VersionSelector), ApiError> {
     let username = username
         .parse()
         .map_err(log_error)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(ApiError::bad_request)?;
 
     let (package_name, version_name) = extract_package_and_version(package_and_version);
 
     let package_name = package_name
         .parse()
         .map_err(log_error)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(ApiError::bad_request)?;
+
+    let version = VersionSelector::parse(version_name)
+        .map_err(log_error)
+        .map_err(ApiError::bad_request)?;
 
-    Ok((username, package_name, version_name))
+    Ok((username, package_name, version))
 }
@@ -0,0 +1,42 @@
+use std::fmt::Display;
+
+use axum::response::{IntoResponse, Response};
+use http::StatusCode;
+
+/// An error status, optionally with a message explaining it to the client.
+#[derive(Debug, PartialEq)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Display) -> Self {
+        Self {
+            status,
+            message: Some(message.to_string()),
+        }
+    }
+
+    pub fn bad_request(message: impl Display) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl From<StatusCode> for ApiError {
+    fn from(status: StatusCode) -> Self {
+        Self {
+            status,
+            message: None,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self.message {
+            Some(message) => (self.status, message).into_response(),
+            None => self.status.into_response(),
+        }
+    }
+}
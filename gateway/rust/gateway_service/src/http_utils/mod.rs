@@ -1,6 +1,20 @@
+mod api_error;
+pub use api_error::*;
+
+mod stored_response;
+
+mod public_address;
+pub use public_address::*;
+
+use base64::Engine;
 use http::{HeaderMap, StatusCode};
 
-use crate::{debug, debugging::log_error};
+use crate::{
+    accounts::{AccountService, KeyValidationError},
+    debug, debug_println,
+    debugging::log_error,
+    models::Username,
+};
 
 pub fn extract_api_key_from_headers(headers: HeaderMap) -> Result<String, StatusCode> {
     debug!(&headers);
@@ -15,7 +29,8 @@ pub fn extract_api_key_from_headers(headers: HeaderMap) -> Result<String, Status
         .to_string();
 
     // Decode the api key
-    let api_key = base64::decode(api_key)
+    let api_key = base64::engine::general_purpose::STANDARD
+        .decode(api_key)
         .map_err(log_error)
         .map_err(|_| StatusCode::UNAUTHORIZED)?;
 
@@ -29,3 +44,21 @@ pub fn internal_server_error<E: std::fmt::Debug>(e: E) -> StatusCode {
     eprintln!("INTERNAL_SERVER_ERROR serializing package: {:?}", e);
     StatusCode::INTERNAL_SERVER_ERROR
 }
+
+/// Returns the user the key belongs to, see `AccountService::authenticate`
+pub async fn verify_user_key(
+    username: &Username,
+    api_key: &str,
+    account_service: &impl AccountService,
+) -> Result<Username, StatusCode> {
+    debug_println!("Verifying API key: {:?}", &api_key);
+
+    account_service
+        .authenticate(username, api_key)
+        .await
+        .map_err(log_error)
+        .map_err(|e| match e {
+            KeyValidationError::Invalid => StatusCode::UNAUTHORIZED,
+            KeyValidationError::Unknown(e) => internal_server_error(e),
+        })
+}
@@ -0,0 +1,183 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    redirect, ClientBuilder,
+};
+use url::{Host, Url};
+
+const MAX_REDIRECTS: usize = 5;
+
+/// Whether the registry may send requests to `ip`. Private, loopback, link-local (which includes
+/// the cloud metadata address 169.254.169.254) and otherwise reserved addresses are internal.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network"
+        || a == 0
+        // Shared address space (carrier-grade NAT)
+        || (a == 100 && (64..128).contains(&b))
+        // IETF protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking
+        || (a == 198 && (18..20).contains(&b))
+        // Reserved
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+
+    // IPv4-mapped (::ffff:0:0/96) and NAT64 (64:ff9b::/96) addresses reach an IPv4 address
+    if let Some(ipv4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(ipv4);
+    }
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [a, b] = segments[6].to_be_bytes();
+        let [c, d] = segments[7].to_be_bytes();
+        return is_public_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, e.g. fd00:ec2::254
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link-local
+        || (segments[0] & 0xffc0) == 0xfe80
+        // Documentation
+        || (segments[0] == 0x2001 && segments[1] == 0xdb8))
+}
+
+/// Parses a URL that the registry will send requests to: it must be http(s) and its host can't
+/// be an internal address. Host names are checked again when they're resolved, see
+/// `public_client_builder`.
+pub fn check_public_url(url: &str) -> Result<Url, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid URL `{}`: {}", url, e))?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(format!("Invalid URL `{}`: expected an http(s) URL", url));
+    }
+
+    let is_public = match url.host() {
+        Some(Host::Ipv4(ip)) => is_public_ipv4(ip),
+        Some(Host::Ipv6(ip)) => is_public_ipv6(ip),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        None => false,
+    };
+
+    match is_public {
+        true => Ok(url),
+        false => Err(format!(
+            "Invalid URL `{}`: requests to internal addresses are not allowed",
+            url
+        )),
+    }
+}
+
+/// A client builder for requests to URLs given by users. Host names that resolve to internal
+/// addresses can't be connected to, and redirects are only followed to public URLs.
+pub fn public_client_builder() -> ClientBuilder {
+    reqwest::Client::builder()
+        .dns_resolver(Arc::new(PublicAddressResolver))
+        .redirect(redirect::Policy::custom(|attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("Too many redirects")
+            } else if let Err(e) = check_public_url(attempt.url().as_str()) {
+                attempt.error(e)
+            } else {
+                attempt.follow()
+            }
+        }))
+}
+
+/// Resolves host names to their public addresses only, so that a host name can't be pointed at
+/// an internal address after the URL was checked.
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public_ip(address.ip()))
+                .collect::<Vec<SocketAddr>>();
+
+            if addresses.is_empty() {
+                return Err(format!("`{}` has no public address", name.as_str()).into());
+            }
+
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_public_url, is_public_ip};
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00:ec2::254",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+
+        for ip in ["8.8.8.8", "104.16.0.1", "2606:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn checks_urls() {
+        assert!(check_public_url("https://example.com/hook").is_ok());
+        assert!(check_public_url("http://8.8.8.8/hook").is_ok());
+
+        for url in [
+            "ftp://example.com/hook",
+            "not a url",
+            "http://localhost:8080/hook",
+            "http://api.LOCALHOST/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/hook",
+            "http://[::ffff:10.0.0.1]/hook",
+            "http://10.0.0.1/hook",
+        ] {
+            assert!(check_public_url(url).is_err(), "{}", url);
+        }
+    }
+}
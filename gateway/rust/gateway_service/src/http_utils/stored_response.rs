@@ -0,0 +1,51 @@
+use axum::response::{IntoResponse, Response};
+use http::{header, StatusCode};
+use serde::Serialize;
+
+use crate::models::StoredResponse;
+
+use super::{internal_server_error, ApiError};
+
+impl StoredResponse {
+    pub fn empty(status: StatusCode) -> Self {
+        Self {
+            status: status.as_u16(),
+            content_type: None,
+            body: String::new(),
+        }
+    }
+
+    pub fn json(status: StatusCode, body: &impl Serialize) -> Result<Self, ApiError> {
+        Ok(Self {
+            status: status.as_u16(),
+            content_type: Some("application/json".to_string()),
+            body: serde_json::to_string(body).map_err(internal_server_error)?,
+        })
+    }
+}
+
+impl From<ApiError> for StoredResponse {
+    fn from(error: ApiError) -> Self {
+        match error.message {
+            Some(message) => Self {
+                status: error.status.as_u16(),
+                content_type: Some("text/plain; charset=utf-8".to_string()),
+                body: message,
+            },
+            None => Self::empty(error.status),
+        }
+    }
+}
+
+impl IntoResponse for StoredResponse {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        match self.content_type {
+            Some(content_type) => {
+                (status, [(header::CONTENT_TYPE, content_type)], self.body).into_response()
+            }
+            None => status.into_response(),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    models::{IdempotencyRecord, StoredResponse},
+    Repository, RepositoryError,
+};
+
+use super::{error::IdempotencyError, REQUEST_LEASE};
+
+#[derive(Debug, PartialEq)]
+pub enum IdempotentRequest {
+    /// The request has to run, the key is held until it completes
+    Run,
+    /// The request already ran, its response is returned again
+    Replay(StoredResponse),
+}
+
+/// Claims the idempotency key for a request, or returns the response of the request that claimed it.
+pub async fn begin_request(
+    id: &str,
+    request_hash: &str,
+    ttl: Duration,
+    idempotency_repo: &impl Repository<IdempotencyRecord>,
+) -> Result<IdempotentRequest, IdempotencyError> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards");
+
+    let revision = match idempotency_repo.read(id).await {
+        Ok(record) if !record.is_expired(now.as_millis()) => {
+            if record.request_hash != request_hash {
+                return Err(IdempotencyError::KeyReused);
+            }
+
+            if let Some(response) = record.response {
+                return Ok(IdempotentRequest::Replay(response));
+            }
+
+            if record.locked_until > now.as_millis() {
+                return Err(IdempotencyError::InProgress);
+            }
+
+            record.revision
+        }
+        Ok(expired_record) => expired_record.revision,
+        Err(RepositoryError::NotFound) => 0,
+        Err(e) => return Err(IdempotencyError::RepositoryError(e.to_string())),
+    };
+
+    let record = IdempotencyRecord {
+        id: id.to_string(),
+        request_hash: request_hash.to_string(),
+        response: None,
+        locked_until: (now + REQUEST_LEASE).as_millis(),
+        expires_at: now.saturating_add(ttl).as_secs(),
+        revision,
+    };
+
+    idempotency_repo
+        .update(&record)
+        .await
+        .map_err(|e| match e {
+            // Someone else claimed the key since it was read
+            RepositoryError::Conflict => IdempotencyError::InProgress,
+            e => IdempotencyError::RepositoryError(e.to_string()),
+        })?;
+
+    Ok(IdempotentRequest::Run)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use mockall::predicate::eq;
+
+    use crate::{
+        idempotency::{begin_request, IdempotencyError, IdempotentRequest},
+        mocks::MockIdempotencyRepository,
+        models::{IdempotencyRecord, StoredResponse},
+        RepositoryError,
+    };
+
+    const TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn now() -> u128 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    }
+
+    fn record(response: Option<StoredResponse>, locked_until: u128) -> IdempotencyRecord {
+        IdempotencyRecord {
+            id: "idempotency:user1:key1".into(),
+            request_hash: "hash1".into(),
+            response,
+            locked_until,
+            expires_at: (now() / 1000) as u64 + 60,
+            revision: 2,
+        }
+    }
+
+    fn repo_with(record: Option<IdempotencyRecord>) -> MockIdempotencyRepository {
+        let mut idempotency_repo = MockIdempotencyRepository::new();
+
+        idempotency_repo
+            .expect_read()
+            .with(eq("idempotency:user1:key1".to_string()))
+            .return_once(move |_| record.ok_or(RepositoryError::NotFound));
+
+        idempotency_repo
+    }
+
+    #[tokio::test]
+    async fn claims_unused_key() {
+        let mut idempotency_repo = repo_with(None);
+
+        idempotency_repo
+            .expect_update()
+            .withf(|record| {
+                record.request_hash == "hash1"
+                    && record.response.is_none()
+                    && record.locked_until > now()
+                    && record.revision == 0
+            })
+            .return_once(|_| Ok(()));
+
+        let result = begin_request("idempotency:user1:key1", "hash1", TTL, &idempotency_repo).await;
+
+        assert_eq!(result, Ok(IdempotentRequest::Run));
+    }
+
+    #[tokio::test]
+    async fn replays_stored_response() {
+        let response = StoredResponse {
+            status: 200,
+            content_type: None,
+            body: "".into(),
+        };
+        let mut idempotency_repo = repo_with(Some(record(Some(response.clone()), 0)));
+
+        idempotency_repo.expect_update().never();
+
+        let result = begin_request("idempotency:user1:key1", "hash1", TTL, &idempotency_repo).await;
+
+        assert_eq!(result, Ok(IdempotentRequest::Replay(response)));
+    }
+
+    #[tokio::test]
+    async fn rejects_different_request_and_running_request() {
+        let mut idempotency_repo = repo_with(Some(record(None, now() + 10_000)));
+        idempotency_repo.expect_update().never();
+
+        let result = begin_request("idempotency:user1:key1", "hash2", TTL, &idempotency_repo).await;
+        assert_eq!(result, Err(IdempotencyError::KeyReused));
+
+        let mut idempotency_repo = repo_with(Some(record(None, now() + 10_000)));
+        idempotency_repo.expect_update().never();
+
+        let result = begin_request("idempotency:user1:key1", "hash1", TTL, &idempotency_repo).await;
+        assert_eq!(result, Err(IdempotencyError::InProgress));
+    }
+
+    #[tokio::test]
+    async fn runs_again_after_lease_or_ttl_expired() {
+        let mut expired_record = record(None, 0);
+        expired_record.request_hash = "hash2".into();
+        expired_record.expires_at = (now() / 1000) as u64 - 1;
+
+        for record in [record(None, now() - 1), expired_record] {
+            let mut idempotency_repo = repo_with(Some(record));
+
+            idempotency_repo
+                .expect_update()
+                .withf(|record| record.request_hash == "hash1" && record.revision == 2)
+                .return_once(|_| Ok(()));
+
+            let result =
+                begin_request("idempotency:user1:key1", "hash1", TTL, &idempotency_repo).await;
+
+            assert_eq!(result, Ok(IdempotentRequest::Run));
+        }
+    }
+}
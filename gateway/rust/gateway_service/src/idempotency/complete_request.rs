@@ -0,0 +1,29 @@
+use crate::{
+    models::{IdempotencyRecord, StoredResponse},
+    Repository,
+};
+
+use super::error::IdempotencyError;
+
+/// Stores the response of a request for its retries.
+/// Without a response the record expires, so the key can be used again by any request.
+pub async fn complete_request(
+    id: &str,
+    response: Option<StoredResponse>,
+    idempotency_repo: &impl Repository<IdempotencyRecord>,
+) -> Result<(), IdempotencyError> {
+    let mut record = idempotency_repo
+        .read(id)
+        .await
+        .map_err(|e| IdempotencyError::RepositoryError(e.to_string()))?;
+
+    match response {
+        Some(response) => record.response = Some(response),
+        None => record.expires_at = 0,
+    }
+
+    idempotency_repo
+        .update(&record)
+        .await
+        .map_err(|e| IdempotencyError::RepositoryError(e.to_string()))
+}
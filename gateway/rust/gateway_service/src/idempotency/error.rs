@@ -0,0 +1,24 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum IdempotencyError {
+    /// The key was used for a request with a different body
+    KeyReused,
+    InProgress,
+    RepositoryError(String),
+}
+
+impl Display for IdempotencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdempotencyError::KeyReused => write!(
+                f,
+                "The idempotency key was already used for a different request"
+            ),
+            IdempotencyError::InProgress => {
+                write!(f, "A request with the same idempotency key is in progress")
+            }
+            IdempotencyError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
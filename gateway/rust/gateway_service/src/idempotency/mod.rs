@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+pub mod error;
+pub use error::*;
+
+mod request_hash;
+pub use request_hash::request_hash;
+
+mod begin_request;
+pub use begin_request::*;
+
+mod complete_request;
+pub use complete_request::complete_request;
+
+/// How long a request holds its key, a retry after that runs the request again.
+/// Longer than a request can take, so only requests that never finished are run twice.
+pub const REQUEST_LEASE: Duration = Duration::from_secs(60);
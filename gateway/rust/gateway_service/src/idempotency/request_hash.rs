@@ -0,0 +1,9 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Identifies a request by everything that affects its outcome
+pub fn request_hash(request: &impl Serialize) -> String {
+    let request = serde_json::to_vec(request).expect("Requests can always be serialized");
+
+    hex::encode(Sha256::digest(request))
+}
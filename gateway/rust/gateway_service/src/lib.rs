@@ -1,6 +1,9 @@
 mod setup_routes;
 pub use setup_routes::setup_routes;
 
+mod deliver_webhooks;
+pub use deliver_webhooks::deliver_webhooks;
+
 mod db;
 pub use db::*;
 
@@ -17,6 +20,24 @@ mod publishing;
 mod resolving;
 use resolving::*;
 
+mod tagging;
+
+mod yanking;
+
+mod transferring;
+
+mod organizations;
+
+mod redirecting;
+
+mod webhooks;
+
+mod signing;
+
+mod idempotency;
+
+mod fetching;
+
 mod extract_package_and_version;
 use extract_package_and_version::extract_package_and_version;
 
@@ -36,3 +57,6 @@ mod models;
 use models::*;
 
 mod http_utils;
+
+#[cfg(test)]
+mod mocks;
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use mockall::mock;
+
+use crate::models::{
+    IdempotencyRecord, Organization, Package, PublisherKeys, Username, Version, VersionName,
+    WebhookScope, WebhookSubscription, WebhookSubscriptions,
+};
+use crate::webhooks::{WebhookDelivery, WebhookQueue};
+use crate::{
+    AccountService, KeyValidationError, Repository, RepositoryError, VersionPage, VersionRepository,
+};
+
+// Tests use the in-memory implementations, these mocks are for the ones that count calls or
+// make a dependency fail.
+
+mock! {
+    pub PackageRepository {}
+    #[async_trait]
+    impl Repository<Package> for PackageRepository {
+        async fn read(&self, key: &str) -> Result<Package, RepositoryError>;
+        async fn update(&self, entity: &Package) -> Result<(), RepositoryError>;
+        async fn update_many(&self, entities: &[Package]) -> Result<(), RepositoryError>;
+    }
+    #[async_trait]
+    impl VersionRepository for PackageRepository {
+        async fn read_header(&self, package_id: &str) -> Result<Package, RepositoryError>;
+        async fn read_version(&self, package_id: &str, version: &VersionName) -> Result<Version, RepositoryError>;
+        async fn read_versions(&self, package_id: &str, after: Option<VersionName>, limit: usize) -> Result<VersionPage, RepositoryError>;
+        async fn update_version(&self, package: &Package, version: &Version) -> Result<(), RepositoryError>;
+    }
+}
+
+mock! {
+    pub OrgRepository {}
+    #[async_trait]
+    impl Repository<Organization> for OrgRepository {
+        async fn read(&self, key: &str) -> Result<Organization, RepositoryError>;
+        async fn update(&self, entity: &Organization) -> Result<(), RepositoryError>;
+        async fn update_many(&self, entities: &[Organization]) -> Result<(), RepositoryError>;
+    }
+}
+
+mock! {
+    pub PublisherKeyRepository {}
+    #[async_trait]
+    impl Repository<PublisherKeys> for PublisherKeyRepository {
+        async fn read(&self, key: &str) -> Result<PublisherKeys, RepositoryError>;
+        async fn update(&self, entity: &PublisherKeys) -> Result<(), RepositoryError>;
+        async fn update_many(&self, entities: &[PublisherKeys]) -> Result<(), RepositoryError>;
+    }
+}
+
+mock! {
+    pub WebhookRepository {}
+    #[async_trait]
+    impl Repository<WebhookSubscriptions> for WebhookRepository {
+        async fn read(&self, key: &str) -> Result<WebhookSubscriptions, RepositoryError>;
+        async fn update(&self, entity: &WebhookSubscriptions) -> Result<(), RepositoryError>;
+        async fn update_many(&self, entities: &[WebhookSubscriptions]) -> Result<(), RepositoryError>;
+    }
+}
+
+mock! {
+    pub IdempotencyRepository {}
+    #[async_trait]
+    impl Repository<IdempotencyRecord> for IdempotencyRepository {
+        async fn read(&self, key: &str) -> Result<IdempotencyRecord, RepositoryError>;
+        async fn update(&self, entity: &IdempotencyRecord) -> Result<(), RepositoryError>;
+        async fn update_many(&self, entities: &[IdempotencyRecord]) -> Result<(), RepositoryError>;
+    }
+}
+
+mock! {
+    pub AccountService {}
+    #[async_trait]
+    impl AccountService for AccountService {
+        async fn verify_user_key(&self, username: &Username, api_key: &str) -> Result<(), KeyValidationError>;
+    }
+}
+
+mock! {
+    pub WebhookDelivery {}
+    #[async_trait]
+    impl WebhookDelivery for WebhookDelivery {
+        async fn deliver(&self, url: &str, body: &str, timestamp: u64, signature: &str) -> Result<(), String>;
+    }
+}
+
+mock! {
+    pub WebhookQueue {}
+    #[async_trait]
+    impl WebhookQueue for WebhookQueue {
+        async fn enqueue(&self, body: &str, subscriptions: &[(WebhookScope, WebhookSubscription)]) -> Result<(), String>;
+    }
+}
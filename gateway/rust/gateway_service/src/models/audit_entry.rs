@@ -0,0 +1,196 @@
+use std::time::SystemTime;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use super::{Username, VersionName, WrapUri};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Publish,
+    /// The URI of a `latest`-only package was replaced
+    OverwriteLatest,
+    Unpublish,
+    Yank,
+    Promote,
+    SetDistTag,
+    RemoveDistTag,
+    UpdateMetadata,
+    UpdatePolicy,
+    RequestTransfer,
+    CancelTransfer,
+    /// The package moved to another user, recorded under both ids
+    Transfer,
+    SetRedirect,
+    RemoveRedirect,
+    CreateOrganization,
+    SetMemberRole,
+    RemoveMember,
+}
+
+/// A record of a change to a package or organization. Entries are stored as pending before their
+/// change is made and committed once it's made, committed entries are never changed or removed.
+/// Entries of organizations are stored under the organization's key, e.g. `org:org1`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub package_id: String,
+    pub action: AuditAction,
+    /// The version that changed, if the change was to a version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<VersionName>,
+    /// What changed besides the version, e.g. the dist-tag or the recipient of a transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    pub username: Username,
+    pub key_id: String,
+    pub previous_uri: Option<WrapUri>,
+    pub new_uri: Option<WrapUri>,
+    pub created_on: u128,
+    pub request_id: String,
+    /// The change was about to be made when the entry was stored, but wasn't known to be made,
+    /// e.g. because the database failed while making it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pending: bool,
+}
+
+impl AuditEntry {
+    /// Entries of a package sort by the time they were created
+    pub fn entry_id(&self) -> String {
+        let version = self
+            .version
+            .as_ref()
+            .map(|version| version.to_string())
+            .unwrap_or_default();
+
+        format!("{:020}#{}#{}", self.created_on, self.request_id, version)
+    }
+}
+
+/// Who made a change and when, recorded on every audit entry of the request.
+/// Entries of a change are created before it's made and again with its result, both get the same
+/// `entry_id` since they're created with the same context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditContext {
+    pub username: Username,
+    pub key_id: String,
+    pub request_id: String,
+    pub created_on: u128,
+}
+
+impl AuditContext {
+    pub fn new(username: Username, key_id: String, request_id: String) -> Self {
+        let created_on = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        Self {
+            username,
+            key_id,
+            request_id,
+            created_on,
+        }
+    }
+
+    /// An entry of a change to a version
+    pub fn entry(
+        &self,
+        package_id: String,
+        action: AuditAction,
+        version: VersionName,
+        previous_uri: Option<WrapUri>,
+        new_uri: Option<WrapUri>,
+    ) -> AuditEntry {
+        AuditEntry {
+            version: Some(version),
+            previous_uri,
+            new_uri,
+            ..self.change(package_id, action, None)
+        }
+    }
+
+    /// An entry of a change that isn't to a version, e.g. of a dist-tag or the package metadata
+    pub fn change(
+        &self,
+        package_id: String,
+        action: AuditAction,
+        details: Option<String>,
+    ) -> AuditEntry {
+        AuditEntry {
+            package_id,
+            action,
+            version: None,
+            details,
+            username: self.username.clone(),
+            key_id: self.key_id.clone(),
+            previous_uri: None,
+            new_uri: None,
+            created_on: self.created_on,
+            request_id: self.request_id.clone(),
+            pending: false,
+        }
+    }
+}
+
+/// The server secret key ids are derived with.
+/// Audit logs are public, without the secret an id can't be checked against a guessed or leaked key.
+#[derive(Clone)]
+pub struct KeyIdSecret(Vec<u8>);
+
+impl KeyIdSecret {
+    pub fn new(secret: &str) -> Self {
+        Self(secret.as_bytes().to_vec())
+    }
+
+    /// Identifies a key without revealing it
+    pub fn key_id(&self, api_key: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts keys of any size");
+        mac.update(api_key.as_bytes());
+
+        hex::encode(&mac.finalize().into_bytes()[..8])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_records_the_key() {
+        let secret = KeyIdSecret::new("secret1");
+        let key_id = secret.key_id("key1");
+
+        assert_eq!(key_id.len(), 16);
+        assert_ne!(key_id, "key1");
+        assert_eq!(key_id, secret.key_id("key1"));
+        assert_ne!(key_id, secret.key_id("key2"));
+    }
+
+    #[test]
+    fn key_ids_depend_on_the_secret() {
+        assert_ne!(
+            KeyIdSecret::new("secret1").key_id("key1"),
+            KeyIdSecret::new("secret2").key_id("key1")
+        );
+    }
+
+    #[test]
+    fn entry_ids_sort_by_creation_time() {
+        let context = AuditContext::new("user1".parse().unwrap(), "key1".into(), "request1".into());
+        let mut first = context.entry(
+            "user1/package1".into(),
+            AuditAction::Publish,
+            "1.0.0".parse().unwrap(),
+            None,
+            Some("test/uri1".parse().unwrap()),
+        );
+        first.created_on = 999;
+        let mut second = first.clone();
+        second.created_on = 1000;
+
+        assert!(first.entry_id() < second.entry_id());
+    }
+}
@@ -0,0 +1,76 @@
+//! Syntax checks for IPFS content identifiers (https://github.com/multiformats/cid).
+
+const SHA2_256: u64 = 0x12;
+const SHA2_256_LENGTH: usize = 32;
+
+/// Whether the string is a CIDv0 or a CIDv1 in one of the common multibase encodings.
+/// Only the structure is checked, not whether the content exists.
+pub fn is_valid_cid(cid: &str) -> bool {
+    if cid.len() == 46 && cid.starts_with("Qm") {
+        return is_valid_cid_v0(cid);
+    }
+
+    let mut chars = cid.chars();
+    let bytes = match chars.next() {
+        // base32, the default for CIDv1 (bafy...)
+        Some('b') => data_encoding::BASE32_NOPAD
+            .decode(chars.as_str().to_ascii_uppercase().as_bytes())
+            .ok(),
+        Some('B') => data_encoding::BASE32_NOPAD
+            .decode(chars.as_str().as_bytes())
+            .ok(),
+        // base58btc
+        Some('z') => bs58::decode(chars.as_str()).into_vec().ok(),
+        _ => None,
+    };
+
+    matches!(bytes, Some(bytes) if is_valid_cid_v1(&bytes))
+}
+
+/// A base58btc encoded sha2-256 multihash
+fn is_valid_cid_v0(cid: &str) -> bool {
+    match bs58::decode(cid).into_vec() {
+        Ok(bytes) => {
+            bytes.len() == 2 + SHA2_256_LENGTH
+                && bytes[0] as u64 == SHA2_256
+                && bytes[1] as usize == SHA2_256_LENGTH
+        }
+        Err(_) => false,
+    }
+}
+
+/// `<version = 1><codec><multihash code><digest length><digest>`, all numbers as varints
+fn is_valid_cid_v1(bytes: &[u8]) -> bool {
+    let mut rest = bytes;
+
+    let mut next_varint = || {
+        let (value, length) = read_varint(rest)?;
+        rest = &rest[length..];
+        Some(value)
+    };
+
+    let version = next_varint();
+    let codec = next_varint();
+    let hash_code = next_varint();
+    let digest_length = next_varint();
+
+    matches!(
+        (version, codec, hash_code, digest_length),
+        (Some(1), Some(_), Some(_), Some(length)) if length > 0 && rest.len() as u64 == length
+    )
+}
+
+/// Reads an unsigned LEB128 varint, returning its value and how many bytes it used
+fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+
+    None
+}
@@ -0,0 +1,84 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::VersionRange;
+
+use super::LATEST_VERSION_NAME;
+
+lazy_static! {
+    static ref DIST_TAG_REGEX: Regex = Regex::new(r"^[a-zA-Z][a-zA-Z0-9_-]*$").unwrap();
+}
+
+/// A named, movable pointer to a published version, e.g. `stable`, `beta` or `next`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DistTag(String);
+
+impl Display for DistTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug)]
+pub struct DistTagParseError;
+
+impl Display for DistTagParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid dist-tag")
+    }
+}
+
+impl std::error::Error for DistTagParseError {}
+
+impl FromStr for DistTag {
+    type Err = DistTagParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if name.is_empty() || name.len() > 50 {
+            return Err(DistTagParseError);
+        }
+
+        if !DIST_TAG_REGEX.is_match(name) {
+            return Err(DistTagParseError);
+        }
+
+        // Tags share the `@` segment with versions, so they must never be mistaken for one
+        if name == LATEST_VERSION_NAME || name.parse::<VersionRange>().is_ok() {
+            return Err(DistTagParseError);
+        }
+
+        Ok(Self(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_dist_tag() {
+        assert!("beta".parse::<DistTag>().is_ok());
+        assert!("next".parse::<DistTag>().is_ok());
+        assert!("stable".parse::<DistTag>().is_ok());
+        assert!("release-candidate_2".parse::<DistTag>().is_ok());
+        assert!("Beta".parse::<DistTag>().is_ok());
+        assert!("".parse::<DistTag>().is_err());
+        assert!("latest".parse::<DistTag>().is_err());
+        assert!("x".parse::<DistTag>().is_err());
+        assert!("X".parse::<DistTag>().is_err());
+        assert!("v1".parse::<DistTag>().is_err());
+        assert!("1.0.0".parse::<DistTag>().is_err());
+        assert!("-beta".parse::<DistTag>().is_err());
+        assert!("beta.1".parse::<DistTag>().is_err());
+        assert!("beta/1".parse::<DistTag>().is_err());
+        assert!("t12345678901234567890123456789012345678901234567890"
+            .parse::<DistTag>()
+            .is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use super::Username;
+
+/// A request made with an `Idempotency-Key`, and its response once it finished.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct IdempotencyRecord {
+    pub id: String,
+    /// Retries with the same key have to be the same request
+    pub request_hash: String,
+    /// Replayed to retries, `None` while the request is in progress
+    pub response: Option<StoredResponse>,
+    /// Until when the request holds the key, after that a retry can run it again
+    pub locked_until: u128,
+    /// Seconds since the epoch, after which the record is ignored and deleted
+    pub expires_at: u64,
+    #[serde(default)]
+    pub revision: u64,
+}
+
+impl IdempotencyRecord {
+    /// The key the record is stored under, which can never be a package id
+    pub fn key(user: &Username, idempotency_key: &str) -> String {
+        format!("idempotency:{}:{}", user.canonical(), idempotency_key)
+    }
+
+    pub fn is_expired(&self, now: u128) -> bool {
+        u128::from(self.expires_at) * 1000 <= now
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: String,
+}
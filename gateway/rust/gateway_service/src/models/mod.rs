@@ -1,14 +1,58 @@
 mod package;
 pub use package::*;
 
+mod package_metadata;
+pub use package_metadata::*;
+
 mod version;
 pub use version::*;
 
+mod yank;
+pub use yank::Yank;
+
+mod version_name;
+pub use version_name::*;
+
 mod wrap_uri;
 pub use wrap_uri::WrapUri;
 
+mod cid;
+
+mod uri_authority;
+pub use uri_authority::*;
+
+mod reserved_names;
+pub use reserved_names::*;
+
 mod username;
 pub use username::Username;
 
 mod package_name;
 pub use package_name::PackageName;
+
+mod dist_tag;
+pub use dist_tag::DistTag;
+
+mod version_selector;
+pub use version_selector::*;
+
+mod webhook;
+pub use webhook::*;
+
+mod publish_policy;
+pub use publish_policy::*;
+
+mod wrap_manifest_info;
+pub use wrap_manifest_info::WrapManifestInfo;
+
+mod audit_entry;
+pub use audit_entry::*;
+
+mod publisher_key;
+pub use publisher_key::*;
+
+mod idempotency_record;
+pub use idempotency_record::*;
+
+mod organization;
+pub use organization::*;
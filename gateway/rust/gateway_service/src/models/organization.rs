@@ -0,0 +1,85 @@
+use std::{fmt::Display, time::SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use super::Username;
+
+/// What a member may do in the namespace of an organization, each role can do everything the roles before it can.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum OrgRole {
+    /// Publishes versions
+    Publisher,
+    /// Manages packages: tags, yanks, unpublishes, metadata, policies and webhooks
+    Maintainer,
+    /// Manages members, publisher keys and transfers
+    Owner,
+}
+
+impl Display for OrgRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrgRole::Publisher => write!(f, "publisher"),
+            OrgRole::Maintainer => write!(f, "maintainer"),
+            OrgRole::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct OrgMember {
+    pub username: Username,
+    pub role: OrgRole,
+    pub added_on: u128,
+}
+
+impl OrgMember {
+    pub fn new(username: Username, role: OrgRole) -> Self {
+        let added_on = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        Self {
+            username,
+            role,
+            added_on,
+        }
+    }
+}
+
+/// A namespace owned by its members rather than a single account, stored as a single entity.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Organization {
+    pub id: String,
+    pub name: Username,
+    pub members: Vec<OrgMember>,
+    #[serde(default)]
+    pub revision: u64,
+}
+
+impl Organization {
+    pub fn new(name: Username, owner: Username) -> Self {
+        Self {
+            id: Self::key(&name),
+            name,
+            members: vec![OrgMember::new(owner, OrgRole::Owner)],
+            revision: 0,
+        }
+    }
+
+    /// The key the organization is stored under, which can never be a package id
+    pub fn key(name: &Username) -> String {
+        format!("org:{}", name.canonical())
+    }
+
+    /// Checks that `name` has the casing the organization was created with, see
+    /// `Package::check_owner`. Returns the name of the organization when it doesn't
+    pub fn check_name(&self, name: &Username) -> Result<(), String> {
+        if self.name != *name {
+            return Err(self.name.to_string());
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
-use std::time::SystemTime;
+use std::{collections::BTreeMap, time::SystemTime};
 
 use serde::{Deserialize, Serialize};
 
-use super::{PackageName, Username, Version};
+use crate::SemVer;
+
+use super::{DistTag, PackageMetadata, PackageName, PublishPolicy, Username, Version, WrapUri};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Package {
@@ -11,6 +13,24 @@ pub struct Package {
     pub user: Username,
     pub versions: Vec<Version>,
     pub created_on: u128,
+    #[serde(default)]
+    pub metadata: PackageMetadata,
+    #[serde(default)]
+    pub dist_tags: BTreeMap<DistTag, SemVer>,
+    #[serde(default)]
+    pub policy: PublishPolicy,
+    /// Tombstones of unpublished versions, so they can never point to a different URI
+    #[serde(default)]
+    pub unpublished_versions: BTreeMap<SemVer, WrapUri>,
+    /// The user the owner offered the package to, the package moves once they accept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_transfer: Option<Username>,
+    /// The id of the package this id now resolves to. A redirect has no versions of its own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<String>,
+    /// Incremented on every write, updates only succeed if the stored revision still matches
+    #[serde(default)]
+    pub revision: u64,
 }
 
 impl PartialEq for Package {
@@ -21,7 +41,7 @@ impl PartialEq for Package {
 
 impl Package {
     pub fn new(name: PackageName, user: Username) -> Self {
-        let id = format!("{}/{}", user, name);
+        let id = Self::id_of(&user, &name);
 
         let created_on = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
@@ -34,6 +54,49 @@ impl Package {
             name,
             versions: vec![],
             created_on,
+            metadata: PackageMetadata::default(),
+            dist_tags: BTreeMap::new(),
+            policy: PublishPolicy::default(),
+            unpublished_versions: BTreeMap::new(),
+            pending_transfer: None,
+            redirect: None,
+            revision: 0,
+        }
+    }
+
+    /// The id a package is stored and looked up under. Ids are lowercase so names that only
+    /// differ in casing can not be told apart, `user` and `name` keep the published casing
+    pub fn id_of(user: &Username, name: &PackageName) -> String {
+        format!("{}/{}", user.canonical(), name.canonical())
+    }
+
+    /// The id packages published before ids were lowercase are stored under, as it was typed
+    pub fn legacy_id_of(user: &Username, name: &PackageName) -> String {
+        format!("{}/{}", user, name)
+    }
+
+    /// Checks that `user` and `name` have the casing the package was created with. Ids are
+    /// lowercase, so the account of a name that only differs in casing would otherwise be able
+    /// to change the package. Returns the name the package is published as when they don't
+    pub fn check_owner(&self, user: &Username, name: &PackageName) -> Result<(), String> {
+        if self.user != *user || self.name != *name {
+            return Err(format!("{}/{}", self.user, self.name));
         }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Package;
+
+    #[test]
+    fn ids_are_lowercase_and_names_keep_their_casing() {
+        let package = Package::new("Foo-Bar".parse().unwrap(), "Polywrap".parse().unwrap());
+
+        assert_eq!(package.id, "polywrap/foo-bar");
+        assert_eq!(package.user.to_string(), "Polywrap");
+        assert_eq!(package.name.to_string(), "Foo-Bar");
     }
 }
@@ -0,0 +1,169 @@
+use std::fmt::{self, Display, Formatter};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+const MAX_DESCRIPTION_LENGTH: usize = 500;
+const MAX_KEYWORDS: usize = 20;
+const MAX_KEYWORD_LENGTH: usize = 50;
+
+lazy_static! {
+    static ref KEYWORD_REGEX: Regex = Regex::new(r"^[a-z0-9][a-z0-9-]*$").unwrap();
+}
+
+/// Package-level information shown to consumers, independent of any version.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PackageMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// An SPDX license expression, e.g. `MIT` or `MIT OR Apache-2.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PackageMetadataError {
+    DescriptionTooLong,
+    TooManyKeywords,
+    InvalidKeyword(String),
+    InvalidLicense(String),
+    InvalidRepository(String),
+    InvalidHomepage(String),
+}
+
+impl Display for PackageMetadataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PackageMetadataError::DescriptionTooLong => write!(
+                f,
+                "Description must be at most {} characters",
+                MAX_DESCRIPTION_LENGTH
+            ),
+            PackageMetadataError::TooManyKeywords => {
+                write!(f, "At most {} keywords are allowed", MAX_KEYWORDS)
+            }
+            PackageMetadataError::InvalidKeyword(keyword) => write!(
+                f,
+                "Invalid keyword `{}`: keywords must be lowercase alphanumeric with dashes and at most {} characters",
+                keyword, MAX_KEYWORD_LENGTH
+            ),
+            PackageMetadataError::InvalidLicense(license) => {
+                write!(f, "Invalid license `{}`: expected an SPDX license expression", license)
+            }
+            PackageMetadataError::InvalidRepository(url) => {
+                write!(f, "Invalid repository `{}`: expected an http(s) URL", url)
+            }
+            PackageMetadataError::InvalidHomepage(url) => {
+                write!(f, "Invalid homepage `{}`: expected an http(s) URL", url)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PackageMetadataError {}
+
+impl PackageMetadata {
+    pub fn validate(&self) -> Result<(), PackageMetadataError> {
+        if matches!(&self.description, Some(description) if description.chars().count() > MAX_DESCRIPTION_LENGTH)
+        {
+            return Err(PackageMetadataError::DescriptionTooLong);
+        }
+
+        if self.keywords.len() > MAX_KEYWORDS {
+            return Err(PackageMetadataError::TooManyKeywords);
+        }
+
+        if let Some(keyword) = self
+            .keywords
+            .iter()
+            .find(|keyword| keyword.len() > MAX_KEYWORD_LENGTH || !KEYWORD_REGEX.is_match(keyword))
+        {
+            return Err(PackageMetadataError::InvalidKeyword(keyword.clone()));
+        }
+
+        if let Some(license) = &self.license {
+            spdx::Expression::parse(license)
+                .map_err(|_| PackageMetadataError::InvalidLicense(license.clone()))?;
+        }
+
+        if let Some(repository) = &self.repository {
+            if !is_http_url(repository) {
+                return Err(PackageMetadataError::InvalidRepository(repository.clone()));
+            }
+        }
+
+        if let Some(homepage) = &self.homepage {
+            if !is_http_url(homepage) {
+                return Err(PackageMetadataError::InvalidHomepage(homepage.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    matches!(Url::parse(url), Ok(url) if url.scheme() == "http" || url.scheme() == "https")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> PackageMetadata {
+        PackageMetadata {
+            description: Some("Reads and writes files".into()),
+            keywords: vec!["fs".into(), "file-system".into()],
+            license: Some("MIT OR Apache-2.0".into()),
+            repository: Some("https://github.com/polywrap/fs".into()),
+            homepage: Some("https://polywrap.io".into()),
+        }
+    }
+
+    #[test]
+    fn validate_package_metadata() {
+        assert_eq!(metadata().validate(), Ok(()));
+        assert_eq!(PackageMetadata::default().validate(), Ok(()));
+
+        let invalid = |update: fn(&mut PackageMetadata)| {
+            let mut metadata = metadata();
+            update(&mut metadata);
+            metadata.validate()
+        };
+
+        assert_eq!(
+            invalid(|m| m.description = Some("a".repeat(501))),
+            Err(PackageMetadataError::DescriptionTooLong)
+        );
+        assert_eq!(
+            invalid(|m| m.keywords = vec!["fs".into(); 21]),
+            Err(PackageMetadataError::TooManyKeywords)
+        );
+        assert_eq!(
+            invalid(|m| m.keywords = vec!["File System".into()]),
+            Err(PackageMetadataError::InvalidKeyword("File System".into()))
+        );
+        assert_eq!(
+            invalid(|m| m.license = Some("Not-A-License".into())),
+            Err(PackageMetadataError::InvalidLicense("Not-A-License".into()))
+        );
+        assert_eq!(
+            invalid(|m| m.repository = Some("ftp://example.com".into())),
+            Err(PackageMetadataError::InvalidRepository(
+                "ftp://example.com".into()
+            ))
+        );
+        assert_eq!(
+            invalid(|m| m.homepage = Some("polywrap.io".into())),
+            Err(PackageMetadataError::InvalidHomepage("polywrap.io".into()))
+        );
+    }
+}
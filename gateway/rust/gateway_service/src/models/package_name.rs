@@ -3,12 +3,24 @@ use std::{
     str::FromStr,
 };
 
+use lazy_static::lazy_static;
 use regex::Regex;
 use serde_derive::{Deserialize, Serialize};
 
+lazy_static! {
+    static ref PACKAGE_NAME_REGEX: Regex = Regex::new(r"^[a-zA-Z0-9_-]*$").unwrap();
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct PackageName(String);
 
+impl PackageName {
+    /// The lowercase form names are unique by, the original casing is kept for display
+    pub fn canonical(&self) -> String {
+        self.0.to_ascii_lowercase()
+    }
+}
+
 impl Display for PackageName {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -34,9 +46,7 @@ impl FromStr for PackageName {
             return Err(&PackageNameParseError);
         }
 
-        let re = Regex::new(r"^[a-zA-Z0-9_-]*$").unwrap();
-
-        if !re.is_match(name) {
+        if !PACKAGE_NAME_REGEX.is_match(name) {
             return Err(&PackageNameParseError);
         }
 
@@ -0,0 +1,202 @@
+use std::fmt::{self, Display, Formatter};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Rules set by the package owner that every new version has to follow.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PublishPolicy {
+    /// A new version must be greater than every published version with the same major
+    #[serde(default)]
+    pub monotonic_within_major: bool,
+    /// A version with a new major can only be published with the `allow_major` flag
+    #[serde(default)]
+    pub require_major_flag: bool,
+    /// The prerelease of a version (e.g. `beta.1` in `1.0.0-beta.1`) must fully match this regex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prerelease_pattern: Option<PrereleasePattern>,
+    /// URIs must use one of these authorities (e.g. `ipfs`), any authority is allowed if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_uri_authorities: Vec<String>,
+}
+
+/// A prerelease regex, compiled once when it's deserialized or created.
+///
+/// An invalid pattern is kept as written so `PublishPolicy::validate` can report it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct PrereleasePattern {
+    pattern: String,
+    regex: Option<Regex>,
+}
+
+impl From<String> for PrereleasePattern {
+    fn from(pattern: String) -> Self {
+        let regex = Regex::new(&anchored(&pattern)).ok();
+        Self { pattern, regex }
+    }
+}
+
+impl From<&str> for PrereleasePattern {
+    fn from(pattern: &str) -> Self {
+        pattern.to_string().into()
+    }
+}
+
+impl From<PrereleasePattern> for String {
+    fn from(pattern: PrereleasePattern) -> Self {
+        pattern.pattern
+    }
+}
+
+impl PartialEq for PrereleasePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PublishPolicyError {
+    InvalidPrereleasePattern(String),
+    InvalidUriAuthority(String),
+}
+
+impl Display for PublishPolicyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PublishPolicyError::InvalidPrereleasePattern(pattern) => {
+                write!(
+                    f,
+                    "Invalid prerelease pattern `{}`: expected a regex",
+                    pattern
+                )
+            }
+            PublishPolicyError::InvalidUriAuthority(authority) => write!(
+                f,
+                "Invalid URI authority `{}`: expected an authority like `ipfs` or `http`",
+                authority
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PublishPolicyError {}
+
+impl PublishPolicy {
+    pub fn validate(&self) -> Result<(), PublishPolicyError> {
+        if let Some(pattern) = &self.prerelease_pattern {
+            if pattern.regex.is_none() {
+                return Err(PublishPolicyError::InvalidPrereleasePattern(
+                    pattern.pattern.clone(),
+                ));
+            }
+        }
+
+        if let Some(authority) = self
+            .allowed_uri_authorities
+            .iter()
+            .find(|authority| !is_uri_authority(authority))
+        {
+            return Err(PublishPolicyError::InvalidUriAuthority(authority.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Whether the prerelease is allowed by the prerelease pattern, if there is one
+    pub fn allows_prerelease(&self, prerelease: &str) -> bool {
+        match &self.prerelease_pattern {
+            Some(pattern) => {
+                matches!(&pattern.regex, Some(regex) if regex.is_match(prerelease))
+            }
+            None => true,
+        }
+    }
+
+    pub fn allows_uri_authority(&self, authority: &str) -> bool {
+        self.allowed_uri_authorities.is_empty()
+            || self.allowed_uri_authorities.iter().any(|a| a == authority)
+    }
+}
+
+fn anchored(pattern: &str) -> String {
+    format!("^(?:{})$", pattern)
+}
+
+/// Same rule as the authority of a WRAP URI
+fn is_uri_authority(authority: &str) -> bool {
+    let mut chars = authority.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+        && authority.len() > 1
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_publish_policy() {
+        let policy = PublishPolicy {
+            monotonic_within_major: true,
+            require_major_flag: true,
+            prerelease_pattern: Some(r"(alpha|beta|rc)\.\d+".into()),
+            allowed_uri_authorities: vec!["ipfs".into(), "http".into()],
+        };
+        assert_eq!(policy.validate(), Ok(()));
+        assert_eq!(PublishPolicy::default().validate(), Ok(()));
+
+        assert_eq!(
+            PublishPolicy {
+                prerelease_pattern: Some("(beta".into()),
+                ..Default::default()
+            }
+            .validate(),
+            Err(PublishPolicyError::InvalidPrereleasePattern("(beta".into()))
+        );
+        assert_eq!(
+            PublishPolicy {
+                allowed_uri_authorities: vec!["IPFS".into()],
+                ..Default::default()
+            }
+            .validate(),
+            Err(PublishPolicyError::InvalidUriAuthority("IPFS".into()))
+        );
+    }
+
+    #[test]
+    fn prerelease_pattern_must_fully_match() {
+        let policy = PublishPolicy {
+            prerelease_pattern: Some(r"(alpha|beta|rc)\.\d+".into()),
+            ..Default::default()
+        };
+
+        assert!(policy.allows_prerelease("beta.1"));
+        assert!(!policy.allows_prerelease("beta"));
+        assert!(!policy.allows_prerelease("nightly.beta.1"));
+        assert!(PublishPolicy::default().allows_prerelease("anything"));
+    }
+
+    #[test]
+    fn prerelease_pattern_is_stored_as_written() {
+        let policy: PublishPolicy =
+            serde_json::from_value(serde_json::json!({ "prerelease_pattern": "beta\\.\\d+" }))
+                .unwrap();
+
+        assert!(policy.allows_prerelease("beta.1"));
+        assert_eq!(
+            serde_json::to_value(&policy).unwrap()["prerelease_pattern"],
+            "beta\\.\\d+"
+        );
+
+        let policy: PublishPolicy =
+            serde_json::from_value(serde_json::json!({ "prerelease_pattern": "(beta" })).unwrap();
+
+        assert!(!policy.allows_prerelease("beta"));
+        assert_eq!(
+            policy.validate(),
+            Err(PublishPolicyError::InvalidPrereleasePattern("(beta".into()))
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{PackageName, Username};
+
+/// An Ed25519 public key a user signs their publishes with.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PublisherKey {
+    pub id: String,
+    /// Base64 encoded, 32 bytes
+    pub public_key: String,
+    pub created_on: u128,
+}
+
+impl PublisherKey {
+    pub fn new(public_key: String) -> Self {
+        let created_on = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        Self {
+            id: key_id(&public_key),
+            public_key,
+            created_on,
+        }
+    }
+}
+
+/// The id is derived from the key, so registering the same key twice replaces it
+fn key_id(public_key: &str) -> String {
+    hex::encode(&Sha256::digest(public_key.as_bytes())[..8])
+}
+
+/// All publisher keys of a user, stored as a single entity.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PublisherKeys {
+    pub id: String,
+    pub keys: Vec<PublisherKey>,
+    #[serde(default)]
+    pub revision: u64,
+}
+
+impl PublisherKeys {
+    pub fn new(user: &Username) -> Self {
+        Self {
+            id: Self::key(user),
+            keys: vec![],
+            revision: 0,
+        }
+    }
+
+    /// The key the publisher keys are stored under, which can never be a package id
+    pub fn key(user: &Username) -> String {
+        format!("keys:{}", user.canonical())
+    }
+}
+
+/// A detached signature of a published version, made with one of the publisher's keys.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct VersionSignature {
+    pub key_id: String,
+    /// Base64 encoded, 64 bytes
+    pub signature: String,
+    /// Set once the version moved to another package: the signed message names the user and
+    /// package of the origin instead, and the key is one of the origin user's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SignatureOrigin>,
+}
+
+/// The package a version was published and signed in, before it moved.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SignatureOrigin {
+    pub user: Username,
+    pub package: PackageName,
+}
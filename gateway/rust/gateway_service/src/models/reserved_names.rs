@@ -0,0 +1,97 @@
+use crate::constants::{DEFAULT_RESERVED_NAMES, DEFAULT_RESERVED_NAMES_ALLOWLIST};
+
+use super::{PackageName, Username};
+
+/// Names only allowlisted accounts may claim, e.g. `polywrap,admin,wrapscan`.
+/// Names are compared regardless of case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReservedNames {
+    names: Vec<String>,
+    allowlist: Vec<String>,
+}
+
+impl Default for ReservedNames {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESERVED_NAMES, DEFAULT_RESERVED_NAMES_ALLOWLIST)
+    }
+}
+
+impl ReservedNames {
+    /// Both lists are comma separated
+    pub fn new(names: &str, allowlist: &str) -> Self {
+        Self {
+            names: parse_list(names),
+            allowlist: parse_list(allowlist),
+        }
+    }
+
+    /// Checks that the user may claim the package, returns the reserved name otherwise
+    pub fn check(&self, user: &Username, package_name: &PackageName) -> Result<(), String> {
+        if self.allowlist.contains(&user.canonical()) {
+            return Ok(());
+        }
+
+        self.check_user(user)?;
+
+        if self.names.contains(&package_name.canonical()) {
+            return Err(package_name.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the namespace may be claimed by its account, returns the reserved name otherwise
+    pub fn check_user(&self, user: &Username) -> Result<(), String> {
+        if self.names.contains(&user.canonical()) && !self.allowlist.contains(&user.canonical()) {
+            return Err(user.to_string());
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|name| name.trim().to_ascii_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(user: &str, package_name: &str) -> Result<(), String> {
+        ReservedNames::new("polywrap, Admin,wrapscan", "polywrap,trusted_user")
+            .check(&user.parse().unwrap(), &package_name.parse().unwrap())
+    }
+
+    #[test]
+    fn reserved_names_are_rejected_regardless_of_case() {
+        assert_eq!(check("user1", "package1"), Ok(()));
+        assert_eq!(check("user1", "ADMIN"), Err("ADMIN".into()));
+        assert_eq!(check("user1", "Polywrap"), Err("Polywrap".into()));
+        assert_eq!(check("WrapScan", "package1"), Err("WrapScan".into()));
+    }
+
+    #[test]
+    fn allowlisted_accounts_can_claim_reserved_names() {
+        assert_eq!(check("polywrap", "admin"), Ok(()));
+        assert_eq!(check("Trusted_User", "wrapscan"), Ok(()));
+    }
+
+    #[test]
+    fn only_allowlisted_accounts_can_claim_reserved_namespaces() {
+        let reserved_names = ReservedNames::new("polywrap,admin", "polywrap");
+
+        assert_eq!(reserved_names.check_user(&"org1".parse().unwrap()), Ok(()));
+        assert_eq!(
+            reserved_names.check_user(&"Polywrap".parse().unwrap()),
+            Ok(())
+        );
+        assert_eq!(
+            reserved_names.check_user(&"ADMIN".parse().unwrap()),
+            Err("ADMIN".into())
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use url::Url;
+
+use super::{cid, WrapUri};
+
+/// The kind of a WRAP URI, decided by its authority (`wrap://<authority>/<path>`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum UriAuthority {
+    Ipfs,
+    Http,
+    Https,
+    Ens,
+    Wrapscan,
+    /// Local paths, which only resolve on the machine that published them
+    Fs,
+    File,
+    Other(String),
+}
+
+impl UriAuthority {
+    pub fn of(uri: &WrapUri) -> Self {
+        match uri.authority() {
+            "ipfs" => UriAuthority::Ipfs,
+            "http" => UriAuthority::Http,
+            "https" => UriAuthority::Https,
+            "ens" => UriAuthority::Ens,
+            "wrapscan" => UriAuthority::Wrapscan,
+            "fs" => UriAuthority::Fs,
+            "file" => UriAuthority::File,
+            other => UriAuthority::Other(other.to_string()),
+        }
+    }
+
+    /// Checks that the path is meaningful for the authority, e.g. that an `ipfs` path is a CID
+    pub fn validate_path(&self, path: &str) -> Result<(), UriAuthorityError> {
+        let is_valid = match self {
+            UriAuthority::Ipfs => {
+                let cid = path.split('/').next().unwrap_or_default();
+                cid::is_valid_cid(cid)
+            }
+            UriAuthority::Http | UriAuthority::Https => {
+                matches!(Url::parse(&format!("{}://{}", self, path)), Ok(url) if url.host().is_some())
+            }
+            UriAuthority::Ens => is_ens_path(path),
+            UriAuthority::Wrapscan => is_wrapscan_path(path),
+            UriAuthority::Fs | UriAuthority::File | UriAuthority::Other(_) => !path.is_empty(),
+        };
+
+        match is_valid {
+            true => Ok(()),
+            false => Err(UriAuthorityError::InvalidPath {
+                authority: self.clone(),
+                path: path.to_string(),
+            }),
+        }
+    }
+
+    fn expected_path(&self) -> &'static str {
+        match self {
+            UriAuthority::Ipfs => "a CIDv0 (Qm...) or CIDv1 (bafy...)",
+            UriAuthority::Http | UriAuthority::Https => "a host with an optional path",
+            UriAuthority::Ens => {
+                "an ENS domain (e.g. wraps.eth), optionally with a network and a `:` path"
+            }
+            UriAuthority::Wrapscan => "a user and package (e.g. polywrap/ethereum@1.0)",
+            UriAuthority::Fs | UriAuthority::File | UriAuthority::Other(_) => "a non-empty path",
+        }
+    }
+}
+
+impl Display for UriAuthority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UriAuthority::Ipfs => write!(f, "ipfs"),
+            UriAuthority::Http => write!(f, "http"),
+            UriAuthority::Https => write!(f, "https"),
+            UriAuthority::Ens => write!(f, "ens"),
+            UriAuthority::Wrapscan => write!(f, "wrapscan"),
+            UriAuthority::Fs => write!(f, "fs"),
+            UriAuthority::File => write!(f, "file"),
+            UriAuthority::Other(authority) => write!(f, "{}", authority),
+        }
+    }
+}
+
+/// The URI authorities a deployment accepts for publishing, e.g. `ipfs,https,ens`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllowedUriAuthorities(Vec<UriAuthority>);
+
+impl Default for AllowedUriAuthorities {
+    fn default() -> Self {
+        Self(vec![
+            UriAuthority::Ipfs,
+            UriAuthority::Http,
+            UriAuthority::Https,
+            UriAuthority::Ens,
+            UriAuthority::Wrapscan,
+        ])
+    }
+}
+
+impl AllowedUriAuthorities {
+    /// Checks that the authority of the URI is allowed and that its path is valid for it
+    pub fn check(&self, uri: &WrapUri) -> Result<(), UriAuthorityError> {
+        let authority = UriAuthority::of(uri);
+
+        if !self.0.contains(&authority) {
+            return Err(UriAuthorityError::NotAllowed {
+                authority,
+                allowed: self.clone(),
+            });
+        }
+
+        authority.validate_path(uri.path())
+    }
+}
+
+impl Display for AllowedUriAuthorities {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let authorities = self.0.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        write!(f, "{}", authorities.join(", "))
+    }
+}
+
+impl FromStr for AllowedUriAuthorities {
+    type Err = String;
+
+    fn from_str(authorities: &str) -> Result<Self, Self::Err> {
+        authorities
+            .split(',')
+            .map(|authority| authority.trim())
+            .filter(|authority| !authority.is_empty())
+            .map(|authority| {
+                format!("wrap://{}/path", authority)
+                    .parse::<WrapUri>()
+                    .map(|uri| UriAuthority::of(&uri))
+                    .map_err(|_| format!("Invalid URI authority `{}`", authority))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(AllowedUriAuthorities)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UriAuthorityError {
+    NotAllowed {
+        authority: UriAuthority,
+        allowed: AllowedUriAuthorities,
+    },
+    InvalidPath {
+        authority: UriAuthority,
+        path: String,
+    },
+}
+
+impl Display for UriAuthorityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UriAuthorityError::NotAllowed { authority, allowed } => write!(
+                f,
+                "URI authority `{}` is not accepted by this registry, expected one of: {}",
+                authority, allowed
+            ),
+            UriAuthorityError::InvalidPath { authority, path } => write!(
+                f,
+                "Invalid `{}` URI path `{}`: expected {}",
+                authority,
+                path,
+                authority.expected_path()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UriAuthorityError {}
+
+/// `[network/]domain[:path]`, e.g. `wraps.eth`, `goerli/wraps.eth` or `wraps.eth:ethereum@1.0.0`
+fn is_ens_path(path: &str) -> bool {
+    let name = path.split(':').next().unwrap_or_default();
+    let domain = name.rsplit('/').next().unwrap_or_default();
+    let labels = domain.split('.').collect::<Vec<_>>();
+
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// `user/package[@version]`, e.g. `polywrap/ethereum@1.0`
+fn is_wrapscan_path(path: &str) -> bool {
+    let segments = path.split('/').collect::<Vec<_>>();
+
+    segments.len() >= 2 && segments.iter().all(|segment| !segment.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(allowed: &str, uri: &str) -> Result<(), UriAuthorityError> {
+        allowed
+            .parse::<AllowedUriAuthorities>()
+            .unwrap()
+            .check(&uri.parse().unwrap())
+    }
+
+    #[test]
+    fn classify_uri_authorities() {
+        let authority = |uri: &str| UriAuthority::of(&uri.parse().unwrap());
+
+        assert_eq!(authority("wrap://ipfs/QmHash"), UriAuthority::Ipfs);
+        assert_eq!(authority("https/example.com/wrap"), UriAuthority::Https);
+        assert_eq!(authority("ens/wraps.eth:ethereum@1.0.0"), UriAuthority::Ens);
+        assert_eq!(authority("fs/./build"), UriAuthority::Fs);
+        assert_eq!(authority("test/uri"), UriAuthority::Other("test".into()));
+    }
+
+    #[test]
+    fn only_allowed_authorities_are_accepted() {
+        assert!(check("ipfs,ens", "ens/wraps.eth").is_ok());
+        assert_eq!(
+            check("ipfs,ens", "fs/./build"),
+            Err(UriAuthorityError::NotAllowed {
+                authority: UriAuthority::Fs,
+                allowed: "ipfs,ens".parse().unwrap(),
+            })
+        );
+        assert!(check("test", "test/uri").is_ok());
+        assert!("ipfs,Not An Authority"
+            .parse::<AllowedUriAuthorities>()
+            .is_err());
+    }
+
+    #[test]
+    fn validate_paths_per_authority() {
+        let allowed = "ipfs,http,https,ens,wrapscan";
+
+        for uri in [
+            "ipfs/QmcT1aYdNwtbaGNJRbE8qkb9aPnkAU8P4Mf2xZZRxdm8yN",
+            "ipfs/QmcT1aYdNwtbaGNJRbE8qkb9aPnkAU8P4Mf2xZZRxdm8yN/wrap.info",
+            "ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+            "https/example.com/wraps/ethereum",
+            "http/localhost:3000/wrap",
+            "ens/wraps.eth",
+            "ens/goerli/wraps.eth:ethereum@1.0.0",
+            "wrapscan/polywrap/ethereum@1.0",
+        ] {
+            assert_eq!(check(allowed, uri), Ok(()), "{}", uri);
+        }
+
+        for uri in [
+            "ipfs/QmHash",
+            "ipfs/bafy-not-a-cid",
+            // A truncated CIDv0, which no longer decodes to a whole multihash
+            "ipfs/QmcT1aYdNwtbaGNJRbE8qkb9aPnkAU8P4Mf2xZZRxdm8y",
+            "https/",
+            "ens/wraps",
+            "wrapscan/polywrap",
+        ] {
+            assert!(
+                matches!(
+                    check(allowed, uri),
+                    Err(UriAuthorityError::InvalidPath { .. })
+                ),
+                "{}",
+                uri
+            );
+        }
+    }
+}
@@ -2,15 +2,27 @@ use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 
-use crate::IVersion;
+use crate::{IVersion, SemVer};
 
-use super::WrapUri;
+use super::{
+    PackageName, SignatureOrigin, Username, VersionName, VersionSignature, WrapManifestInfo,
+    WrapUri, Yank,
+};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Version {
-    pub name: String,
+    pub name: VersionName,
     pub uri: WrapUri,
     pub created_on: u128,
+    /// Yanked versions are only resolved by their exact version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yanked: Option<Yank>,
+    /// Recorded from the `wrap.info` when the manifest was verified on publish
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest: Option<WrapManifestInfo>,
+    /// Made by the publisher over the user, package, version and URI, see `signing::signed_message`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<VersionSignature>,
 }
 
 impl PartialEq for Version {
@@ -20,7 +32,7 @@ impl PartialEq for Version {
 }
 
 impl Version {
-    pub fn new(name: String, uri: WrapUri) -> Self {
+    pub fn new(name: VersionName, uri: WrapUri) -> Self {
         let created_on = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .expect("Time went backwards")
@@ -30,23 +42,31 @@ impl Version {
             name,
             uri,
             created_on,
+            yanked: None,
+            manifest: None,
+            signature: None,
         }
     }
-}
 
-impl IVersion for Version {
-    fn name(&self) -> String {
-        self.name.clone()
+    pub fn is_yanked(&self) -> bool {
+        self.yanked.is_some()
     }
-}
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct PartialVersion {
-    pub name: String,
+    /// The version moved out of the `package` of `user`, its signature keeps naming where it was signed
+    pub fn moved_from(mut self, user: &Username, package: &PackageName) -> Self {
+        if let Some(signature) = &mut self.signature {
+            signature.origin.get_or_insert_with(|| SignatureOrigin {
+                user: user.clone(),
+                package: package.clone(),
+            });
+        }
+
+        self
+    }
 }
 
-impl IVersion for PartialVersion {
-    fn name(&self) -> String {
-        self.name.clone()
+impl IVersion for Version {
+    fn semver(&self) -> Option<&SemVer> {
+        self.name.semver()
     }
 }
@@ -0,0 +1,108 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{SemVer, SemVerParseError};
+
+pub const LATEST_VERSION_NAME: &str = "latest";
+
+/// The name of a published version: either a semantic version,
+/// or `latest` for packages which are only ever published without a version.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VersionName {
+    Latest,
+    SemVer(SemVer),
+}
+
+impl VersionName {
+    pub fn semver(&self) -> Option<&SemVer> {
+        match self {
+            VersionName::Latest => None,
+            VersionName::SemVer(semver) => Some(semver),
+        }
+    }
+}
+
+impl From<SemVer> for VersionName {
+    fn from(semver: SemVer) -> Self {
+        VersionName::SemVer(semver)
+    }
+}
+
+impl Display for VersionName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionName::Latest => write!(f, "{}", LATEST_VERSION_NAME),
+            VersionName::SemVer(semver) => write!(f, "{}", semver),
+        }
+    }
+}
+
+impl FromStr for VersionName {
+    type Err = SemVerParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if name == LATEST_VERSION_NAME {
+            return Ok(VersionName::Latest);
+        }
+
+        name.parse().map(VersionName::SemVer)
+    }
+}
+
+impl Serialize for VersionName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        if name == LATEST_VERSION_NAME {
+            return Ok(VersionName::Latest);
+        }
+
+        SemVer::parse_legacy(&name)
+            .map(VersionName::SemVer)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_version_name() {
+        assert_eq!("latest".parse(), Ok(VersionName::Latest));
+        assert_eq!(
+            "1.0.0-beta.1".parse(),
+            Ok(VersionName::SemVer("1.0.0-beta.1".parse().unwrap()))
+        );
+        assert!("Latest".parse::<VersionName>().is_err());
+        assert!("1.0".parse::<VersionName>().is_err());
+    }
+
+    #[test]
+    fn serializes_as_a_string() {
+        let name: VersionName = "1.0.0-beta.1+build".parse().unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&name).unwrap(),
+            "\"1.0.0-beta.1+build\""
+        );
+        assert_eq!(
+            serde_json::from_str::<VersionName>("\"latest\"").unwrap(),
+            VersionName::Latest
+        );
+        assert_eq!(
+            serde_json::from_str::<VersionName>("\"01.0.0\"").unwrap(),
+            "1.0.0".parse().unwrap()
+        );
+        assert!("01.0.0".parse::<VersionName>().is_err());
+    }
+}
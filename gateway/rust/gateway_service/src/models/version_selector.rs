@@ -0,0 +1,140 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{SemVer, VersionRange};
+
+use super::{DistTag, LATEST_VERSION_NAME};
+
+lazy_static! {
+    static ref PARTIAL_VERSION_REGEX: Regex = Regex::new(r"^\d+(\.\d+)?$").unwrap();
+}
+
+/// The version part of a `package@version` segment.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionSelector {
+    /// No version or `latest`: the latest published version, an empty version is invalid
+    Latest,
+    /// A full semantic version, e.g. `1.2.3` or `1.2.3-beta.1`
+    Exact(SemVer),
+    /// A partial version, e.g. `1` or `1.2`, matched as the equivalent range
+    Partial(VersionRange),
+    /// An npm-style range, e.g. `^1.2.0` or `>=1.0.0 <2.0.0`
+    Range(VersionRange),
+    /// A dist-tag, e.g. `beta`
+    Tag(DistTag),
+}
+
+impl VersionSelector {
+    pub fn parse(selector: Option<&str>) -> Result<Self, VersionSelectorParseError> {
+        match selector {
+            None => Ok(VersionSelector::Latest),
+            Some(selector) => selector.parse(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VersionSelectorParseError {
+    selector: String,
+}
+
+impl Display for VersionSelectorParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid version `{}`: expected a version (1.2.3), partial version (1.2), range (^1.2.0) or dist-tag (beta)",
+            self.selector
+        )
+    }
+}
+
+impl std::error::Error for VersionSelectorParseError {}
+
+impl FromStr for VersionSelector {
+    type Err = VersionSelectorParseError;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        // An empty range would match any version, a missing version is `None` instead
+        if selector.is_empty() {
+            return Err(VersionSelectorParseError {
+                selector: selector.to_string(),
+            });
+        }
+
+        if selector == LATEST_VERSION_NAME {
+            return Ok(VersionSelector::Latest);
+        }
+
+        if let Ok(version) = selector.parse::<SemVer>() {
+            return Ok(VersionSelector::Exact(version));
+        }
+
+        if let Ok(range) = selector.parse::<VersionRange>() {
+            return Ok(if PARTIAL_VERSION_REGEX.is_match(selector) {
+                VersionSelector::Partial(range)
+            } else {
+                VersionSelector::Range(range)
+            });
+        }
+
+        if let Ok(tag) = selector.parse::<DistTag>() {
+            return Ok(VersionSelector::Tag(tag));
+        }
+
+        Err(VersionSelectorParseError {
+            selector: selector.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_version_selector() {
+        assert_eq!(VersionSelector::parse(None), Ok(VersionSelector::Latest));
+        assert_eq!("latest".parse(), Ok(VersionSelector::Latest));
+        assert_eq!(
+            "1.2.3-beta.1".parse(),
+            Ok(VersionSelector::Exact("1.2.3-beta.1".parse().unwrap()))
+        );
+        assert_eq!(
+            "1".parse(),
+            Ok(VersionSelector::Partial("1".parse().unwrap()))
+        );
+        assert_eq!(
+            "1.2".parse(),
+            Ok(VersionSelector::Partial("1.2".parse().unwrap()))
+        );
+        assert_eq!(
+            "^1.2.0".parse(),
+            Ok(VersionSelector::Range("^1.2.0".parse().unwrap()))
+        );
+        assert_eq!(
+            "1.x".parse(),
+            Ok(VersionSelector::Range("1.x".parse().unwrap()))
+        );
+        assert_eq!(
+            "beta".parse(),
+            Ok(VersionSelector::Tag("beta".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_version_selectors() {
+        for selector in ["", "^1.2-beta", "1.2.3.4", "beta.1", "-beta", "1.0.0a"] {
+            assert_eq!(
+                selector.parse::<VersionSelector>(),
+                Err(VersionSelectorParseError {
+                    selector: selector.to_string()
+                }),
+            );
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{Package, PackageName, Username};
+
+/// Which publishes a webhook subscription receives.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookScope {
+    /// Every publish in the registry
+    Registry,
+    /// Publishes of a single package
+    Package {
+        user: Username,
+        package: PackageName,
+    },
+}
+
+impl WebhookScope {
+    /// The key the subscriptions are stored under, which can never be a package id
+    pub fn key(&self) -> String {
+        match self {
+            WebhookScope::Registry => "webhooks".to_string(),
+            WebhookScope::Package { user, package } => {
+                format!("webhooks:{}", Package::id_of(user, package))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub url: String,
+    /// Used to sign the events, only known to the subscriber
+    pub secret: String,
+    pub created_on: u128,
+}
+
+impl WebhookSubscription {
+    pub fn new(url: String, secret: String) -> Self {
+        let created_on = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        Self {
+            id: subscription_id(&url),
+            url,
+            secret,
+            created_on,
+        }
+    }
+}
+
+/// The id is derived from the URL, so subscribing the same URL twice replaces the subscription
+fn subscription_id(url: &str) -> String {
+    hex::encode(&Sha256::digest(url.as_bytes())[..8])
+}
+
+/// All subscriptions of a scope, stored as a single entity.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WebhookSubscriptions {
+    pub id: String,
+    pub subscriptions: Vec<WebhookSubscription>,
+    #[serde(default)]
+    pub revision: u64,
+}
+
+impl WebhookSubscriptions {
+    pub fn new(scope: &WebhookScope) -> Self {
+        Self {
+            id: scope.key(),
+            subscriptions: vec![],
+            revision: 0,
+        }
+    }
+}
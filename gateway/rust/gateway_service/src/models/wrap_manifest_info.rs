@@ -0,0 +1,100 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use wrap_manifest_schemas::deserialize::{deserialize_wrap_manifest, DeserializeManifestOptions};
+
+lazy_static! {
+    static ref WRAP_NAME_REGEX: Regex = Regex::new(r"^[a-zA-Z0-9\-_]+$").unwrap();
+}
+
+const WRAP_TYPES: [&str; 3] = ["wasm", "interface", "plugin"];
+
+/// What a version's `wrap.info` declares about the wrap, recorded when it is published.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct WrapManifestInfo {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub wrap_type: String,
+}
+
+impl WrapManifestInfo {
+    /// Decodes a msgpack encoded `wrap.info`
+    pub fn decode(manifest: &[u8]) -> Result<Self, String> {
+        // The manifest schemas panic on a missing version or a failed validation,
+        // so the version is checked here and the validation done below
+        let json: serde_json::Value =
+            polywrap_msgpack_serde::from_slice(manifest).map_err(|e| e.to_string())?;
+        if !json["version"].is_string() {
+            return Err("Manifest has no version".to_string());
+        }
+
+        let manifest = deserialize_wrap_manifest(
+            manifest,
+            Some(DeserializeManifestOptions {
+                no_validate: true,
+                ext_schema: None,
+            }),
+        )
+        .map_err(|e| e.to_string())?;
+
+        if !WRAP_NAME_REGEX.is_match(&manifest.name) {
+            return Err(format!("Invalid wrap name `{}`", manifest.name));
+        }
+
+        if !WRAP_TYPES.contains(&manifest.type_.as_str()) {
+            return Err(format!(
+                "Invalid wrap type `{}`, expected one of: {}",
+                manifest.type_,
+                WRAP_TYPES.join(", ")
+            ));
+        }
+
+        Ok(Self {
+            name: manifest.name,
+            wrap_type: manifest.type_,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::WrapManifestInfo;
+
+    fn encode(manifest: serde_json::Value) -> Vec<u8> {
+        polywrap_msgpack_serde::to_vec(&manifest).unwrap()
+    }
+
+    #[test]
+    fn decodes_valid_manifest() {
+        let manifest = encode(json!({
+            "version": "0.1",
+            "name": "ethereum",
+            "type": "wasm",
+            "abi": { "version": "0.1" }
+        }));
+
+        assert_eq!(
+            WrapManifestInfo::decode(&manifest),
+            Ok(WrapManifestInfo {
+                name: "ethereum".into(),
+                wrap_type: "wasm".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_manifests() {
+        for manifest in [
+            b"not msgpack".to_vec(),
+            encode(json!({ "name": "ethereum", "type": "wasm", "abi": {} })),
+            encode(json!({ "version": "9.9", "name": "ethereum", "type": "wasm", "abi": {} })),
+            encode(json!({ "version": "0.1", "type": "wasm", "abi": {} })),
+            encode(json!({ "version": "0.1", "name": "eth ereum", "type": "wasm", "abi": {} })),
+            encode(json!({ "version": "0.1", "name": "ethereum", "type": "app", "abi": {} })),
+        ] {
+            assert!(WrapManifestInfo::decode(&manifest).is_err());
+        }
+    }
+}
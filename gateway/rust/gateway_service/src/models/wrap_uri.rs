@@ -24,6 +24,17 @@ impl<'de> Deserialize<'de> for WrapUri {
     }
 }
 
+impl WrapUri {
+    /// The part after `wrap://` that decides how the URI is resolved, e.g. `ipfs` or `ens`
+    pub fn authority(&self) -> &str {
+        self.0.authority()
+    }
+
+    pub fn path(&self) -> &str {
+        self.0.path()
+    }
+}
+
 impl Display for WrapUri {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
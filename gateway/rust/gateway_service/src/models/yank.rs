@@ -0,0 +1,21 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+/// Why and when a version was yanked.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Yank {
+    pub reason: String,
+    pub yanked_on: u128,
+}
+
+impl Yank {
+    pub fn new(reason: String) -> Self {
+        let yanked_on = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis();
+
+        Self { reason, yanked_on }
+    }
+}
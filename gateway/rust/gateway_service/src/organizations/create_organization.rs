@@ -0,0 +1,34 @@
+use crate::{
+    models::{Organization, ReservedNames, Username},
+    Repository, RepositoryError,
+};
+
+use super::error::OrgError;
+
+/// Turns a namespace into an organization, with `owner` as its first member.
+/// A reserved name can only become an organization of an allowlisted account.
+pub async fn create_organization(
+    name: &Username,
+    owner: &Username,
+    reserved_names: &ReservedNames,
+    org_repo: impl Repository<Organization>,
+) -> Result<Organization, OrgError> {
+    reserved_names
+        .check_user(name)
+        .map_err(OrgError::ReservedName)?;
+
+    match org_repo.read(&Organization::key(name)).await {
+        Ok(_) => return Err(OrgError::OrganizationExists),
+        Err(RepositoryError::NotFound) => {}
+        Err(e) => return Err(OrgError::RepositoryError(e.to_string())),
+    }
+
+    let organization = Organization::new(name.clone(), owner.clone());
+
+    org_repo.update(&organization).await.map_err(|e| match e {
+        RepositoryError::Conflict => OrgError::OrganizationExists,
+        e => OrgError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(organization)
+}
@@ -0,0 +1,31 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum OrgError {
+    OrganizationNotFound,
+    OrganizationExists,
+    MemberNotFound,
+    LastOwner,
+    ReservedName(String),
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for OrgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrgError::OrganizationNotFound => write!(f, "Organization not found"),
+            OrgError::OrganizationExists => write!(f, "Organization already exists"),
+            OrgError::MemberNotFound => write!(f, "Member not found"),
+            OrgError::LastOwner => write!(f, "An organization must keep at least one owner"),
+            OrgError::ReservedName(name) => write!(f, "`{}` is a reserved name", name),
+            OrgError::ConfusableName(name) => write!(
+                f,
+                "Organization is named `{}`, names can not differ only in casing",
+                name
+            ),
+            OrgError::Conflict => write!(f, "Organization was changed concurrently"),
+            OrgError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::{
+    models::{Organization, Username},
+    Repository, RepositoryError,
+};
+
+use super::error::OrgError;
+
+pub async fn get_organization(
+    name: &Username,
+    org_repo: &impl Repository<Organization>,
+) -> Result<Organization, OrgError> {
+    org_repo
+        .read(&Organization::key(name))
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => OrgError::OrganizationNotFound,
+            e => OrgError::RepositoryError(e.to_string()),
+        })
+}
@@ -0,0 +1,14 @@
+pub mod error;
+pub use error::*;
+
+mod create_organization;
+pub use create_organization::create_organization;
+
+mod get_organization;
+pub use get_organization::get_organization;
+
+mod set_member_role;
+pub use set_member_role::set_member_role;
+
+mod remove_member;
+pub use remove_member::remove_member;
@@ -0,0 +1,132 @@
+use crate::{
+    models::{OrgRole, Organization, Username},
+    Repository, RepositoryError,
+};
+
+use super::{error::OrgError, get_organization};
+
+pub async fn remove_member(
+    name: &Username,
+    username: &Username,
+    org_repo: impl Repository<Organization>,
+) -> Result<(), OrgError> {
+    let mut organization = get_organization(name, &org_repo).await?;
+    organization
+        .check_name(name)
+        .map_err(OrgError::ConfusableName)?;
+
+    let members = organization.members.len();
+    organization
+        .members
+        .retain(|member| &member.username != username);
+
+    if organization.members.len() == members {
+        return Err(OrgError::MemberNotFound);
+    }
+
+    if !organization
+        .members
+        .iter()
+        .any(|m| m.role == OrgRole::Owner)
+    {
+        return Err(OrgError::LastOwner);
+    }
+
+    org_repo.update(&organization).await.map_err(|e| match e {
+        RepositoryError::Conflict => OrgError::Conflict,
+        e => OrgError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mocks::MockOrgRepository,
+        models::{OrgMember, OrgRole, Organization},
+        organizations::{remove_member, OrgError},
+    };
+
+    fn organization() -> Organization {
+        let mut organization = Organization::new("org1".parse().unwrap(), "user1".parse().unwrap());
+        organization
+            .members
+            .push(OrgMember::new("user2".parse().unwrap(), OrgRole::Publisher));
+        organization
+    }
+
+    #[tokio::test]
+    async fn removes_member() {
+        let mut org_repo = MockOrgRepository::new();
+
+        org_repo.expect_read().return_once(|_| Ok(organization()));
+        org_repo
+            .expect_update()
+            .withf(|org| {
+                org.members.len() == 1 && org.members[0].username == "user1".parse().unwrap()
+            })
+            .times(1)
+            .return_once(|_| Ok(()));
+
+        let result = remove_member(
+            &"org1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            org_repo,
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn cannot_remove_last_owner() {
+        let mut org_repo = MockOrgRepository::new();
+
+        org_repo.expect_read().return_once(|_| Ok(organization()));
+        org_repo.expect_update().never();
+
+        let result = remove_member(
+            &"org1".parse().unwrap(),
+            &"user1".parse().unwrap(),
+            org_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(OrgError::LastOwner));
+    }
+
+    #[tokio::test]
+    async fn member_must_exist() {
+        let mut org_repo = MockOrgRepository::new();
+
+        org_repo.expect_read().return_once(|_| Ok(organization()));
+        org_repo.expect_update().never();
+
+        let result = remove_member(
+            &"org1".parse().unwrap(),
+            &"user3".parse().unwrap(),
+            org_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(OrgError::MemberNotFound));
+    }
+
+    #[tokio::test]
+    async fn forbids_differently_cased_name() {
+        let mut org_repo = MockOrgRepository::new();
+
+        org_repo.expect_read().return_once(|_| Ok(organization()));
+        org_repo.expect_update().never();
+
+        let result = remove_member(
+            &"ORG1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            org_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(OrgError::ConfusableName("org1".into())));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    models::{OrgMember, OrgRole, Organization, Username},
+    Repository, RepositoryError,
+};
+
+use super::{error::OrgError, get_organization};
+
+/// Adds a member, or changes the role of an existing one
+pub async fn set_member_role(
+    name: &Username,
+    username: &Username,
+    role: OrgRole,
+    org_repo: impl Repository<Organization>,
+) -> Result<Organization, OrgError> {
+    let mut organization = get_organization(name, &org_repo).await?;
+    organization
+        .check_name(name)
+        .map_err(OrgError::ConfusableName)?;
+
+    match organization
+        .members
+        .iter_mut()
+        .find(|member| &member.username == username)
+    {
+        Some(member) if member.role == role => return Ok(organization),
+        Some(member) => member.role = role,
+        None => organization
+            .members
+            .push(OrgMember::new(username.clone(), role)),
+    }
+
+    if !organization
+        .members
+        .iter()
+        .any(|m| m.role == OrgRole::Owner)
+    {
+        return Err(OrgError::LastOwner);
+    }
+
+    org_repo.update(&organization).await.map_err(|e| match e {
+        RepositoryError::Conflict => OrgError::Conflict,
+        e => OrgError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(organization)
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use crate::{
+        mocks::MockOrgRepository,
+        models::{OrgRole, Organization},
+        organizations::{set_member_role, OrgError},
+    };
+
+    fn organization() -> Organization {
+        Organization::new("org1".parse().unwrap(), "user1".parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn adds_member() {
+        let mut org_repo = MockOrgRepository::new();
+
+        org_repo
+            .expect_read()
+            .with(eq("org:org1".to_string()))
+            .return_once(|_| Ok(organization()));
+        org_repo
+            .expect_update()
+            .withf(|org| {
+                org.members.len() == 2
+                    && org.members[1].username == "user2".parse().unwrap()
+                    && org.members[1].role == OrgRole::Publisher
+            })
+            .times(1)
+            .return_once(|_| Ok(()));
+
+        let result = set_member_role(
+            &"org1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            OrgRole::Publisher,
+            org_repo,
+        )
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn keeps_an_owner() {
+        let mut org_repo = MockOrgRepository::new();
+
+        org_repo.expect_read().return_once(|_| Ok(organization()));
+        org_repo.expect_update().never();
+
+        let result = set_member_role(
+            &"org1".parse().unwrap(),
+            &"user1".parse().unwrap(),
+            OrgRole::Maintainer,
+            org_repo,
+        )
+        .await;
+
+        assert_eq!(result.map(|_| ()), Err(OrgError::LastOwner));
+    }
+
+    #[tokio::test]
+    async fn forbids_differently_cased_name() {
+        let mut org_repo = MockOrgRepository::new();
+
+        org_repo.expect_read().return_once(|_| Ok(organization()));
+        org_repo.expect_update().never();
+
+        let result = set_member_role(
+            &"ORG1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            OrgRole::Owner,
+            org_repo,
+        )
+        .await;
+
+        assert_eq!(
+            result.map(|_| ()),
+            Err(OrgError::ConfusableName("org1".into()))
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::models::{Package, Version, VersionName, VersionSignature, WrapManifestInfo, WrapUri};
+use crate::semver;
+
+use super::error::PublishError;
+
+/// Adds a new version to the package, enforcing the rules shared by every kind of publish.
+/// Returns the added version.
+pub fn add_version(
+    package: &mut Package,
+    new_version: VersionName,
+    uri: WrapUri,
+    manifest: Option<WrapManifestInfo>,
+    signature: Option<VersionSignature>,
+) -> Result<Version, PublishError> {
+    let existing_version = package
+        .versions
+        .iter()
+        .find(|version| version.name == new_version);
+    if let Some(existing_version) = existing_version {
+        match existing_version.uri == uri {
+            true => return Err(PublishError::DuplicateVersionNameAndUri),
+            false => return Err(PublishError::DuplicateVersionName),
+        }
+    }
+
+    let unpublished_uri = new_version
+        .semver()
+        .and_then(|version| package.unpublished_versions.get(version));
+    if matches!(unpublished_uri, Some(unpublished_uri) if *unpublished_uri != uri) {
+        return Err(PublishError::UnpublishedVersionName);
+    }
+
+    let mut version = Version::new(new_version, uri);
+    version.manifest = manifest;
+    version.signature = signature;
+    package.versions.push(version.clone());
+
+    semver::sort_versions(&mut package.versions);
+
+    Ok(version)
+}
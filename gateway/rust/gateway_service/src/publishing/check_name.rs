@@ -0,0 +1,84 @@
+use crate::models::{Package, PackageName, ReservedNames, Username};
+
+use super::error::PublishError;
+
+/// Checks the name a version is published under. An existing package must be named with the
+/// casing it was created with, and a new package must not claim a reserved name. Whether the
+/// package is new can't be told from it: packages read from the legacy table have revision 0
+pub fn check_name(
+    package: &Package,
+    new_package: bool,
+    user: &Username,
+    package_name: &PackageName,
+    reserved_names: &ReservedNames,
+) -> Result<(), PublishError> {
+    package
+        .check_owner(user, package_name)
+        .map_err(PublishError::ConfusableName)?;
+
+    if new_package {
+        reserved_names
+            .check(user, package_name)
+            .map_err(PublishError::ReservedName)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{Package, ReservedNames},
+        publishing::PublishError,
+    };
+
+    use super::check_name;
+
+    fn check(
+        package: &Package,
+        new_package: bool,
+        user: &str,
+        package_name: &str,
+    ) -> Result<(), PublishError> {
+        check_name(
+            package,
+            new_package,
+            &user.parse().unwrap(),
+            &package_name.parse().unwrap(),
+            &ReservedNames::new("polywrap,admin", "polywrap"),
+        )
+    }
+
+    #[test]
+    fn rejects_names_that_only_differ_in_casing() {
+        let package = Package::new("Package1".parse().unwrap(), "User1".parse().unwrap());
+
+        assert_eq!(check(&package, false, "User1", "Package1"), Ok(()));
+        assert_eq!(
+            check(&package, false, "user1", "package1"),
+            Err(PublishError::ConfusableName("User1/Package1".into()))
+        );
+        assert_eq!(
+            check(&package, false, "User1", "PACKAGE1"),
+            Err(PublishError::ConfusableName("User1/Package1".into()))
+        );
+    }
+
+    #[test]
+    fn only_new_packages_are_checked_for_reserved_names() {
+        let package = Package::new("Admin".parse().unwrap(), "user1".parse().unwrap());
+        assert_eq!(
+            check(&package, true, "user1", "Admin"),
+            Err(PublishError::ReservedName("Admin".into()))
+        );
+
+        let package = Package::new("admin".parse().unwrap(), "polywrap".parse().unwrap());
+        assert_eq!(check(&package, true, "polywrap", "admin"), Ok(()));
+
+        // Packages that were claimed before the name was reserved can still be published to, even
+        // at revision 0, e.g. when read from the legacy table
+        let package = Package::new("admin".parse().unwrap(), "user1".parse().unwrap());
+        assert_eq!(package.revision, 0);
+        assert_eq!(check(&package, false, "user1", "admin"), Ok(()));
+    }
+}
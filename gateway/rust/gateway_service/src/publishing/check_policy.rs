@@ -0,0 +1,163 @@
+use crate::models::{Package, VersionName, WrapUri};
+
+use super::error::PublishError;
+
+/// Checks a new version against the publish policy of the package.
+/// Versions that are already published are left to the duplicate checks of `add_version`.
+pub fn check_policy(
+    package: &Package,
+    new_version: &VersionName,
+    uri: &WrapUri,
+    allow_major: bool,
+) -> Result<(), PublishError> {
+    let policy = &package.policy;
+
+    if !policy.allows_uri_authority(uri.authority()) {
+        return Err(PublishError::UriAuthorityNotAllowed(
+            uri.authority().to_string(),
+        ));
+    }
+
+    let Some(version) = new_version.semver() else {
+        return Ok(());
+    };
+
+    if package.versions.iter().any(|v| v.name == *new_version) {
+        return Ok(());
+    }
+
+    let published = || package.versions.iter().filter_map(|v| v.name.semver());
+
+    if policy.monotonic_within_major {
+        let greatest_in_major = published().filter(|v| v.major == version.major).max();
+
+        if matches!(greatest_in_major, Some(greatest) if version < greatest) {
+            return Err(PublishError::VersionNotMonotonic);
+        }
+    }
+
+    if policy.require_major_flag && !allow_major {
+        let greatest_major = published().map(|v| v.major).max();
+
+        if matches!(greatest_major, Some(major) if version.major > major) {
+            return Err(PublishError::MajorVersionNotAllowed);
+        }
+    }
+
+    if version.is_prerelease() && !policy.allows_prerelease(&version.prerelease_tag()) {
+        return Err(PublishError::PrereleaseNotAllowed(version.prerelease_tag()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{Package, PublishPolicy, Version},
+        publishing::{check_policy, PublishError},
+    };
+
+    fn package(policy: PublishPolicy, versions: &[&str]) -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.policy = policy;
+        package.versions = versions
+            .iter()
+            .map(|name| Version::new(name.parse().unwrap(), "ipfs/QmHash".parse().unwrap()))
+            .collect();
+        package
+    }
+
+    fn check(
+        package: &Package,
+        version: &str,
+        uri: &str,
+        allow_major: bool,
+    ) -> Result<(), PublishError> {
+        check_policy(
+            package,
+            &version.parse().unwrap(),
+            &uri.parse().unwrap(),
+            allow_major,
+        )
+    }
+
+    #[test]
+    fn versions_must_increase_within_major() {
+        let package = package(
+            PublishPolicy {
+                monotonic_within_major: true,
+                ..Default::default()
+            },
+            &["1.0.0", "1.2.0", "2.0.0"],
+        );
+
+        assert_eq!(check(&package, "1.3.0", "ipfs/QmHash", false), Ok(()));
+        assert_eq!(
+            check(&package, "1.1.5", "ipfs/QmHash", false),
+            Err(PublishError::VersionNotMonotonic)
+        );
+        assert_eq!(
+            check(&package, "1.2.0-beta.1", "ipfs/QmHash", false),
+            Err(PublishError::VersionNotMonotonic)
+        );
+        // Already published versions are handled as duplicates
+        assert_eq!(check(&package, "1.0.0", "ipfs/QmHash", false), Ok(()));
+    }
+
+    #[test]
+    fn new_major_requires_flag() {
+        let package = package(
+            PublishPolicy {
+                require_major_flag: true,
+                ..Default::default()
+            },
+            &["1.0.0"],
+        );
+
+        assert_eq!(check(&package, "1.1.0", "ipfs/QmHash", false), Ok(()));
+        assert_eq!(
+            check(&package, "2.0.0", "ipfs/QmHash", false),
+            Err(PublishError::MajorVersionNotAllowed)
+        );
+        assert_eq!(check(&package, "2.0.0", "ipfs/QmHash", true), Ok(()));
+    }
+
+    #[test]
+    fn prerelease_must_match_pattern() {
+        let package = package(
+            PublishPolicy {
+                prerelease_pattern: Some(r"(alpha|beta|rc)\.\d+".into()),
+                ..Default::default()
+            },
+            &[],
+        );
+
+        assert_eq!(check(&package, "1.0.0-rc.1", "ipfs/QmHash", false), Ok(()));
+        assert_eq!(
+            check(&package, "1.0.0-nightly", "ipfs/QmHash", false),
+            Err(PublishError::PrereleaseNotAllowed("nightly".into()))
+        );
+    }
+
+    #[test]
+    fn uri_authority_must_be_allowed() {
+        let package = package(
+            PublishPolicy {
+                allowed_uri_authorities: vec!["ipfs".into()],
+                ..Default::default()
+            },
+            &[],
+        );
+
+        assert_eq!(
+            check(&package, "1.0.0", "wrap://ipfs/QmHash", false),
+            Ok(())
+        );
+        assert_eq!(check(&package, "latest", "ipfs/QmHash", false), Ok(()));
+        assert_eq!(
+            check(&package, "1.0.0", "wrap://http/example.com", false),
+            Err(PublishError::UriAuthorityNotAllowed("http".into()))
+        );
+    }
+}
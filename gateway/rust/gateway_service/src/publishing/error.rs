@@ -1,21 +1,203 @@
 use std::fmt::Display;
 
-#[derive(Debug, thiserror::Error, PartialEq)]
+use crate::RepositoryError;
+
+use super::MAX_BATCH_BYTES;
+
+#[derive(Debug, thiserror::Error, PartialEq, Clone, serde::Serialize)]
 pub enum PublishError {
-    InvalidVersionFormat,
     DuplicateVersionName,
     DuplicateVersionNameAndUri,
+    /// The version is already published, so the metadata given with it wasn't stored
+    MetadataNotApplied,
+    UnpublishedVersionName,
     LatestVersionNotAllowed,
+    VersionNotMonotonic,
+    MajorVersionNotAllowed,
+    PrereleaseNotAllowed(String),
+    UriAuthorityNotAllowed(String),
+    ManifestNotFound(String),
+    InvalidManifest(String),
+    ManifestUnavailable(String),
+    PackageMoved(String),
+    ConfusableName(String),
+    ReservedName(String),
+    /// The estimated size of the batch in bytes
+    BatchTooLarge(usize),
+    Conflict,
     RepositoryError(String),
 }
 impl Display for PublishError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            PublishError::InvalidVersionFormat => write!(f, "Invalid version format"),
             PublishError::DuplicateVersionName => write!(f, "Duplicate version name"),
             PublishError::DuplicateVersionNameAndUri => write!(f, "Duplicate version name and URI"),
+            PublishError::MetadataNotApplied => write!(
+                f,
+                "Version is already published with different metadata, update the metadata of the package instead"
+            ),
+            PublishError::UnpublishedVersionName => {
+                write!(f, "Version name was unpublished with a different URI")
+            }
             PublishError::LatestVersionNotAllowed => write!(f, "Latest version not allowed"),
+            PublishError::VersionNotMonotonic => write!(
+                f,
+                "Version must be greater than every published version with the same major"
+            ),
+            PublishError::MajorVersionNotAllowed => {
+                write!(
+                    f,
+                    "Publishing a new major version requires the allow_major flag"
+                )
+            }
+            PublishError::PrereleaseNotAllowed(prerelease) => write!(
+                f,
+                "Prerelease `{}` does not match the prerelease pattern of the package",
+                prerelease
+            ),
+            PublishError::UriAuthorityNotAllowed(authority) => write!(
+                f,
+                "URI authority `{}` is not allowed by the package",
+                authority
+            ),
+            PublishError::ManifestNotFound(uri) => write!(f, "No wrap.info found at `{}`", uri),
+            PublishError::InvalidManifest(e) => write!(f, "Invalid wrap.info: {}", e),
+            PublishError::ManifestUnavailable(e) => write!(f, "Failed to fetch wrap.info: {}", e),
+            PublishError::PackageMoved(id) => write!(f, "Package was moved to `{}`", id),
+            PublishError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            PublishError::ReservedName(name) => write!(f, "`{}` is a reserved name", name),
+            PublishError::BatchTooLarge(size) => write!(
+                f,
+                "Batch of {} bytes is larger than the {} bytes that can be written at once",
+                size, MAX_BATCH_BYTES
+            ),
+            PublishError::Conflict => write!(f, "Package was changed by a concurrent publish"),
             PublishError::RepositoryError(e) => write!(f, "Repository error: {}", e),
         }
     }
 }
+
+impl From<RepositoryError> for PublishError {
+    fn from(error: RepositoryError) -> Self {
+        match error {
+            RepositoryError::Conflict => PublishError::Conflict,
+            error => PublishError::RepositoryError(error.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum UnpublishError {
+    PackageNotFound,
+    VersionNotFound,
+    UnpublishWindowExpired,
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for UnpublishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnpublishError::PackageNotFound => write!(f, "Package not found"),
+            UnpublishError::VersionNotFound => write!(f, "Version not found"),
+            UnpublishError::UnpublishWindowExpired => write!(f, "Unpublish window expired"),
+            UnpublishError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            UnpublishError::Conflict => write!(f, "Package was changed by a concurrent update"),
+            UnpublishError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum UpdateMetadataError {
+    PackageNotFound,
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for UpdateMetadataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateMetadataError::PackageNotFound => write!(f, "Package not found"),
+            UpdateMetadataError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            UpdateMetadataError::Conflict => {
+                write!(f, "Package was changed by a concurrent update")
+            }
+            UpdateMetadataError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum UpdatePolicyError {
+    PackageNotFound,
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for UpdatePolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdatePolicyError::PackageNotFound => write!(f, "Package not found"),
+            UpdatePolicyError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            UpdatePolicyError::Conflict => write!(f, "Package was changed by a concurrent update"),
+            UpdatePolicyError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum PromoteError {
+    PackageNotFound,
+    NotLatestOnly,
+    PrereleaseVersion,
+    UnpublishedVersionName,
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for PromoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromoteError::PackageNotFound => write!(f, "Package not found"),
+            PromoteError::NotLatestOnly => {
+                write!(
+                    f,
+                    "Only packages published solely as `latest` can be promoted"
+                )
+            }
+            PromoteError::PrereleaseVersion => {
+                write!(
+                    f,
+                    "The latest version can only be promoted to a release version"
+                )
+            }
+            PromoteError::UnpublishedVersionName => {
+                write!(f, "Version name was unpublished with a different URI")
+            }
+            PromoteError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            PromoteError::Conflict => write!(f, "Package was changed by a concurrent update"),
+            PromoteError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
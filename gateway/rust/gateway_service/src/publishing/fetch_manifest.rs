@@ -0,0 +1,27 @@
+use crate::{
+    fetching::{FetchError, WrapFetcher},
+    models::{WrapManifestInfo, WrapUri},
+};
+
+use super::error::PublishError;
+
+/// Fetches and decodes the `wrap.info` the URI points to.
+/// Returns `None` for URIs the fetcher can't fetch, e.g. `ens` URIs which need resolving first.
+pub async fn fetch_manifest(
+    uri: &WrapUri,
+    wrap_fetcher: &impl WrapFetcher,
+) -> Result<Option<WrapManifestInfo>, PublishError> {
+    let manifest = match wrap_fetcher.fetch_manifest(uri).await {
+        Ok(manifest) => manifest,
+        Err(FetchError::UnsupportedUri(_)) => return Ok(None),
+        Err(FetchError::NotFound) => return Err(PublishError::ManifestNotFound(uri.to_string())),
+        Err(e @ (FetchError::Forbidden(_) | FetchError::TooLarge(_))) => {
+            return Err(PublishError::InvalidManifest(e.to_string()))
+        }
+        Err(FetchError::Unknown(e)) => return Err(PublishError::ManifestUnavailable(e)),
+    };
+
+    WrapManifestInfo::decode(&manifest)
+        .map(Some)
+        .map_err(PublishError::InvalidManifest)
+}
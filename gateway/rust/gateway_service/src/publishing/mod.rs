@@ -1,8 +1,41 @@
 pub mod error;
 pub use error::*;
 
+/// Concurrent writes to the same package conflict, so publishing retries on the latest revision
+pub const MAX_PUBLISH_ATTEMPTS: usize = 3;
+
+/// DynamoDB transactions are limited to 4 MB, a batch that is larger is rejected before writing
+pub const MAX_BATCH_BYTES: usize = 4 * 1024 * 1024;
+
+mod add_version;
+pub use add_version::add_version;
+
 mod publish_latest_version;
 pub use publish_latest_version::publish_latest_version;
 
+mod fetch_manifest;
+pub use fetch_manifest::fetch_manifest;
+
+mod check_policy;
+pub use check_policy::check_policy;
+
+mod check_name;
+pub use check_name::check_name;
+
 mod publish_package;
-pub use publish_package::publish_package;
+pub use publish_package::*;
+
+mod promote_latest_version;
+pub use promote_latest_version::promote_latest_version;
+
+mod unpublish_version;
+pub use unpublish_version::unpublish_version;
+
+mod update_package_metadata;
+pub use update_package_metadata::update_package_metadata;
+
+mod update_publish_policy;
+pub use update_publish_policy::update_publish_policy;
+
+mod publish_batch;
+pub use publish_batch::*;
@@ -0,0 +1,172 @@
+use crate::{
+    models::{DistTag, Package, PackageName, Username, Version, VersionName},
+    read_package_by_name, Repository, RepositoryError, SemVer,
+};
+
+use super::error::PromoteError;
+
+/// Converts the `latest` entry of a package that was only ever published as `latest` into a semver version,
+/// so the package can be published with semver versions from then on.
+/// The entry keeps its URI and `created_on`, so resolving the package without a version still returns the same URI.
+pub async fn promote_latest_version(
+    user: &Username,
+    package_name: &PackageName,
+    version: SemVer,
+    tag: Option<DistTag>,
+    package_repo: impl Repository<Package>,
+) -> Result<Version, PromoteError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => PromoteError::PackageNotFound,
+            e => PromoteError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(PromoteError::ConfusableName)?;
+
+    if package.versions.len() != 1 || package.versions[0].name != VersionName::Latest {
+        return Err(PromoteError::NotLatestOnly);
+    }
+
+    // A prerelease is skipped when resolving without a version, which would break existing URIs
+    if version.is_prerelease() {
+        return Err(PromoteError::PrereleaseVersion);
+    }
+
+    let latest_version = &mut package.versions[0];
+
+    if matches!(package.unpublished_versions.get(&version), Some(uri) if *uri != latest_version.uri)
+    {
+        return Err(PromoteError::UnpublishedVersionName);
+    }
+
+    latest_version.name = VersionName::SemVer(version.clone());
+    let promoted_version = latest_version.clone();
+
+    if let Some(tag) = tag {
+        package.dist_tags.insert(tag, version);
+    }
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => PromoteError::Conflict,
+        e => PromoteError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(promoted_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        publishing::{promote_latest_version, PromoteError},
+        InMemoryPackageRepository, Package, Repository, Version, VersionName,
+    };
+
+    fn latest_only_package() -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version {
+            created_on: 5,
+            ..Version::new(VersionName::Latest, "test/uri1".parse().unwrap())
+        }];
+        package
+    }
+
+    #[tokio::test]
+    async fn promotes_latest_version_to_semver_and_dist_tag() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&latest_only_package()).await.unwrap();
+
+        let result = promote_latest_version(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            Some("stable".parse().unwrap()),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result.map(|v| (v.name, v.created_on)),
+            Ok(("1.0.0".parse().unwrap(), 5))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.versions.len(), 1);
+        assert_eq!(package.versions[0].name, "1.0.0".parse().unwrap());
+        assert_eq!(package.versions[0].uri, "test/uri1".parse().unwrap());
+        assert_eq!(package.versions[0].created_on, 5);
+        assert_eq!(
+            package.dist_tags.get(&"stable".parse().unwrap()),
+            Some(&"1.0.0".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn only_promotes_latest_only_packages() {
+        let mut package = latest_only_package();
+        package.versions[0].name = "1.0.0".parse().unwrap();
+
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package).await.unwrap();
+
+        let result = promote_latest_version(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "2.0.0".parse().unwrap(),
+            None,
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(PromoteError::NotLatestOnly));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+
+    #[tokio::test]
+    async fn forbids_promoting_to_prerelease() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&latest_only_package()).await.unwrap();
+
+        let result = promote_latest_version(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0-beta.1".parse().unwrap(),
+            None,
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(PromoteError::PrereleaseVersion));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.versions[0].name, VersionName::Latest);
+        assert_eq!(package.revision, 1);
+    }
+
+    #[tokio::test]
+    async fn forbids_promoting_as_differently_cased_user() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&latest_only_package()).await.unwrap();
+
+        let result = promote_latest_version(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            None,
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(PromoteError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use futures::future::join_all;
+
+use crate::{
+    fetching::WrapFetcher,
+    models::{
+        Package, PackageName, ReservedNames, Username, Version, VersionName, VersionSignature,
+        WrapManifestInfo, WrapUri,
+    },
+    read_package_by_name, Repository, RepositoryError, SemVer,
+};
+
+use super::{
+    add_version, check_name, check_policy, error::PublishError, fetch_manifest,
+    publish_package::check_not_moved, MAX_BATCH_BYTES, MAX_PUBLISH_ATTEMPTS,
+};
+
+/// A single version to publish as part of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    pub package_name: PackageName,
+    pub version: SemVer,
+    pub uri: WrapUri,
+    /// Allows a new major version when the package policy requires the flag
+    pub allow_major: bool,
+    /// Recorded on the version, verified against the publisher keys before publishing
+    pub signature: Option<VersionSignature>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BatchPublishResult {
+    /// Whether the batch was applied, which only happens if no entry failed
+    pub published: bool,
+    /// The published version of each entry, in the order they were given
+    pub results: Vec<Result<Version, PublishError>>,
+}
+
+/// Publishes all entries with the same rules as `publish_package`, all-or-nothing.
+pub async fn publish_batch(
+    user: &Username,
+    entries: &[BatchEntry],
+    reserved_names: &ReservedNames,
+    package_repo: impl Repository<Package>,
+    wrap_fetcher: Option<&impl WrapFetcher>,
+) -> Result<BatchPublishResult, PublishError> {
+    // Verified once and at the same time, the retries below only race on the packages
+    let manifests = match wrap_fetcher {
+        Some(wrap_fetcher) => {
+            join_all(
+                entries
+                    .iter()
+                    .map(|entry| fetch_manifest(&entry.uri, wrap_fetcher)),
+            )
+            .await
+        }
+        None => entries.iter().map(|_| Ok(None)).collect(),
+    };
+
+    let mut attempts = 1;
+
+    loop {
+        match try_publish_batch(user, entries, &manifests, reserved_names, &package_repo).await {
+            Err(PublishError::Conflict) if attempts < MAX_PUBLISH_ATTEMPTS => attempts += 1,
+            result => return result,
+        }
+    }
+}
+
+async fn try_publish_batch(
+    user: &Username,
+    entries: &[BatchEntry],
+    manifests: &[Result<Option<WrapManifestInfo>, PublishError>],
+    reserved_names: &ReservedNames,
+    package_repo: &impl Repository<Package>,
+) -> Result<BatchPublishResult, PublishError> {
+    let mut packages: BTreeMap<String, Package> = BTreeMap::new();
+    let mut changed_packages = BTreeSet::new();
+    let mut new_packages = BTreeSet::new();
+    let mut results = vec![];
+
+    for (entry, manifest) in entries.iter().zip(manifests) {
+        let id = Package::id_of(user, &entry.package_name);
+
+        if !packages.contains_key(&id) {
+            let package = match read_package_by_name(user, &entry.package_name, package_repo).await
+            {
+                Ok(package) => package,
+                Err(RepositoryError::NotFound) => {
+                    new_packages.insert(id.clone());
+                    Package::new(entry.package_name.clone(), user.clone())
+                }
+                Err(e) => return Err(PublishError::RepositoryError(e.to_string())),
+            };
+            packages.insert(id.clone(), package);
+        }
+
+        // Entries for the same package are applied on top of each other
+        let package = packages.get_mut(&id).expect("package was just inserted");
+        let new_package = new_packages.contains(&id);
+        let version = VersionName::SemVer(entry.version.clone());
+        let result = manifest.clone().and_then(|manifest| {
+            check_name(
+                package,
+                new_package,
+                user,
+                &entry.package_name,
+                reserved_names,
+            )
+            .and_then(|_| check_not_moved(package))
+            .and_then(|_| check_policy(package, &version, &entry.uri, entry.allow_major))
+            .and_then(|_| {
+                add_version(
+                    package,
+                    version,
+                    entry.uri.clone(),
+                    manifest,
+                    entry.signature.clone(),
+                )
+            })
+        });
+
+        if result.is_ok() {
+            changed_packages.insert(id);
+        }
+        results.push(result);
+    }
+
+    // Republishing the same version with the same URI changes nothing, just like a single publish
+    let published = results.iter().all(|result| {
+        matches!(
+            result,
+            Ok(_) | Err(PublishError::DuplicateVersionNameAndUri)
+        )
+    });
+
+    if published && !changed_packages.is_empty() {
+        let changed_packages = changed_packages
+            .iter()
+            .map(|id| packages[id].clone())
+            .collect::<Vec<_>>();
+
+        let size = batch_size(&changed_packages, &results);
+        if size > MAX_BATCH_BYTES {
+            return Err(PublishError::BatchTooLarge(size));
+        }
+
+        package_repo
+            .update_many(&changed_packages)
+            .await
+            .map_err(PublishError::from)?;
+    }
+
+    Ok(BatchPublishResult { published, results })
+}
+
+/// The JSON size of what a batch writes: the packages without their versions, which are stored
+/// in the package header, and the new versions
+fn batch_size(packages: &[Package], results: &[Result<Version, PublishError>]) -> usize {
+    let headers: usize = packages
+        .iter()
+        .map(|package| {
+            serde_json::to_vec(&Package {
+                versions: vec![],
+                ..package.clone()
+            })
+            .map_or(0, |json| json.len())
+        })
+        .sum();
+    let versions: usize = results
+        .iter()
+        .filter_map(|result| result.as_ref().ok())
+        .map(|version| serde_json::to_vec(version).map_or(0, |json| json.len()))
+        .sum();
+
+    headers + versions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fetching::InMemoryWrapFetcher,
+        mocks::MockPackageRepository,
+        models::ReservedNames,
+        publishing::{
+            publish_batch, BatchEntry, BatchPublishResult, PublishError, MAX_BATCH_BYTES,
+        },
+        InMemoryPackageRepository, Package, Repository, RepositoryError, Version,
+    };
+
+    /// The published version names, which are compared instead of the versions with their times
+    fn version_names(result: BatchPublishResult) -> (bool, Vec<Result<String, PublishError>>) {
+        let names = result
+            .results
+            .into_iter()
+            .map(|result| result.map(|version| version.name.to_string()))
+            .collect();
+
+        (result.published, names)
+    }
+
+    fn package(name: &str) -> Package {
+        let mut package = Package::new(name.parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            "1.0.0".parse().unwrap(),
+            format!("test/{}/uri1", name).parse().unwrap(),
+        )];
+        package
+    }
+
+    fn entry(package: &str, version: &str, uri: &str) -> BatchEntry {
+        BatchEntry {
+            package_name: package.parse().unwrap(),
+            version: version.parse().unwrap(),
+            uri: uri.parse().unwrap(),
+            allow_major: false,
+            signature: None,
+        }
+    }
+
+    async fn package_repo() -> InMemoryPackageRepository {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo
+            .update_many(&[package("package1"), package("package2")])
+            .await
+            .unwrap();
+
+        package_repo
+    }
+
+    /// Nothing was written since `package_repo` stored the packages
+    async fn assert_unchanged(package_repo: &InMemoryPackageRepository) {
+        for id in ["user1/package1", "user1/package2"] {
+            let package: Package = package_repo.read(id).await.unwrap();
+            assert_eq!(package.revision, 1);
+        }
+        assert!(matches!(
+            Repository::<Package>::read(package_repo, "user1/package3").await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn publishes_all_entries_in_one_transaction() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo.expect_read().returning(|id| match id {
+            "user1/package1" => Ok(package("package1")),
+            "user1/package2" => Ok(package("package2")),
+            _ => Err(RepositoryError::NotFound),
+        });
+        package_repo.expect_update().never();
+
+        package_repo
+            .expect_update_many()
+            .withf(|packages| {
+                packages.len() == 3
+                    && packages[0].id == "user1/package1"
+                    && packages[0].versions.len() == 3
+                    && packages[1].id == "user1/package2"
+                    && packages[1].versions.len() == 2
+                    && packages[2].id == "user1/package3"
+                    && packages[2].versions.len() == 1
+            })
+            .times(1)
+            .return_once(|_| Ok(()));
+
+        let result = publish_batch(
+            &"user1".parse().unwrap(),
+            &[
+                entry("package1", "1.1.0", "test/package1/uri2"),
+                entry("package1", "1.2.0", "test/package1/uri3"),
+                entry("package2", "1.1.0", "test/package2/uri2"),
+                entry("package2", "1.0.0", "test/package2/uri1"),
+                entry("package3", "1.0.0", "test/package3/uri1"),
+            ],
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(
+            version_names(result.unwrap()),
+            (
+                true,
+                vec![
+                    Ok("1.1.0".into()),
+                    Ok("1.2.0".into()),
+                    Ok("1.1.0".into()),
+                    Err(PublishError::DuplicateVersionNameAndUri),
+                    Ok("1.0.0".into()),
+                ]
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn publishes_nothing_if_any_entry_fails() {
+        let package_repo = package_repo().await;
+
+        let result = publish_batch(
+            &"user1".parse().unwrap(),
+            &[
+                entry("package1", "1.1.0", "test/package1/uri2"),
+                entry("package2", "1.0.0", "test/package2/uri2"),
+                entry("package1", "1.1.0", "test/package1/uri3"),
+            ],
+            &ReservedNames::default(),
+            package_repo.clone(),
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_unchanged(&package_repo).await;
+        assert_eq!(
+            version_names(result.unwrap()),
+            (
+                false,
+                vec![
+                    Ok("1.1.0".into()),
+                    Err(PublishError::DuplicateVersionName),
+                    Err(PublishError::DuplicateVersionName),
+                ]
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn verifies_the_manifest_of_every_entry() {
+        let package_repo = package_repo().await;
+
+        let wrap_fetcher = InMemoryWrapFetcher::new()
+            .with_manifest("test/package1/uri2", b"not a manifest".to_vec());
+
+        let result = publish_batch(
+            &"user1".parse().unwrap(),
+            &[
+                entry("package1", "1.1.0", "test/package1/uri2"),
+                entry("package2", "1.1.0", "test/package2/uri2"),
+            ],
+            &ReservedNames::default(),
+            package_repo.clone(),
+            Some(&wrap_fetcher),
+        )
+        .await
+        .unwrap();
+
+        assert_unchanged(&package_repo).await;
+        assert!(!result.published);
+        assert!(matches!(
+            result.results[0],
+            Err(PublishError::InvalidManifest(_))
+        ));
+        assert_eq!(
+            result.results[1],
+            Err(PublishError::ManifestNotFound(
+                "wrap://test/package2/uri2".into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_batch_larger_than_a_transaction() {
+        let package_repo = InMemoryPackageRepository::default();
+
+        // Each header fits in an item, both together don't fit in a transaction
+        let packages = ["package1", "package2"].map(|name| {
+            let mut package = package(name);
+            package.metadata.description = Some("a".repeat(MAX_BATCH_BYTES / 2));
+            package
+        });
+        package_repo.update_many(&packages).await.unwrap();
+
+        let result = publish_batch(
+            &"user1".parse().unwrap(),
+            &[
+                entry("package1", "1.1.0", "test/package1/uri2"),
+                entry("package2", "1.1.0", "test/package2/uri2"),
+            ],
+            &ReservedNames::default(),
+            package_repo.clone(),
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert!(matches!(result, Err(PublishError::BatchTooLarge(size)) if size > MAX_BATCH_BYTES));
+        assert_unchanged(&package_repo).await;
+    }
+}
@@ -1,87 +1,97 @@
 use crate::{
-    models::{Package, Version, WrapUri},
-    Repository,
+    models::{Package, Version, VersionName, VersionSignature, WrapManifestInfo, WrapUri},
+    VersionRepository,
 };
 
-use super::error::PublishError;
+use super::{error::PublishError, PublishedVersion};
 
 pub async fn publish_latest_version(
     package: &mut Package,
     uri: WrapUri,
-    package_repo: impl Repository<Package>,
-) -> Result<(), PublishError> {
+    manifest: Option<WrapManifestInfo>,
+    signature: Option<VersionSignature>,
+    dry_run: bool,
+    package_repo: &impl VersionRepository,
+) -> Result<PublishedVersion, PublishError> {
     if package.versions.len() > 1 {
         return Err(PublishError::LatestVersionNotAllowed);
     }
 
-    if package.versions.len() == 1 {
+    let previous_uri = if package.versions.len() == 1 {
         let existing_version = &mut package.versions[0];
 
-        if existing_version.name != "latest" {
+        if existing_version.name != VersionName::Latest {
             return Err(PublishError::LatestVersionNotAllowed);
         }
 
-        existing_version.uri = uri;
+        // Republishing the same wrap changes nothing, so it isn't stored, audited or announced
+        if existing_version.uri == uri
+            && existing_version.manifest == manifest
+            && existing_version.signature == signature
+        {
+            return Err(PublishError::DuplicateVersionNameAndUri);
+        }
+
+        existing_version.manifest = manifest;
+        existing_version.signature = signature;
+        Some(std::mem::replace(&mut existing_version.uri, uri))
     } else {
-        package
-            .versions
-            .push(Version::new("latest".to_string(), uri.clone()));
-    }
+        let mut version = Version::new(VersionName::Latest, uri);
+        version.manifest = manifest;
+        version.signature = signature;
+        package.versions.push(version);
+        None
+    };
 
-    package_repo
-        .update(package)
-        .await
-        .map_err(|e| PublishError::RepositoryError(e.to_string()))?;
+    if !dry_run {
+        package_repo
+            .update_version(package, &package.versions[0])
+            .await
+            .map_err(PublishError::from)?;
+    }
 
-    Ok(())
+    Ok(PublishedVersion {
+        version: package.versions[0].clone(),
+        previous_uri,
+        new_package: false,
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use async_trait::async_trait;
-    use mockall::{mock, predicate::eq};
-
     use crate::{
+        mocks::MockPackageRepository,
         publishing::{publish_latest_version, PublishError},
-        Package, Repository, RepositoryError, Version,
+        InMemoryPackageRepository, Package, Repository, RepositoryError, Version, VersionName,
     };
 
-    mock! {
-      PackageRepository {}
-        #[async_trait]
-        impl Repository<Package> for PackageRepository {
-            async fn read(&self, key: &str) -> Result<Package, RepositoryError>;
-            async fn update(&self, entity: &Package) -> Result<(), RepositoryError>;
-        }
+    fn package(versions: &[&str]) -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = versions
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                Version::new(
+                    name.parse().unwrap(),
+                    format!("test/uri{}", i + 1).parse().unwrap(),
+                )
+            })
+            .collect();
+        package
     }
 
     #[tokio::test]
     async fn can_publish_latest_version_when_no_versions_published() {
-        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
-
-        let update_package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![Version {
-                name: "latest".into(),
-                uri: "test/uri_latest".parse().unwrap(),
-                created_on: 0,
-            }],
-            created_on: 0,
-        };
-
-        let mut mock_package_repo = MockPackageRepository::new();
-        mock_package_repo
-            .expect_update()
-            .with(eq(update_package))
-            .times(1)
-            .returning(|_| Ok(()));
+        let mut package = package(&[]);
+        let package_repo = InMemoryPackageRepository::default();
 
         let result = publish_latest_version(
             &mut package,
             "test/uri_latest".parse().unwrap(),
-            mock_package_repo,
+            None,
+            None,
+            false,
+            &package_repo,
         )
         .await;
 
@@ -90,9 +100,12 @@ mod tests {
             "Publishing the latest version failed: {:?}",
             result
         );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
         assert_eq!(package.versions.len(), 1, "Unexpected number of versions");
         assert_eq!(
-            package.versions[0].name, "latest",
+            package.versions[0].name,
+            VersionName::Latest,
             "Version name is not 'latest'"
         );
         assert_eq!(
@@ -104,52 +117,31 @@ mod tests {
 
     #[tokio::test]
     async fn can_publish_latest_version_when_latest_already_published() {
-        let mut package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![Version {
-                name: "latest".into(),
-                uri: "test/uri1".parse().unwrap(),
-                created_on: 0,
-            }],
-            created_on: 0,
-        };
-
-        let update_package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![Version {
-                name: "latest".into(),
-                uri: "test/uri_latest".parse().unwrap(),
-                created_on: 0,
-            }],
-            created_on: 0,
-        };
-
-        let mut mock_package_repo = MockPackageRepository::new();
-        mock_package_repo
-            .expect_update()
-            .with(eq(update_package.clone()))
-            .times(1)
-            .returning(|_| Ok(()));
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package(&["latest"])).await.unwrap();
+        let mut package: Package = package_repo.read("user1/package1").await.unwrap();
 
         let result = publish_latest_version(
             &mut package,
             "test/uri_latest".parse().unwrap(),
-            mock_package_repo,
+            None,
+            None,
+            false,
+            &package_repo,
         )
         .await;
 
-        assert!(
-            result.is_ok(),
-            "Publishing the latest version failed: {:?}",
-            result
+        assert_eq!(
+            result.map(|p| p.previous_uri),
+            Ok(Some("test/uri1".parse().unwrap())),
+            "The overwritten URI was not returned"
         );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
         assert_eq!(package.versions.len(), 1, "Unexpected number of versions");
         assert_eq!(
-            package.versions[0].name, "latest",
+            package.versions[0].name,
+            VersionName::Latest,
             "Version name is not 'latest'"
         );
         assert_eq!(
@@ -159,100 +151,90 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn republishing_the_same_latest_version_changes_nothing() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package(&["latest"])).await.unwrap();
+        let mut package: Package = package_repo.read("user1/package1").await.unwrap();
+
+        let result = publish_latest_version(
+            &mut package,
+            "test/uri1".parse().unwrap(),
+            None,
+            None,
+            false,
+            &package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(PublishError::DuplicateVersionNameAndUri));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+
     #[tokio::test]
     async fn can_publish_latest_version_when_one_already_published() {
-        let mut package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![Version {
-                name: "1.0.0".into(),
-                uri: "test/uri1".parse().unwrap(),
-                created_on: 0,
-            }],
-            created_on: 0,
-        };
-
-        let mut mock_package_repo = MockPackageRepository::new();
-        mock_package_repo.expect_update().times(0);
+        let mut package = package(&["1.0.0"]);
+        let package_repo = InMemoryPackageRepository::default();
 
         let result = publish_latest_version(
             &mut package,
             "test/uri_latest".parse().unwrap(),
-            mock_package_repo,
+            None,
+            None,
+            false,
+            &package_repo,
         )
         .await;
 
         assert_eq!(result, Err(PublishError::LatestVersionNotAllowed));
+        assert!(matches!(
+            Repository::<Package>::read(&package_repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
     }
 
     #[tokio::test]
     async fn can_publish_latest_version_when_multiple_already_published() {
-        let mut package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![
-                Version {
-                    name: "1.0.0".into(),
-                    uri: "test/uri1".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "1.0.1".into(),
-                    uri: "test/uri2".parse().unwrap(),
-                    created_on: 0,
-                },
-            ],
-            created_on: 0,
-        };
-
-        let mut mock_package_repo = MockPackageRepository::new();
-        mock_package_repo.expect_update().times(0);
+        let mut package = package(&["1.0.0", "1.0.1"]);
+        let package_repo = InMemoryPackageRepository::default();
 
         let result = publish_latest_version(
             &mut package,
             "test/uri_latest".parse().unwrap(),
-            mock_package_repo,
+            None,
+            None,
+            false,
+            &package_repo,
         )
         .await;
 
         assert_eq!(result, Err(PublishError::LatestVersionNotAllowed));
+        assert!(matches!(
+            Repository::<Package>::read(&package_repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
     }
 
     #[tokio::test]
     async fn publish_latest_version_fails_when_unknown_repository_error() {
-        let mut package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![],
-            created_on: 0,
-        };
-
-        let update_package = Package {
-            id: "user1/package1".into(),
-            name: "package1".parse().unwrap(),
-            user: "user1".parse().unwrap(),
-            versions: vec![Version {
-                name: "latest".into(),
-                uri: "test/uri_latest".parse().unwrap(),
-                created_on: 0,
-            }],
-            created_on: 0,
-        };
-
-        let mut mock_package_repo = MockPackageRepository::new();
-        mock_package_repo
-            .expect_update()
-            .with(eq(update_package))
+        let mut package = package(&[]);
+
+        let mut package_repo = MockPackageRepository::new();
+        package_repo
+            .expect_update_version()
+            .withf(|p, v| p.versions.len() == 1 && v.name == VersionName::Latest)
             .times(1)
-            .returning(|_| Err(RepositoryError::Unknown("some error".to_string())));
+            .returning(|_, _| Err(RepositoryError::Unknown("some error".to_string())));
 
         let result = publish_latest_version(
             &mut package,
             "test/uri_latest".parse().unwrap(),
-            mock_package_repo,
+            None,
+            None,
+            false,
+            &package_repo,
         )
         .await;
 
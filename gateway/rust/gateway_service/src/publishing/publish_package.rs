@@ -1,29 +1,92 @@
-use crate::models::{Package, PackageName, PartialVersion, Username, Version, WrapUri};
-use crate::{semver, Repository, RepositoryError};
+use crate::fetching::WrapFetcher;
+use crate::models::{
+    Package, PackageMetadata, PackageName, ReservedNames, Username, Version, VersionName,
+    VersionSignature, WrapManifestInfo, WrapUri,
+};
+use crate::{
+    read_all_versions, read_header_by_name, read_version_of, Repository, RepositoryError,
+    VersionRepository,
+};
 
 use super::error::PublishError;
 
-use super::publish_latest_version;
+use super::{
+    add_version, check_name, check_policy, fetch_manifest, publish_latest_version,
+    MAX_PUBLISH_ATTEMPTS,
+};
+
+/// A published version, and the URI it replaced when `latest` was overwritten
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishedVersion {
+    pub version: Version,
+    pub previous_uri: Option<WrapUri>,
+    /// Whether the package was created by the publish
+    pub new_package: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PublishOptions {
+    /// Replaces the package metadata when given
+    pub metadata: Option<PackageMetadata>,
+    /// Allows a new major version when the package policy requires the flag
+    pub allow_major: bool,
+    /// Recorded on the version, verified against the publisher keys before publishing
+    pub signature: Option<VersionSignature>,
+    /// Runs every check of the publish without storing the package
+    pub dry_run: bool,
+}
 
+#[allow(clippy::too_many_arguments)]
 pub async fn publish_package(
     user: &Username,
     package_name: &PackageName,
-    version_name: Option<&str>,
+    new_version: VersionName,
     uri: WrapUri,
-    package_repo: impl Repository<Package>,
-) -> Result<(), PublishError> {
-    if let Some(version) = version_name {
-        if !semver::verify(&PartialVersion {
-            name: version.to_string(),
-        }) {
-            return Err(PublishError::InvalidVersionFormat);
+    options: PublishOptions,
+    reserved_names: &ReservedNames,
+    package_repo: impl Repository<Package> + VersionRepository,
+    wrap_fetcher: Option<&impl WrapFetcher>,
+) -> Result<PublishedVersion, PublishError> {
+    // Verified once, the retries below only race on the package
+    let manifest = match wrap_fetcher {
+        Some(wrap_fetcher) => fetch_manifest(&uri, wrap_fetcher).await?,
+        None => None,
+    };
+
+    let mut attempts = 1;
+
+    loop {
+        let result = try_publish_package(
+            user,
+            package_name,
+            new_version.clone(),
+            uri.clone(),
+            options.clone(),
+            manifest.clone(),
+            reserved_names,
+            &package_repo,
+        )
+        .await;
+
+        match result {
+            Err(PublishError::Conflict) if attempts < MAX_PUBLISH_ATTEMPTS => attempts += 1,
+            result => return result,
         }
     }
+}
 
-    let new_version = version_name.unwrap_or("latest");
-    let id = format!("{}/{}", user, package_name);
-
-    let package = package_repo.read(&id).await;
+#[allow(clippy::too_many_arguments)]
+async fn try_publish_package(
+    user: &Username,
+    package_name: &PackageName,
+    new_version: VersionName,
+    uri: WrapUri,
+    options: PublishOptions,
+    manifest: Option<WrapManifestInfo>,
+    reserved_names: &ReservedNames,
+    package_repo: &(impl Repository<Package> + VersionRepository),
+) -> Result<PublishedVersion, PublishError> {
+    let package = read_header_by_name(user, package_name, package_repo).await;
 
     let package = match package {
         Ok(package) => Some(package),
@@ -31,60 +94,122 @@ pub async fn publish_package(
         Err(e) => return Err(PublishError::RepositoryError(e.to_string())),
     };
 
+    let new_package = package.is_none();
+    let mut metadata_changed = false;
+
     let mut package = if let Some(mut package) = package {
-        if new_version == "latest" {
-            return publish_latest_version(&mut package, uri, package_repo).await;
+        check_name(&package, new_package, user, package_name, reserved_names)?;
+        check_not_moved(&package)?;
+
+        package.versions = read_checked_versions(&package, &new_version, package_repo)
+            .await
+            .map_err(|e| PublishError::RepositoryError(e.to_string()))?;
+        check_policy(&package, &new_version, &uri, options.allow_major)?;
+
+        if let Some(metadata) = options.metadata {
+            metadata_changed = metadata != package.metadata;
+            package.metadata = metadata;
         }
 
-        let existing_version = package
-            .versions
-            .iter()
-            .find(|version| version.name == new_version);
-        if let Some(existing_version) = existing_version {
-            match existing_version.uri == uri {
-                true => return Err(PublishError::DuplicateVersionNameAndUri),
-                false => return Err(PublishError::DuplicateVersionName),
-            }
+        if new_version == VersionName::Latest {
+            return match publish_latest_version(
+                &mut package,
+                uri,
+                manifest,
+                options.signature,
+                options.dry_run,
+                package_repo,
+            )
+            .await
+            {
+                Err(PublishError::DuplicateVersionNameAndUri) if metadata_changed => {
+                    Err(PublishError::MetadataNotApplied)
+                }
+                result => result,
+            };
         }
 
         package
     } else {
-        Package::new(package_name.clone(), user.clone())
+        let mut package = Package::new(package_name.clone(), user.clone());
+        check_name(&package, new_package, user, package_name, reserved_names)?;
+        package.metadata = options.metadata.unwrap_or_default();
+        package
+    };
+
+    let version = match add_version(&mut package, new_version, uri, manifest, options.signature) {
+        // Republishing the same version doesn't store anything, so it can't change the metadata
+        Err(PublishError::DuplicateVersionNameAndUri) if metadata_changed => {
+            return Err(PublishError::MetadataNotApplied)
+        }
+        result => result?,
     };
 
-    package
-        .versions
-        .push(Version::new(new_version.to_string(), uri));
+    if !options.dry_run {
+        package_repo
+            .update_version(&package, &version)
+            .await
+            .map_err(PublishError::from)?;
+    }
 
-    semver::sort_versions(&mut package.versions);
+    Ok(PublishedVersion {
+        version,
+        previous_uri: None,
+        new_package,
+    })
+}
 
-    package_repo
-        .update(&package)
-        .await
-        .map_err(|e| PublishError::RepositoryError(e.to_string()))?;
+/// Reads the versions of a package the checks of a publish of `new_version` need, rather than
+/// all of them: `latest` can only be published to a package without other versions, and the
+/// duplicate checks only need the version itself unless the policy compares it to the others
+async fn read_checked_versions(
+    package: &Package,
+    new_version: &VersionName,
+    version_repo: &impl VersionRepository,
+) -> Result<Vec<Version>, RepositoryError> {
+    if package.revision == 0 {
+        return Ok(package.versions.clone());
+    }
+
+    let policy = &package.policy;
+    if *new_version == VersionName::Latest {
+        return Ok(version_repo
+            .read_versions(&package.id, None, 2)
+            .await?
+            .versions);
+    }
+    if policy.monotonic_within_major || policy.require_major_flag {
+        return read_all_versions(package, version_repo).await;
+    }
 
-    Ok(())
+    match read_version_of(package, new_version, version_repo).await {
+        Ok(version) => Ok(vec![version]),
+        Err(RepositoryError::NotFound) => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
+
+/// A moved package only redirects, new versions go to where it moved
+pub(super) fn check_not_moved(package: &Package) -> Result<(), PublishError> {
+    match &package.redirect {
+        Some(target) => Err(PublishError::PackageMoved(target.clone())),
+        None => Ok(()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use async_trait::async_trait;
-    use mockall::{mock, predicate::eq};
+    use mockall::predicate::eq;
+    use serde_json::json;
 
     use crate::{
-        publishing::{publish_package, PublishError},
-        Package, Repository, RepositoryError, Version,
+        fetching::InMemoryWrapFetcher,
+        mocks::MockPackageRepository,
+        models::{PackageMetadata, ReservedNames},
+        publishing::{publish_package, PublishError, PublishOptions, MAX_PUBLISH_ATTEMPTS},
+        Package, RepositoryError, Version, WrapManifestInfo,
     };
 
-    mock! {
-      PackageRepository {}
-        #[async_trait]
-        impl Repository<Package> for PackageRepository {
-            async fn read(&self, key: &str) -> Result<Package, RepositoryError>;
-            async fn update(&self, entity: &Package) -> Result<(), RepositoryError>;
-        }
-    }
-
     #[tokio::test]
     async fn can_publish_version() {
         let package = Package {
@@ -92,45 +217,106 @@ mod tests {
             name: "package1".parse().unwrap(),
             user: "user1".parse().unwrap(),
             versions: vec![Version {
-                name: "1.0.0".into(),
+                name: "1.0.0".parse().unwrap(),
                 uri: "test/uri1".parse().unwrap(),
                 created_on: 0,
+                yanked: None,
+                manifest: None,
+                signature: None,
             }],
             created_on: 0,
+            metadata: Default::default(),
+            dist_tags: Default::default(),
+            unpublished_versions: Default::default(),
+            pending_transfer: None,
+            redirect: None,
+            revision: 0,
+            policy: Default::default(),
         };
 
         let new_version = Version {
-            name: "2.0.0".into(),
+            name: "2.0.0".parse().unwrap(),
             uri: "test/uri2".parse().unwrap(),
             created_on: 0,
+            yanked: None,
+            manifest: None,
+            signature: None,
         };
 
         let mut package_repo = MockPackageRepository::new();
 
         let read_package = package.clone();
         package_repo
-            .expect_read()
+            .expect_read_header()
             .with(eq("user1/package1".to_string()))
             .return_once(move |_| Ok(read_package));
 
         let package = package.clone();
         package_repo
-            .expect_update()
-            .withf(move |p| {
+            .expect_update_version()
+            .withf(move |p, _| {
                 p.id == package.id && p.versions.len() == 2 && p.versions[1] == new_version
             })
-            .return_once(move |_| Ok(()));
+            .return_once(move |_, _| Ok(()));
 
         let result = publish_package(
             &package.user,
             &package.name,
-            Some("2.0.0"),
+            "2.0.0".parse().unwrap(),
             "test/uri2".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
             package_repo,
+            None::<&InMemoryWrapFetcher>,
         )
         .await;
 
-        assert_eq!(result, Ok(()));
+        assert_eq!(result.map(|p| p.version.name), Ok("2.0.0".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn reads_only_the_published_version_of_a_stored_package() {
+        for (stored_uri, expected) in [
+            (None, Ok("2.0.0".parse().unwrap())),
+            (Some("test/uri1"), Err(PublishError::DuplicateVersionName)),
+        ] {
+            let mut package_repo = MockPackageRepository::new();
+
+            package_repo.expect_read_header().return_once(|_| {
+                let mut package =
+                    Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+                package.revision = 3;
+                Ok(package)
+            });
+            package_repo
+                .expect_read_version()
+                .withf(|id, version| id == "user1/package1" && version.to_string() == "2.0.0")
+                .return_once(move |_, version| match stored_uri {
+                    Some(uri) => Ok(Version::new(version.clone(), uri.parse().unwrap())),
+                    None => Err(RepositoryError::NotFound),
+                });
+            package_repo.expect_read_versions().never();
+            package_repo.expect_read().never();
+            package_repo
+                .expect_update_version()
+                .withf(|p, v| p.revision == 3 && v.name.to_string() == "2.0.0")
+                .times(expected.is_ok() as usize)
+                .return_once(|_, _| Ok(()));
+
+            let result = publish_package(
+                &"user1".parse().unwrap(),
+                &"package1".parse().unwrap(),
+                "2.0.0".parse().unwrap(),
+                "test/uri2".parse().unwrap(),
+                Default::default(),
+                &ReservedNames::default(),
+                package_repo,
+                None::<&InMemoryWrapFetcher>,
+            )
+            .await;
+
+            assert_eq!(result.map(|p| p.version.name), expected);
+        }
     }
 
     #[tokio::test]
@@ -140,11 +326,21 @@ mod tests {
             name: "package1".parse().unwrap(),
             user: "user1".parse().unwrap(),
             versions: vec![Version {
-                name: "1.0.0".into(),
+                name: "1.0.0".parse().unwrap(),
                 uri: "test/uri1".parse().unwrap(),
                 created_on: 0,
+                yanked: None,
+                manifest: None,
+                signature: None,
             }],
             created_on: 0,
+            metadata: Default::default(),
+            dist_tags: Default::default(),
+            unpublished_versions: Default::default(),
+            pending_transfer: None,
+            redirect: None,
+            revision: 0,
+            policy: Default::default(),
         };
 
         let mut package_repo = MockPackageRepository::new();
@@ -152,19 +348,22 @@ mod tests {
         {
             let package = package.clone();
             package_repo
-                .expect_read()
+                .expect_read_header()
                 .with(eq("user1/package1".to_string()))
                 .return_once(move |_| Ok(package));
         }
 
-        package_repo.expect_update().never();
+        package_repo.expect_update_version().never();
 
         let result = publish_package(
             &package.user,
             &package.name,
-            Some("1.0.0"),
+            "1.0.0".parse().unwrap(),
             "test/uri2".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
             package_repo,
+            None::<&InMemoryWrapFetcher>,
         )
         .await;
 
@@ -178,11 +377,21 @@ mod tests {
             name: "package1".parse().unwrap(),
             user: "user1".parse().unwrap(),
             versions: vec![Version {
-                name: "1.0.0".into(),
+                name: "1.0.0".parse().unwrap(),
                 uri: "test/uri1".parse().unwrap(),
                 created_on: 0,
+                yanked: None,
+                manifest: None,
+                signature: None,
             }],
             created_on: 0,
+            metadata: Default::default(),
+            dist_tags: Default::default(),
+            unpublished_versions: Default::default(),
+            pending_transfer: None,
+            redirect: None,
+            revision: 0,
+            policy: Default::default(),
         };
 
         let mut package_repo = MockPackageRepository::new();
@@ -190,19 +399,22 @@ mod tests {
         {
             let package = package.clone();
             package_repo
-                .expect_read()
+                .expect_read_header()
                 .with(eq("user1/package1".to_string()))
                 .return_once(move |_| Ok(package));
         }
 
-        package_repo.expect_update().never();
+        package_repo.expect_update_version().never();
 
         let result = publish_package(
             &package.user,
             &package.name,
-            Some("1.0.0"),
+            "1.0.0".parse().unwrap(),
             "test/uri1".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
             package_repo,
+            None::<&InMemoryWrapFetcher>,
         )
         .await;
 
@@ -210,39 +422,513 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn forbids_publishing_invalid_version() {
+    async fn rejects_new_metadata_when_republishing_same_version() {
+        let package = Package {
+            id: "user1/package1".into(),
+            name: "package1".parse().unwrap(),
+            user: "user1".parse().unwrap(),
+            versions: vec![Version {
+                name: "1.0.0".parse().unwrap(),
+                uri: "test/uri1".parse().unwrap(),
+                created_on: 0,
+                yanked: None,
+                manifest: None,
+                signature: None,
+            }],
+            created_on: 0,
+            metadata: Default::default(),
+            dist_tags: Default::default(),
+            unpublished_versions: Default::default(),
+            pending_transfer: None,
+            redirect: None,
+            revision: 0,
+            policy: Default::default(),
+        };
+
+        let mut package_repo = MockPackageRepository::new();
+
+        {
+            let package = package.clone();
+            package_repo
+                .expect_read_header()
+                .with(eq("user1/package1".to_string()))
+                .return_once(move |_| Ok(package));
+        }
+
+        package_repo.expect_update_version().never();
+
+        let result = publish_package(
+            &package.user,
+            &package.name,
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            PublishOptions {
+                metadata: Some(PackageMetadata {
+                    description: Some("A package".into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(result, Err(PublishError::MetadataNotApplied));
+    }
+
+    #[tokio::test]
+    async fn forbids_republishing_unpublished_version_with_different_uri() {
         let package = Package {
             id: "user1/package1".into(),
             name: "package1".parse().unwrap(),
             user: "user1".parse().unwrap(),
             versions: vec![Version {
-                name: "1.0.0".into(),
+                name: "1.0.0".parse().unwrap(),
                 uri: "test/uri1".parse().unwrap(),
                 created_on: 0,
+                yanked: None,
+                manifest: None,
+                signature: None,
             }],
             created_on: 0,
+            metadata: Default::default(),
+            dist_tags: Default::default(),
+            unpublished_versions: [("1.0.1".parse().unwrap(), "test/uri2".parse().unwrap())].into(),
+            pending_transfer: None,
+            redirect: None,
+            revision: 0,
+            policy: Default::default(),
+        };
+
+        let mut package_repo = MockPackageRepository::new();
+
+        {
+            let package = package.clone();
+            package_repo
+                .expect_read_header()
+                .with(eq("user1/package1".to_string()))
+                .return_once(move |_| Ok(package));
+        }
+
+        package_repo.expect_update_version().never();
+
+        let result = publish_package(
+            &package.user,
+            &package.name,
+            "1.0.1".parse().unwrap(),
+            "test/uri3".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(result, Err(PublishError::UnpublishedVersionName));
+    }
+
+    #[tokio::test]
+    async fn stores_metadata_when_publishing_new_package() {
+        let metadata = PackageMetadata {
+            description: Some("Reads and writes files".into()),
+            license: Some("MIT".into()),
+            ..Default::default()
         };
 
         let mut package_repo = MockPackageRepository::new();
 
+        package_repo
+            .expect_read_header()
+            .with(eq("user1/package1".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+
+        {
+            let metadata = metadata.clone();
+            package_repo
+                .expect_update_version()
+                .withf(move |p, _| p.metadata == metadata && p.versions.len() == 1)
+                .return_once(|_, _| Ok(()));
+        }
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            PublishOptions {
+                metadata: Some(metadata),
+                ..Default::default()
+            },
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(result.map(|p| p.version.name), Ok("1.0.0".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn dry_run_does_not_store_package() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read_header()
+            .with(eq("user1/package1".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+        package_repo.expect_update_version().never();
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            PublishOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.version.name, "1.0.0".parse().unwrap());
+        assert!(result.new_package);
+    }
+
+    #[tokio::test]
+    async fn retries_publish_on_conflict() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read_header()
+            .with(eq("user1/package1".to_string()))
+            .times(2)
+            .returning(|_| Err(RepositoryError::NotFound));
+
+        let mut conflicted = false;
+        package_repo
+            .expect_update_version()
+            .times(2)
+            .returning(move |_, _| {
+                if conflicted {
+                    Ok(())
+                } else {
+                    conflicted = true;
+                    Err(RepositoryError::Conflict)
+                }
+            });
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(result.map(|p| p.version.name), Ok("1.0.0".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn fails_with_conflict_when_retries_are_exhausted() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read_header()
+            .times(MAX_PUBLISH_ATTEMPTS)
+            .returning(|_| Err(RepositoryError::NotFound));
+        package_repo
+            .expect_update_version()
+            .times(MAX_PUBLISH_ATTEMPTS)
+            .returning(|_, _| Err(RepositoryError::Conflict));
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(result, Err(PublishError::Conflict));
+    }
+
+    #[tokio::test]
+    async fn enforces_policy_before_writing() {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+        )];
+        package.policy.require_major_flag = true;
+
+        let mut package_repo = MockPackageRepository::new();
+
         {
             let package = package.clone();
             package_repo
-                .expect_read()
+                .expect_read_header()
                 .with(eq("user1/package1".to_string()))
                 .return_once(move |_| Ok(package));
         }
-        package_repo.expect_update().never();
+
+        package_repo.expect_update_version().never();
 
         let result = publish_package(
             &package.user,
             &package.name,
-            Some("1.0.0a"),
+            "2.0.0".parse().unwrap(),
+            "test/uri2".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(result, Err(PublishError::MajorVersionNotAllowed));
+    }
+
+    #[tokio::test]
+    async fn records_manifest_of_published_version() {
+        let wrap_fetcher = InMemoryWrapFetcher::new().with_manifest(
+            "test/uri1",
+            polywrap_msgpack_serde::to_vec(&json!({
+                "version": "0.1",
+                "name": "package1",
+                "type": "wasm",
+                "abi": { "version": "0.1" }
+            }))
+            .unwrap(),
+        );
+
+        let manifest = WrapManifestInfo {
+            name: "package1".into(),
+            wrap_type: "wasm".into(),
+        };
+
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read_header()
+            .with(eq("user1/package1".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+
+        {
+            let manifest = manifest.clone();
+            package_repo
+                .expect_update_version()
+                .withf(move |p, _| p.versions[0].manifest.as_ref() == Some(&manifest))
+                .return_once(|_, _| Ok(()));
+        }
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            Some(&wrap_fetcher),
+        )
+        .await;
+
+        assert_eq!(result.map(|p| p.version.manifest), Ok(Some(manifest)));
+    }
+
+    #[tokio::test]
+    async fn forbids_publishing_uri_without_manifest() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo.expect_read_header().never();
+        package_repo.expect_update_version().never();
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            Some(&InMemoryWrapFetcher::new()),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(PublishError::ManifestNotFound("wrap://test/uri1".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn forbids_publishing_uri_with_malformed_manifest() {
+        let wrap_fetcher =
+            InMemoryWrapFetcher::new().with_manifest("test/uri1", b"not a manifest".to_vec());
+
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo.expect_read_header().never();
+        package_repo.expect_update_version().never();
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            Some(&wrap_fetcher),
+        )
+        .await;
+
+        assert!(matches!(result, Err(PublishError::InvalidManifest(_))));
+    }
+
+    #[tokio::test]
+    async fn rejects_name_that_only_differs_in_casing() {
+        let mut package_repo = MockPackageRepository::new();
+
+        let mut package = Package::new("Package1".parse().unwrap(), "user1".parse().unwrap());
+        package.revision = 1;
+        package_repo
+            .expect_read_header()
+            .with(eq("user1/package1".to_string()))
+            .return_once(move |_| Ok(package));
+        package_repo.expect_update_version().never();
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(PublishError::ConfusableName("user1/Package1".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_new_package_with_reserved_name() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read_header()
+            .with(eq("user1/wrapscan".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+        package_repo
+            .expect_read()
+            .with(eq("user1/WrapScan".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+        package_repo.expect_update_version().never();
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"WrapScan".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
+            package_repo,
+            None::<&InMemoryWrapFetcher>,
+        )
+        .await;
+
+        assert_eq!(result, Err(PublishError::ReservedName("WrapScan".into())));
+    }
+
+    #[tokio::test]
+    async fn publishes_to_existing_package_with_reserved_name_at_revision_0() {
+        let mut package_repo = MockPackageRepository::new();
+
+        // As read from the legacy table, or written by `migrate_packages`
+        package_repo
+            .expect_read_header()
+            .with(eq("user1/wrapscan".to_string()))
+            .return_once(|_| {
+                let mut package =
+                    Package::new("wrapscan".parse().unwrap(), "user1".parse().unwrap());
+                package.versions = vec![Version::new(
+                    "1.0.0".parse().unwrap(),
+                    "test/uri1".parse().unwrap(),
+                )];
+                Ok(package)
+            });
+        package_repo
+            .expect_update_version()
+            .withf(|p, v| p.revision == 0 && v.name.to_string() == "1.1.0")
+            .times(1)
+            .return_once(|_, _| Ok(()));
+
+        let result = publish_package(
+            &"user1".parse().unwrap(),
+            &"wrapscan".parse().unwrap(),
+            "1.1.0".parse().unwrap(),
             "test/uri2".parse().unwrap(),
+            Default::default(),
+            &ReservedNames::default(),
             package_repo,
+            None::<&InMemoryWrapFetcher>,
         )
         .await;
 
-        assert_eq!(result, Err(PublishError::InvalidVersionFormat));
+        assert!(!result.unwrap().new_package);
+    }
+
+    #[tokio::test]
+    async fn moved_package_rejects_versions() {
+        for version in ["1.0.0", "latest"] {
+            let mut package_repo = MockPackageRepository::new();
+
+            package_repo
+                .expect_read_header()
+                .with(eq("user1/package1".to_string()))
+                .return_once(|_| {
+                    let mut package =
+                        Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+                    package.redirect = Some("user2/package1".into());
+                    Ok(package)
+                });
+            package_repo.expect_update_version().never();
+
+            let result = publish_package(
+                &"user1".parse().unwrap(),
+                &"package1".parse().unwrap(),
+                version.parse().unwrap(),
+                "test/uri2".parse().unwrap(),
+                Default::default(),
+                &ReservedNames::default(),
+                package_repo,
+                None::<&InMemoryWrapFetcher>,
+            )
+            .await;
+
+            assert_eq!(
+                result,
+                Err(PublishError::PackageMoved("user2/package1".into()))
+            );
+        }
     }
 }
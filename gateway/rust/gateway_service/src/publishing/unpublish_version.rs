@@ -0,0 +1,164 @@
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    models::{Package, PackageName, Username, WrapUri},
+    read_package_by_name, Repository, RepositoryError, SemVer,
+};
+
+use super::error::UnpublishError;
+
+/// Returns the URI of the unpublished version
+pub async fn unpublish_version(
+    user: &Username,
+    package_name: &PackageName,
+    version: &SemVer,
+    unpublish_window: Duration,
+    package_repo: impl Repository<Package>,
+) -> Result<WrapUri, UnpublishError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => UnpublishError::PackageNotFound,
+            e => UnpublishError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(UnpublishError::ConfusableName)?;
+
+    let index = package
+        .versions
+        .iter()
+        .position(|v| v.name.semver() == Some(version))
+        .ok_or(UnpublishError::VersionNotFound)?;
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis();
+
+    if now.saturating_sub(package.versions[index].created_on) > unpublish_window.as_millis() {
+        return Err(UnpublishError::UnpublishWindowExpired);
+    }
+
+    let unpublished = package.versions.remove(index);
+
+    package
+        .unpublished_versions
+        .insert(version.clone(), unpublished.uri.clone());
+    package.dist_tags.retain(|_, tagged| tagged != version);
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => UnpublishError::Conflict,
+        e => UnpublishError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(unpublished.uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use crate::{
+        publishing::{unpublish_version, UnpublishError},
+        InMemoryPackageRepository, Package, Repository, Version,
+    };
+
+    const UNPUBLISH_WINDOW: Duration = Duration::from_secs(72 * 60 * 60);
+
+    fn package(created_on: u128) -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![
+            Version {
+                created_on: 0,
+                ..Version::new("1.0.0".parse().unwrap(), "test/uri1".parse().unwrap())
+            },
+            Version {
+                created_on,
+                ..Version::new("1.0.1".parse().unwrap(), "test/uri2".parse().unwrap())
+            },
+        ];
+        package.dist_tags = [("beta".parse().unwrap(), "1.0.1".parse().unwrap())].into();
+        package
+    }
+
+    fn now() -> u128 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    }
+
+    #[tokio::test]
+    async fn can_unpublish_version_within_window() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package(now())).await.unwrap();
+
+        let result = unpublish_version(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"1.0.1".parse().unwrap(),
+            UNPUBLISH_WINDOW,
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Ok("test/uri2".parse().unwrap()));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.versions.len(), 1);
+        assert!(package.dist_tags.is_empty());
+        assert_eq!(
+            package.unpublished_versions.get(&"1.0.1".parse().unwrap()),
+            Some(&"test/uri2".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn forbids_unpublishing_after_window() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo
+            .update(&package(now() - UNPUBLISH_WINDOW.as_millis() - 1))
+            .await
+            .unwrap();
+
+        let result = unpublish_version(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"1.0.1".parse().unwrap(),
+            UNPUBLISH_WINDOW,
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(UnpublishError::UnpublishWindowExpired));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.versions.len(), 2);
+        assert_eq!(package.revision, 1);
+    }
+
+    #[tokio::test]
+    async fn forbids_unpublishing_as_differently_cased_user() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package(now())).await.unwrap();
+
+        let result = unpublish_version(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"1.0.1".parse().unwrap(),
+            UNPUBLISH_WINDOW,
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(UnpublishError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}
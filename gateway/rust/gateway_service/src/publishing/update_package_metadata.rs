@@ -0,0 +1,132 @@
+use crate::{
+    models::{Package, PackageMetadata, PackageName, Username},
+    read_package_by_name, Repository, RepositoryError,
+};
+
+use super::error::UpdateMetadataError;
+
+pub async fn update_package_metadata(
+    user: &Username,
+    package_name: &PackageName,
+    metadata: PackageMetadata,
+    package_repo: impl Repository<Package>,
+) -> Result<(), UpdateMetadataError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => UpdateMetadataError::PackageNotFound,
+            e => UpdateMetadataError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(UpdateMetadataError::ConfusableName)?;
+
+    if package.redirect.is_some() {
+        return Err(UpdateMetadataError::PackageNotFound);
+    }
+
+    if package.metadata == metadata {
+        return Ok(());
+    }
+
+    package.metadata = metadata;
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => UpdateMetadataError::Conflict,
+        e => UpdateMetadataError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::PackageMetadata,
+        publishing::{update_package_metadata, UpdateMetadataError},
+        InMemoryPackageRepository, Package, Repository, RepositoryError, Version,
+    };
+
+    #[tokio::test]
+    async fn can_update_metadata_without_publishing() {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+        )];
+
+        let metadata = PackageMetadata {
+            keywords: vec!["fs".into()],
+            homepage: Some("https://polywrap.io".into()),
+            ..Default::default()
+        };
+
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package).await.unwrap();
+
+        let result = update_package_metadata(
+            &package.user,
+            &package.name,
+            metadata.clone(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.metadata, metadata);
+        assert_eq!(package.versions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fails_to_update_metadata_of_non_existent_package() {
+        let package_repo = InMemoryPackageRepository::default();
+
+        let result = update_package_metadata(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            Default::default(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(UpdateMetadataError::PackageNotFound));
+        assert!(matches!(
+            Repository::<Package>::read(&package_repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn forbids_updating_metadata_as_differently_cased_user() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo
+            .update(&Package::new(
+                "package1".parse().unwrap(),
+                "user1".parse().unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let result = update_package_metadata(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            PackageMetadata {
+                keywords: vec!["fs".into()],
+                ..Default::default()
+            },
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(UpdateMetadataError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}
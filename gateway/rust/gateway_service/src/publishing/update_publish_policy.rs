@@ -0,0 +1,127 @@
+use crate::{
+    models::{Package, PackageName, PublishPolicy, Username},
+    read_package_by_name, Repository, RepositoryError,
+};
+
+use super::error::UpdatePolicyError;
+
+pub async fn update_publish_policy(
+    user: &Username,
+    package_name: &PackageName,
+    policy: PublishPolicy,
+    package_repo: impl Repository<Package>,
+) -> Result<(), UpdatePolicyError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => UpdatePolicyError::PackageNotFound,
+            e => UpdatePolicyError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(UpdatePolicyError::ConfusableName)?;
+
+    if package.redirect.is_some() {
+        return Err(UpdatePolicyError::PackageNotFound);
+    }
+
+    if package.policy == policy {
+        return Ok(());
+    }
+
+    package.policy = policy;
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => UpdatePolicyError::Conflict,
+        e => UpdatePolicyError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::PublishPolicy,
+        publishing::{update_publish_policy, UpdatePolicyError},
+        InMemoryPackageRepository, Package, Repository, RepositoryError,
+    };
+
+    #[tokio::test]
+    async fn can_update_policy() {
+        let package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+
+        let policy = PublishPolicy {
+            monotonic_within_major: true,
+            allowed_uri_authorities: vec!["ipfs".into()],
+            ..Default::default()
+        };
+
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package).await.unwrap();
+
+        let result = update_publish_policy(
+            &package.user,
+            &package.name,
+            policy.clone(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.policy, policy);
+    }
+
+    #[tokio::test]
+    async fn fails_to_update_policy_of_non_existent_package() {
+        let package_repo = InMemoryPackageRepository::default();
+
+        let result = update_publish_policy(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            Default::default(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(UpdatePolicyError::PackageNotFound));
+        assert!(matches!(
+            Repository::<Package>::read(&package_repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn forbids_updating_policy_as_differently_cased_user() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo
+            .update(&Package::new(
+                "package1".parse().unwrap(),
+                "user1".parse().unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let result = update_publish_policy(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            PublishPolicy {
+                monotonic_within_major: true,
+                ..Default::default()
+            },
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(UpdatePolicyError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}
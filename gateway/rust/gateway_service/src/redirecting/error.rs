@@ -0,0 +1,44 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum RedirectError {
+    TargetNotFound,
+    PackageHasVersions,
+    VersionConflict(String),
+    RedirectLoop,
+    TooManyRedirects,
+    RedirectNotFound,
+    ReservedName(String),
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for RedirectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedirectError::TargetNotFound => write!(f, "Target package not found"),
+            RedirectError::PackageHasVersions => {
+                write!(
+                    f,
+                    "Package has versions, it can only redirect to a package of the same user"
+                )
+            }
+            RedirectError::VersionConflict(version) => write!(
+                f,
+                "Version `{}` of the package conflicts with the target's",
+                version
+            ),
+            RedirectError::RedirectLoop => write!(f, "Target redirects back to the package"),
+            RedirectError::TooManyRedirects => write!(f, "Target is behind too many redirects"),
+            RedirectError::RedirectNotFound => write!(f, "Package does not redirect"),
+            RedirectError::ReservedName(name) => write!(f, "`{}` is a reserved name", name),
+            RedirectError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            RedirectError::Conflict => write!(f, "Package was changed concurrently"),
+            RedirectError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
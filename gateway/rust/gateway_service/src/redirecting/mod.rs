@@ -0,0 +1,8 @@
+pub mod error;
+pub use error::*;
+
+mod set_redirect;
+pub use set_redirect::set_redirect;
+
+mod remove_redirect;
+pub use remove_redirect::remove_redirect;
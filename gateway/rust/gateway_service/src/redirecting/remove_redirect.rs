@@ -0,0 +1,67 @@
+use crate::{
+    models::{Package, PackageName, Username},
+    read_package_by_name, Repository, RepositoryError,
+};
+
+use super::error::RedirectError;
+
+/// Stops the name from redirecting, so it can be published to again
+pub async fn remove_redirect(
+    user: &Username,
+    package_name: &PackageName,
+    package_repo: impl Repository<Package>,
+) -> Result<(), RedirectError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|e| match e {
+            RepositoryError::NotFound => RedirectError::RedirectNotFound,
+            e => RedirectError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(RedirectError::ConfusableName)?;
+
+    if package.redirect.take().is_none() {
+        return Err(RedirectError::RedirectNotFound);
+    }
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => RedirectError::Conflict,
+        e => RedirectError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        redirecting::{remove_redirect, RedirectError},
+        InMemoryPackageRepository, Package, Repository,
+    };
+
+    #[tokio::test]
+    async fn forbids_removing_redirect_as_differently_cased_user() {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.redirect = Some("user2/package1".into());
+
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package).await.unwrap();
+
+        let result = remove_redirect(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(RedirectError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}
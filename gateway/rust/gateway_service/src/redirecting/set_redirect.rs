@@ -0,0 +1,508 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    models::{Package, PackageMetadata, PackageName, PublishPolicy, ReservedNames, Username},
+    read_package_by_name,
+    resolving::MAX_REDIRECTS,
+    semver, Repository, RepositoryError,
+};
+
+use super::error::RedirectError;
+
+/// Makes `user/package_name` an alias of the target package, which can belong to any user.
+/// An existing redirect is replaced.
+/// Creating an alias claims the name, so a reserved name can only be used by allowlisted users.
+/// A package with versions can only redirect to another package of the same user, which renames
+/// it: its versions, unpublished versions and the dist-tags the target doesn't have move to the
+/// target, in the same transaction as the redirect is written.
+pub async fn set_redirect(
+    user: &Username,
+    package_name: &PackageName,
+    target_user: &Username,
+    target_name: &PackageName,
+    reserved_names: &ReservedNames,
+    package_repo: impl Repository<Package>,
+) -> Result<(), RedirectError> {
+    let id = Package::id_of(user, package_name);
+    let target_id = Package::id_of(target_user, target_name);
+
+    let target = check_target(&id, target_user, target_name, &package_repo).await?;
+
+    let mut package = match read_package_by_name(user, package_name, &package_repo).await {
+        Ok(package) => package,
+        Err(RepositoryError::NotFound) => {
+            reserved_names
+                .check(user, package_name)
+                .map_err(RedirectError::ReservedName)?;
+            Package::new(package_name.clone(), user.clone())
+        }
+        Err(e) => return Err(RedirectError::RepositoryError(e.to_string())),
+    };
+
+    package
+        .check_owner(user, package_name)
+        .map_err(RedirectError::ConfusableName)?;
+
+    if package.redirect.as_ref() == Some(&target_id) {
+        return Ok(());
+    }
+
+    let result = if package.versions.is_empty() {
+        package.redirect = Some(target_id);
+        package.pending_transfer = None;
+
+        package_repo.update(&package).await
+    } else {
+        if target.user.canonical() != package.user.canonical() {
+            return Err(RedirectError::PackageHasVersions);
+        }
+
+        let mut target = target;
+        move_versions(&package, &mut target)?;
+
+        let redirect = Package {
+            versions: vec![],
+            metadata: PackageMetadata::default(),
+            dist_tags: BTreeMap::new(),
+            policy: PublishPolicy::default(),
+            unpublished_versions: BTreeMap::new(),
+            pending_transfer: None,
+            redirect: Some(target_id),
+            ..package
+        };
+
+        package_repo.update_many(&[target, redirect]).await
+    };
+
+    result.map_err(|e| match e {
+        RepositoryError::Conflict => RedirectError::Conflict,
+        e => RedirectError::RepositoryError(e.to_string()),
+    })
+}
+
+/// Adds the versions of `package` to `target`. A version can only move if the target doesn't
+/// have it, or has it with the same URI, and packages published as `latest` can't be merged
+/// with packages that use semver versioning.
+fn move_versions(package: &Package, target: &mut Package) -> Result<(), RedirectError> {
+    let is_latest_only = |package: &Package| {
+        package
+            .versions
+            .iter()
+            .any(|version| version.name.semver().is_none())
+    };
+    if !target.versions.is_empty() && is_latest_only(package) != is_latest_only(target) {
+        return Err(RedirectError::VersionConflict("latest".to_string()));
+    }
+
+    for version in &package.versions {
+        let existing_uri = target
+            .versions
+            .iter()
+            .find(|existing| existing.name == version.name)
+            .map(|existing| &existing.uri)
+            .or_else(|| {
+                version
+                    .name
+                    .semver()
+                    .and_then(|name| target.unpublished_versions.get(name))
+            });
+
+        match existing_uri {
+            Some(uri) if *uri == version.uri => {}
+            Some(_) => return Err(RedirectError::VersionConflict(version.name.to_string())),
+            None => target
+                .versions
+                .push(version.clone().moved_from(&package.user, &package.name)),
+        }
+    }
+
+    for (name, uri) in &package.unpublished_versions {
+        let existing_uri = target
+            .versions
+            .iter()
+            .find(|existing| existing.name.semver() == Some(name))
+            .map(|existing| &existing.uri)
+            .or_else(|| target.unpublished_versions.get(name));
+
+        match existing_uri {
+            Some(existing_uri) if existing_uri == uri => {}
+            Some(_) => return Err(RedirectError::VersionConflict(name.to_string())),
+            None => {
+                target
+                    .unpublished_versions
+                    .insert(name.clone(), uri.clone());
+            }
+        }
+    }
+
+    for (tag, version) in &package.dist_tags {
+        target
+            .dist_tags
+            .entry(tag.clone())
+            .or_insert_with(|| version.clone());
+    }
+
+    semver::sort_versions(&mut target.versions);
+
+    Ok(())
+}
+
+/// The target has to lead to a package within the redirects that are followed when resolving,
+/// counting the new redirect, and never back to `id`. Returns the package it leads to.
+async fn check_target(
+    id: &str,
+    target_user: &Username,
+    target_name: &PackageName,
+    package_repo: &impl Repository<Package>,
+) -> Result<Package, RedirectError> {
+    if Package::id_of(target_user, target_name) == id {
+        return Err(RedirectError::RedirectLoop);
+    }
+
+    let to_error = |e| match e {
+        RepositoryError::NotFound => RedirectError::TargetNotFound,
+        e => RedirectError::RepositoryError(e.to_string()),
+    };
+
+    let mut package = read_package_by_name(target_user, target_name, package_repo)
+        .await
+        .map_err(to_error)?;
+
+    for _ in 1..MAX_REDIRECTS {
+        match &package.redirect {
+            Some(next) if next == id => return Err(RedirectError::RedirectLoop),
+            Some(next) => package = package_repo.read(next).await.map_err(to_error)?,
+            None => return Ok(package),
+        }
+    }
+
+    match package.redirect {
+        Some(_) => Err(RedirectError::TooManyRedirects),
+        None => Ok(package),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use crate::{
+        mocks::MockPackageRepository,
+        models::{ReservedNames, VersionSignature},
+        redirecting::{set_redirect, RedirectError},
+        Package, RepositoryError, Version,
+    };
+
+    fn package(user: &str, name: &str, redirect: Option<&str>) -> Package {
+        let mut package = Package::new(name.parse().unwrap(), user.parse().unwrap());
+        package.redirect = redirect.map(String::from);
+        if redirect.is_none() {
+            package.versions = vec![Version::new(
+                "1.0.0".parse().unwrap(),
+                "test/uri1".parse().unwrap(),
+            )];
+        }
+        package
+    }
+
+    #[tokio::test]
+    async fn creates_alias() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user2/package1".to_string()))
+            .return_once(|_| Ok(package("user2", "package1", None)));
+        package_repo
+            .expect_read()
+            .with(eq("user1/old-name".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+
+        package_repo
+            .expect_update()
+            .withf(|p| {
+                p.id == "user1/old-name"
+                    && p.redirect == Some("user2/package1".into())
+                    && p.versions.is_empty()
+                    && p.revision == 0
+            })
+            .times(1)
+            .return_once(|_| Ok(()));
+
+        let result = set_redirect(
+            &"user1".parse().unwrap(),
+            &"old-name".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    fn version(name: &str, uri: &str) -> Version {
+        Version::new(name.parse().unwrap(), uri.parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn renaming_moves_versions_to_the_target() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user1/new-name".to_string()))
+            .return_once(|_| {
+                let mut package = package("user1", "new-name", None);
+                package.versions.push(version("2.0.0", "test/uri2"));
+                Ok(package)
+            });
+        package_repo
+            .expect_read()
+            .with(eq("user1/old-name".to_string()))
+            .return_once(|_| {
+                let mut package = package("user1", "old-name", None);
+                let mut signed_version = version("1.1.0", "test/uri3");
+                signed_version.signature = Some(VersionSignature {
+                    key_id: "key1".into(),
+                    signature: "signature1".into(),
+                    origin: None,
+                });
+                package.versions.push(signed_version);
+                package.dist_tags = [("stable".parse().unwrap(), "1.1.0".parse().unwrap())].into();
+                package.revision = 4;
+                Ok(package)
+            });
+
+        package_repo.expect_update().never();
+        package_repo
+            .expect_update_many()
+            .withf(|packages| {
+                let [target, redirect] = packages else {
+                    return false;
+                };
+                let names = target
+                    .versions
+                    .iter()
+                    .map(|v| v.name.to_string())
+                    .collect::<Vec<_>>();
+                let origin = target.versions[1]
+                    .signature
+                    .as_ref()
+                    .and_then(|signature| signature.origin.as_ref());
+
+                target.id == "user1/new-name"
+                    && names == ["1.0.0", "1.1.0", "2.0.0"]
+                    && origin.map(|origin| origin.package.to_string()) == Some("old-name".into())
+                    && target.dist_tags.len() == 1
+                    && redirect.id == "user1/old-name"
+                    && redirect.redirect == Some("user1/new-name".into())
+                    && redirect.versions.is_empty()
+                    && redirect.dist_tags.is_empty()
+                    && redirect.revision == 4
+            })
+            .times(1)
+            .return_once(|_| Ok(()));
+
+        let result = set_redirect(
+            &"user1".parse().unwrap(),
+            &"old-name".parse().unwrap(),
+            &"user1".parse().unwrap(),
+            &"new-name".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn renaming_rejects_conflicting_versions() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user1/new-name".to_string()))
+            .return_once(|_| Ok(package("user1", "new-name", None)));
+        package_repo
+            .expect_read()
+            .with(eq("user1/old-name".to_string()))
+            .return_once(|_| {
+                let mut package = package("user1", "old-name", None);
+                package.versions = vec![version("1.0.0", "test/other-uri")];
+                Ok(package)
+            });
+        package_repo.expect_update().never();
+        package_repo.expect_update_many().never();
+
+        let result = set_redirect(
+            &"user1".parse().unwrap(),
+            &"old-name".parse().unwrap(),
+            &"user1".parse().unwrap(),
+            &"new-name".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(RedirectError::VersionConflict("1.0.0".into())));
+    }
+
+    #[tokio::test]
+    async fn package_with_versions_cannot_redirect_to_another_user() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user2/package1".to_string()))
+            .return_once(|_| Ok(package("user2", "package1", None)));
+        package_repo
+            .expect_read()
+            .with(eq("user1/old-name".to_string()))
+            .return_once(|_| Ok(package("user1", "old-name", None)));
+        package_repo.expect_update().never();
+        package_repo.expect_update_many().never();
+
+        let result = set_redirect(
+            &"user1".parse().unwrap(),
+            &"old-name".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(RedirectError::PackageHasVersions));
+    }
+
+    #[tokio::test]
+    async fn rejects_redirect_loops() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user1/package-b".to_string()))
+            .return_once(|_| Ok(package("user1", "package-b", Some("user1/package-a"))));
+        package_repo.expect_update().never();
+
+        let result = set_redirect(
+            &"user1".parse().unwrap(),
+            &"package-a".parse().unwrap(),
+            &"user1".parse().unwrap(),
+            &"package-b".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(RedirectError::RedirectLoop));
+    }
+
+    #[tokio::test]
+    async fn rejects_long_redirect_chains() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .returning(|id| Ok(package("user1", "package-b", Some(&format!("{}x", id)))));
+        package_repo.expect_update().never();
+
+        let result = set_redirect(
+            &"user1".parse().unwrap(),
+            &"package-a".parse().unwrap(),
+            &"user1".parse().unwrap(),
+            &"package-b".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(RedirectError::TooManyRedirects));
+    }
+
+    #[tokio::test]
+    async fn target_must_exist() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user2/package1".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+        package_repo.expect_update().never();
+
+        let result = set_redirect(
+            &"user1".parse().unwrap(),
+            &"old-name".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(RedirectError::TargetNotFound));
+    }
+
+    #[tokio::test]
+    async fn reserved_names_can_not_be_claimed_by_alias() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user2/package1".to_string()))
+            .return_once(|_| Ok(package("user2", "package1", None)));
+        for id in ["user1/admin", "user1/Admin"] {
+            package_repo
+                .expect_read()
+                .with(eq(id.to_string()))
+                .return_once(|_| Err(RepositoryError::NotFound));
+        }
+        package_repo.expect_update().never();
+
+        let result = set_redirect(
+            &"user1".parse().unwrap(),
+            &"Admin".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(RedirectError::ReservedName("Admin".into())));
+    }
+
+    #[tokio::test]
+    async fn forbids_redirecting_as_differently_cased_user() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user2/package1".to_string()))
+            .return_once(|_| Ok(package("user2", "package1", None)));
+        package_repo
+            .expect_read()
+            .with(eq("user1/old-name".to_string()))
+            .return_once(|_| Ok(package("user1", "old-name", None)));
+        package_repo.expect_update().never();
+        package_repo.expect_update_many().never();
+
+        let result = set_redirect(
+            &"User1".parse().unwrap(),
+            &"old-name".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &ReservedNames::default(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(RedirectError::ConfusableName("user1/old-name".into()))
+        );
+    }
+}
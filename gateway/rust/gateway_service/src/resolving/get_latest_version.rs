@@ -1,37 +1,100 @@
 use std::fmt::Display;
 
 use crate::{
-    models::{Package, PackageName, Username, Version},
-    semver, Repository, RepositoryError,
+    models::{Package, PackageName, Username, Version, VersionName, VersionSelector},
+    read_all_versions, read_version_of, semver, Repository, RepositoryError, VersionRepository,
 };
 
+use super::{read_package_header, Resolved};
+
 pub async fn get_latest_version(
     user: &Username,
     package_name: &PackageName,
-    version_name: Option<&str>,
-    package_repo: &impl Repository<Package>,
-) -> Result<Version, ResolveError> {
-    let id = format!("{}/{}", user, package_name);
+    version: &VersionSelector,
+    include_prerelease: bool,
+    package_repo: &(impl Repository<Package> + VersionRepository),
+) -> Result<Resolved<Version>, ResolveError> {
+    let package = read_package_header(user, package_name, package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => ResolveError::PackageNotFound,
+            e => ResolveError::RepositoryError(e.to_string()),
+        })?;
 
-    let package = package_repo.read(&id).await.map_err(|error| match error {
-        RepositoryError::NotFound => ResolveError::PackageNotFound,
-        RepositoryError::Unknown(e) => ResolveError::RepositoryError(e),
-    })?;
+    let canonical_id = package.id.clone();
 
-    Ok(if let Some(version) = version_name {
-        let latest_version =
-            semver::get_latest(version, &package.versions).ok_or(ResolveError::VersionNotFound)?;
+    // Exact versions and dist-tags read a single version, ranges need all of them
+    let version = match version {
+        VersionSelector::Exact(version) => {
+            read_version(&package, &version.clone().into(), package_repo).await?
+        }
+        VersionSelector::Tag(tag) => {
+            let tagged_version = package
+                .dist_tags
+                .get(tag)
+                .ok_or(ResolveError::VersionNotFound)?;
 
-        latest_version.clone()
-    } else {
-        let mut versions = package.versions;
-        semver::sort_versions(&mut versions);
-        let latest_version = versions.last().ok_or(ResolveError::VersionNotFound)?;
+            read_version(&package, &tagged_version.clone().into(), package_repo).await?
+        }
+        VersionSelector::Latest => get_latest_stable_version(
+            unyanked(read_versions(&package, package_repo).await?),
+            include_prerelease,
+        )?,
+        VersionSelector::Partial(range) | VersionSelector::Range(range) => {
+            let versions = unyanked(read_versions(&package, package_repo).await?);
 
-        latest_version.clone()
+            semver::get_latest(range, &versions, include_prerelease)
+                .ok_or(ResolveError::VersionNotFound)?
+                .clone()
+        }
+    };
+
+    Ok(Resolved {
+        canonical_id,
+        value: version,
     })
 }
 
+async fn read_version(
+    package: &Package,
+    name: &VersionName,
+    version_repo: &impl VersionRepository,
+) -> Result<Version, ResolveError> {
+    read_version_of(package, name, version_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => ResolveError::VersionNotFound,
+            e => ResolveError::RepositoryError(e.to_string()),
+        })
+}
+
+async fn read_versions(
+    package: &Package,
+    version_repo: &impl VersionRepository,
+) -> Result<Vec<Version>, ResolveError> {
+    read_all_versions(package, version_repo)
+        .await
+        .map_err(|e| ResolveError::RepositoryError(e.to_string()))
+}
+
+/// Yanked versions are skipped by everything except exact versions and dist-tags
+fn unyanked(mut versions: Vec<Version>) -> Vec<Version> {
+    versions.retain(|v| !v.is_yanked());
+    versions
+}
+
+fn get_latest_stable_version(
+    mut versions: Vec<Version>,
+    include_prerelease: bool,
+) -> Result<Version, ResolveError> {
+    if !include_prerelease {
+        versions.retain(|v| !matches!(v.name.semver(), Some(semver) if semver.is_prerelease()));
+    }
+    semver::sort_versions(&mut versions);
+
+    versions.pop().ok_or(ResolveError::VersionNotFound)
+}
+
 #[derive(Debug, thiserror::Error, PartialEq, Clone)]
 pub enum ResolveError {
     PackageNotFound,
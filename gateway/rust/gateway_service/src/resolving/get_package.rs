@@ -5,17 +5,19 @@ use crate::{
     Repository, RepositoryError,
 };
 
+use super::read_package;
+
 pub async fn get_package(
     user: &Username,
     package_name: &PackageName,
     package_repo: &impl Repository<Package>,
 ) -> Result<Package, GetPackageError> {
-    let id = format!("{}/{}", user, package_name);
-
-    let package = package_repo.read(&id).await.map_err(|error| match error {
-        RepositoryError::NotFound => GetPackageError::PackageNotFound,
-        RepositoryError::Unknown(e) => GetPackageError::RepositoryError(e),
-    })?;
+    let package = read_package(user, package_name, package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => GetPackageError::PackageNotFound,
+            e => GetPackageError::RepositoryError(e.to_string()),
+        })?;
 
     Ok(package)
 }
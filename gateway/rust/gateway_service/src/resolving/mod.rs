@@ -6,3 +6,12 @@ pub use resolve_package::*;
 
 mod get_package;
 pub use get_package::*;
+
+mod resolved;
+pub use resolved::Resolved;
+
+mod read_package;
+pub use read_package::{read_package, read_package_header};
+
+/// How many redirects are followed when reading a package
+pub const MAX_REDIRECTS: usize = 5;
@@ -0,0 +1,106 @@
+use crate::{
+    models::{Package, PackageName, Username},
+    read_header_by_name, read_package_by_name, Repository, RepositoryError, VersionRepository,
+};
+
+use super::MAX_REDIRECTS;
+
+/// Reads a package, following the redirects left behind by transfers.
+/// A chain longer than `MAX_REDIRECTS` is treated as a missing package.
+pub async fn read_package(
+    user: &Username,
+    package_name: &PackageName,
+    package_repo: &impl Repository<Package>,
+) -> Result<Package, RepositoryError> {
+    let mut package = read_package_by_name(user, package_name, package_repo).await?;
+
+    for _ in 0..MAX_REDIRECTS {
+        match &package.redirect {
+            Some(target) => package = package_repo.read(target).await?,
+            None => return Ok(package),
+        }
+    }
+
+    match package.redirect {
+        Some(_) => Err(RepositoryError::NotFound),
+        None => Ok(package),
+    }
+}
+
+/// Reads a package without its versions, following redirects like `read_package`
+pub async fn read_package_header(
+    user: &Username,
+    package_name: &PackageName,
+    package_repo: &(impl Repository<Package> + VersionRepository),
+) -> Result<Package, RepositoryError> {
+    let mut package = read_header_by_name(user, package_name, package_repo).await?;
+
+    for _ in 0..MAX_REDIRECTS {
+        match &package.redirect {
+            Some(target) => package = package_repo.read_header(target).await?,
+            None => return Ok(package),
+        }
+    }
+
+    match package.redirect {
+        Some(_) => Err(RepositoryError::NotFound),
+        None => Ok(package),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        resolving::read_package, InMemoryPackageRepository, Package, Repository, RepositoryError,
+    };
+
+    fn redirect(user: &str, target: &str) -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), user.parse().unwrap());
+        package.redirect = Some(target.into());
+        package
+    }
+
+    #[tokio::test]
+    async fn follows_redirects() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo
+            .update_many(&[
+                redirect("user1", "user2/package1"),
+                redirect("user2", "user3/package1"),
+                Package::new("package1".parse().unwrap(), "user3".parse().unwrap()),
+            ])
+            .await
+            .unwrap();
+
+        let package = read_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &package_repo,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(package.id, "user3/package1");
+    }
+
+    #[tokio::test]
+    async fn stops_at_redirect_loops() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo
+            .update_many(&[
+                redirect("user1", "user2/package1"),
+                redirect("user2", "user1/package1"),
+            ])
+            .await
+            .unwrap();
+
+        let result = read_package(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &package_repo,
+        )
+        .await;
+
+        assert!(matches!(result, Err(RepositoryError::NotFound)));
+    }
+}
@@ -1,195 +1,232 @@
 use crate::{
-    models::{Package, PackageName, Username, WrapUri},
-    Repository,
+    models::{Package, PackageName, Username, VersionSelector, WrapUri},
+    Repository, VersionRepository,
 };
 
-use super::ResolveError;
+use super::{ResolveError, Resolved};
 
 pub async fn resolve_package(
     user: &Username,
     package_name: &PackageName,
-    version_name: Option<&str>,
-    package_repo: &impl Repository<Package>,
-) -> Result<WrapUri, ResolveError> {
-    let latest_version =
-        super::get_latest_version(user, package_name, version_name, package_repo).await?;
-
-    Ok(latest_version.uri)
+    version: &VersionSelector,
+    include_prerelease: bool,
+    package_repo: &(impl Repository<Package> + VersionRepository),
+) -> Result<Resolved<WrapUri>, ResolveError> {
+    let latest_version = super::get_latest_version(
+        user,
+        package_name,
+        version,
+        include_prerelease,
+        package_repo,
+    )
+    .await?;
+
+    Ok(latest_version.map(|version| version.uri))
 }
 
 #[cfg(test)]
 mod tests {
-    use async_trait::async_trait;
-    use mockall::{mock, predicate::eq};
+    use mockall::predicate::eq;
     use resolve_package::ResolveError;
 
     use crate::{
-        models::{Package, PackageName, Username, Version},
-        resolving::resolve_package,
-        Repository, RepositoryError,
+        mocks::MockPackageRepository,
+        models::{Package, PackageName, Username, Version, VersionSelector, Yank},
+        resolving::{resolve_package, Resolved},
+        InMemoryPackageRepository, Repository, RepositoryError,
     };
 
-    mock! {
-      PackageRepository {}
-        #[async_trait]
-        impl Repository<Package> for PackageRepository {
-            async fn read(&self, key: &str) -> Result<Package, RepositoryError>;
-            async fn update(&self, entity: &Package) -> Result<(), RepositoryError>;
-        }
+    /// A stored `user1/package1` with `versions`, published as `test/uri1`, `test/uri2`, ...
+    async fn repo_with_versions(versions: &[&str]) -> InMemoryPackageRepository {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = versions
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                Version::new(
+                    name.parse().unwrap(),
+                    format!("test/uri{}", i + 1).parse().unwrap(),
+                )
+            })
+            .collect();
+
+        let repo = InMemoryPackageRepository::default();
+        repo.update(&package).await.unwrap();
+        repo
+    }
+
+    fn user_and_package() -> (Username, PackageName) {
+        ("user1".parse().unwrap(), "package1".parse().unwrap())
     }
 
     #[tokio::test]
     async fn can_resolve_package() {
-        let mut mock_repo = MockPackageRepository::new();
+        let repo = repo_with_versions(&["1.0.0", "2.0.0"]).await;
+        let (user, package_name) = user_and_package();
 
-        let user: Username = "user1".parse().unwrap();
-        let package_name: PackageName = "package1".parse().unwrap();
-        let id = format!("{}/{}", user, package_name);
-
-        let expected_package = Package {
-            id: id.clone(),
-            user: user.clone(),
-            name: package_name.clone(),
-            versions: vec![
-                Version {
-                    name: "1.0.0".to_string(),
-                    uri: "test/uri1".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "2.0.0".to_string(),
-                    uri: "test/uri2".parse().unwrap(),
-                    created_on: 0,
-                },
-            ],
-            created_on: 0,
-        };
+        let result =
+            resolve_package(&user, &package_name, &VersionSelector::Latest, false, &repo).await;
 
-        mock_repo
-            .expect_read()
-            .with(eq(id.clone()))
-            .times(1)
-            .returning(move |_| Ok(expected_package.clone()));
-
-        let result = resolve_package(&user, &package_name, None, &mock_repo).await;
-
-        assert_eq!(result, Ok("test/uri2".parse().unwrap()));
+        assert_eq!(result.map(|r| r.value), Ok("test/uri2".parse().unwrap()));
     }
 
     #[tokio::test]
     async fn resolves_package_with_specified_version() {
-        let mut mock_repo = MockPackageRepository::new();
-
-        let user: Username = "user1".parse().unwrap();
-        let package_name: PackageName = "package1".parse().unwrap();
-        let id = format!("{}/{}", user, package_name);
-
-        let expected_package = Package {
-            id: id.clone(),
-            user: user.clone(),
-            name: package_name.clone(),
-            versions: vec![
-                Version {
-                    name: "1.0.0".to_string(),
-                    uri: "test/uri1".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "2.0.0".to_string(),
-                    uri: "test/uri2".parse().unwrap(),
-                    created_on: 0,
-                },
-            ],
-            created_on: 0,
-        };
+        let repo = repo_with_versions(&["1.0.0", "2.0.0"]).await;
+        let (user, package_name) = user_and_package();
+
+        let result = resolve_package(
+            &user,
+            &package_name,
+            &"2.0.0".parse().unwrap(),
+            false,
+            &repo,
+        )
+        .await;
+
+        assert_eq!(result.map(|r| r.value), Ok("test/uri2".parse().unwrap()));
+    }
 
-        mock_repo
-            .expect_read()
-            .with(eq(id.clone()))
-            .times(1)
-            .returning(move |_| Ok(expected_package.clone()));
+    #[tokio::test]
+    async fn resolves_latest_stable_version_by_default() {
+        let repo = repo_with_versions(&["1.0.0", "2.0.0-rc.1"]).await;
+        let (user, package_name) = user_and_package();
+
+        let result =
+            resolve_package(&user, &package_name, &VersionSelector::Latest, false, &repo).await;
+        assert_eq!(result.map(|r| r.value), Ok("test/uri1".parse().unwrap()));
+
+        let result = resolve_package(
+            &user,
+            &package_name,
+            &"2.0.0-rc.1".parse().unwrap(),
+            false,
+            &repo,
+        )
+        .await;
+        assert_eq!(result.map(|r| r.value), Ok("test/uri2".parse().unwrap()));
+
+        let result =
+            resolve_package(&user, &package_name, &VersionSelector::Latest, true, &repo).await;
+        assert_eq!(result.map(|r| r.value), Ok("test/uri2".parse().unwrap()));
+    }
 
-        let result = resolve_package(&user, &package_name, Some("2.0.0"), &mock_repo).await;
+    #[tokio::test]
+    async fn skips_yanked_versions_unless_exact() {
+        let repo = repo_with_versions(&["1.0.0", "1.1.0"]).await;
+        let (user, package_name) = user_and_package();
+
+        let mut package: Package = repo.read("user1/package1").await.unwrap();
+        package.versions[1].yanked = Some(Yank {
+            reason: "Broken build".into(),
+            yanked_on: 0,
+        });
+        repo.update(&package).await.unwrap();
+
+        for version in [
+            VersionSelector::Latest,
+            "1".parse().unwrap(),
+            "^1.0.0".parse().unwrap(),
+        ] {
+            let result = resolve_package(&user, &package_name, &version, false, &repo).await;
+            assert_eq!(result.map(|r| r.value), Ok("test/uri1".parse().unwrap()));
+        }
 
-        assert_eq!(result, Ok("test/uri2".parse().unwrap()));
+        let result = resolve_package(
+            &user,
+            &package_name,
+            &"1.1.0".parse().unwrap(),
+            false,
+            &repo,
+        )
+        .await;
+        assert_eq!(result.map(|r| r.value), Ok("test/uri2".parse().unwrap()));
     }
 
     #[tokio::test]
     async fn returns_version_not_found_error_when_resolving_package_with_non_existent_version() {
-        let mut mock_repo = MockPackageRepository::new();
-
-        let user: Username = "user1".parse().unwrap();
-        let package_name: PackageName = "package1".parse().unwrap();
-        let id = format!("{}/{}", user, package_name);
-
-        let expected_package = Package {
-            id: id.clone(),
-            user: user.clone(),
-            name: package_name.clone(),
-            versions: vec![
-                Version {
-                    name: "1.0.0".to_string(),
-                    uri: "test/uri1".parse().unwrap(),
-                    created_on: 0,
-                },
-                Version {
-                    name: "2.0.0".to_string(),
-                    uri: "test/uri2".parse().unwrap(),
-                    created_on: 0,
-                },
-            ],
-            created_on: 0,
-        };
-
-        mock_repo
-            .expect_read()
-            .with(eq(id.clone()))
-            .times(1)
-            .returning(move |_| Ok(expected_package.clone()));
-
-        let result = resolve_package(&user, &package_name, Some("3.0.0"), &mock_repo).await;
-
-        assert_eq!(result, Err(ResolveError::VersionNotFound));
+        let repo = repo_with_versions(&["1.0.0", "2.0.0"]).await;
+        let (user, package_name) = user_and_package();
+
+        let result = resolve_package(
+            &user,
+            &package_name,
+            &"3.0.0".parse().unwrap(),
+            false,
+            &repo,
+        )
+        .await;
+
+        assert_eq!(result.map(|r| r.value), Err(ResolveError::VersionNotFound));
     }
 
     #[tokio::test]
     async fn returns_package_not_found_when_resolving_non_existent_package() {
-        let mut mock_repo = MockPackageRepository::new();
-
-        let user: Username = "user1".parse().unwrap();
-        let package_name: PackageName = "package1".parse().unwrap();
-        let id = format!("{}/{}", user, package_name);
-
-        mock_repo
-            .expect_read()
-            .with(eq(id.clone()))
-            .times(1)
-            .returning(move |_| Err(RepositoryError::NotFound));
+        let repo = InMemoryPackageRepository::default();
+        let (user, package_name) = user_and_package();
 
-        let result = resolve_package(&user, &package_name, None, &mock_repo).await;
+        let result =
+            resolve_package(&user, &package_name, &VersionSelector::Latest, false, &repo).await;
 
-        assert_eq!(result, Err(ResolveError::PackageNotFound));
+        assert_eq!(result.map(|r| r.value), Err(ResolveError::PackageNotFound));
     }
 
     #[tokio::test]
     async fn returns_repository_error_when_resolving_package_with_repository_error() {
         let mut mock_repo = MockPackageRepository::new();
-
-        let user: Username = "user1".parse().unwrap();
-        let package_name: PackageName = "package1".parse().unwrap();
-        let id = format!("{}/{}", user, package_name);
+        let (user, package_name) = user_and_package();
 
         mock_repo
-            .expect_read()
-            .with(eq(id.clone()))
+            .expect_read_header()
+            .with(eq("user1/package1".to_string()))
             .times(1)
             .returning(move |_| Err(RepositoryError::Unknown("Some error".to_string())));
 
-        let result = resolve_package(&user, &package_name, None, &mock_repo).await;
+        let result = resolve_package(
+            &user,
+            &package_name,
+            &VersionSelector::Latest,
+            false,
+            &mock_repo,
+        )
+        .await;
+
+        assert_eq!(
+            result.map(|r| r.value),
+            Err(ResolveError::RepositoryError(
+                "Unknown error: Some error".to_string()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn follows_redirect_to_canonical_id() {
+        let repo = InMemoryPackageRepository::default();
+
+        let mut redirect = Package::new("old-package".parse().unwrap(), "user1".parse().unwrap());
+        redirect.redirect = Some("user2/package1".into());
+        let mut target = Package::new("package1".parse().unwrap(), "user2".parse().unwrap());
+        target.versions = vec![Version::new(
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+        )];
+        repo.update_many(&[redirect, target]).await.unwrap();
+
+        let result = resolve_package(
+            &"user1".parse().unwrap(),
+            &"old-package".parse().unwrap(),
+            &VersionSelector::Latest,
+            false,
+            &repo,
+        )
+        .await;
 
         assert_eq!(
             result,
-            Err(ResolveError::RepositoryError("Some error".to_string()))
+            Ok(Resolved {
+                canonical_id: "user2/package1".into(),
+                value: "test/uri1".parse().unwrap(),
+            })
         );
     }
 }
@@ -0,0 +1,16 @@
+/// A value found by resolving a package, with the id of the package after following its redirects.
+/// Clients can replace the id they resolved with the canonical one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved<T> {
+    pub canonical_id: String,
+    pub value: T,
+}
+
+impl<T> Resolved<T> {
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Resolved<U> {
+        Resolved {
+            canonical_id: self.canonical_id,
+            value: f(self.value),
+        }
+    }
+}
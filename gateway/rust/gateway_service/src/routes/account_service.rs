@@ -0,0 +1,56 @@
+use crate::{
+    accounts::{AccountService, OrgAccountService},
+    models::{OrgRole, Organization},
+    Repository,
+};
+
+/// The key of the wrap account, and the keys of every other user if `ACCOUNT_SERVICE_URL` is set
+#[cfg(not(feature = "local"))]
+pub async fn get_wrap_account_service() -> impl AccountService {
+    use crate::{
+        accounts::{FallbackAccountService, SingleAccountService},
+        constants::{self, POLYWRAP_USERNAME},
+    };
+
+    FallbackAccountService::new(
+        SingleAccountService::new(
+            POLYWRAP_USERNAME.parse().unwrap(),
+            std::env::var(constants::ENV_WRAP_USER_KEY).expect("ENV_WRAP_USER_KEY not set"),
+        ),
+        get_remote_account_service(),
+    )
+}
+
+#[cfg(feature = "local")]
+pub async fn get_wrap_account_service() -> impl AccountService {
+    use crate::AllowAllAccountService;
+
+    AllowAllAccountService {}
+}
+
+/// The wrap account service, which for organizations also accepts the keys of members with at least `role`
+pub async fn get_org_account_service<T>(org_repo: T, role: OrgRole) -> impl AccountService
+where
+    T: Repository<Organization> + Send + Sync,
+{
+    OrgAccountService::new(get_wrap_account_service().await, org_repo, role)
+}
+
+#[cfg(not(feature = "local"))]
+fn get_remote_account_service() -> Option<crate::accounts::RemoteAccountService> {
+    std::env::var(crate::constants::ENV_ACCOUNT_SERVICE_URL)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map(crate::accounts::RemoteAccountService::new)
+}
+
+/// Whether users other than the wrap account can authenticate, which organizations need
+#[cfg(not(feature = "local"))]
+pub fn has_user_accounts() -> bool {
+    get_remote_account_service().is_some()
+}
+
+#[cfg(feature = "local")]
+pub fn has_user_accounts() -> bool {
+    true
+}
@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use http::HeaderMap;
+
+use crate::{
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package, PublisherKey, PublisherKeys},
+    Repository,
+};
+
+use super::{get_org_account_service, Dependencies};
+
+pub async fn add_publisher_key<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path(user): Path<String>,
+    headers: HeaderMap,
+    Json(PublisherKeyBody { public_key }): Json<PublisherKeyBody>,
+) -> Result<Json<PublisherKey>, ApiError>
+where
+    T: Repository<Package> + Repository<PublisherKeys> + Repository<Organization> + Send + Sync,
+{
+    let Dependencies {
+        key_repo, org_repo, ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    let key =
+        functions::add_publisher_key(user, public_key, api_key, key_repo, account_service).await?;
+
+    Ok(Json(key))
+}
+
+#[derive(serde::Deserialize)]
+pub struct PublisherKeyBody {
+    pub public_key: String,
+}
@@ -0,0 +1,88 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use http::HeaderMap;
+
+use crate::{
+    debugging::log_error,
+    functions::{self, WebhookInfo},
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package, WebhookSubscriptions},
+    Repository,
+};
+
+use super::{get_org_account_service, Dependencies};
+
+pub async fn add_webhook<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(body): Json<WebhookBody>,
+) -> Result<Json<WebhookInfo>, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    add(deps, Some((user, package)), headers, body).await
+}
+
+pub async fn add_registry_webhook<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    headers: HeaderMap,
+    Json(body): Json<WebhookBody>,
+) -> Result<Json<WebhookInfo>, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    add(deps, None, headers, body).await
+}
+
+async fn add<T, A>(
+    deps: Dependencies<T, A>,
+    user_and_package: Option<(String, String)>,
+    headers: HeaderMap,
+    WebhookBody { url, secret }: WebhookBody,
+) -> Result<Json<WebhookInfo>, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    let Dependencies {
+        webhook_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Maintainer).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    let webhook = functions::add_webhook(
+        user_and_package,
+        url,
+        secret,
+        api_key,
+        webhook_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(Json(webhook))
+}
+
+#[derive(serde::Deserialize)]
+pub struct WebhookBody {
+    pub url: String,
+    pub secret: String,
+}
@@ -0,0 +1,32 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+use crate::{
+    functions, http_utils::ApiError, models::Package, AuditPage, AuditRepository, Repository,
+};
+
+use super::Dependencies;
+
+pub async fn audit_log<T, A>(
+    Path((user, package)): Path<(String, String)>,
+    Query(AuditQuery { after, limit }): Query<AuditQuery>,
+    State(deps): State<Dependencies<T, A>>,
+) -> Result<Json<AuditPage>, ApiError>
+where
+    T: Repository<Package>,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies { audit_repo, .. } = deps;
+
+    let page = functions::audit_log(user, package, after, limit, audit_repo).await?;
+
+    Ok(Json(page))
+}
+
+#[derive(serde::Deserialize)]
+pub struct AuditQuery {
+    pub after: Option<String>,
+    pub limit: Option<usize>,
+}
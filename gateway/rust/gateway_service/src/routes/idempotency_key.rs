@@ -0,0 +1,34 @@
+use axum::{async_trait, extract::FromRequestParts};
+use http::request::Parts;
+
+use crate::{constants, http_utils::ApiError};
+
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// The optional `Idempotency-Key` header, retries with the same key get the response of the first request.
+pub struct IdempotencyKey(pub Option<String>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for IdempotencyKey {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(constants::IDEMPOTENCY_KEY_HEADER) else {
+            return Ok(IdempotencyKey(None));
+        };
+
+        match value.to_str() {
+            Ok(key)
+                if !key.is_empty()
+                    && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH
+                    && key.chars().all(|c| c.is_ascii_graphic()) =>
+            {
+                Ok(IdempotencyKey(Some(key.to_string())))
+            }
+            _ => Err(ApiError::bad_request(format!(
+                "Idempotency key must be between 1 and {} visible ASCII characters",
+                MAX_IDEMPOTENCY_KEY_LENGTH
+            ))),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use lazy_static::lazy_static;
+
+use crate::models::KeyIdSecret;
+
+pub fn get_key_id_secret() -> &'static KeyIdSecret {
+    &KEY_ID_SECRET
+}
+
+#[cfg(not(feature = "local"))]
+lazy_static! {
+    static ref KEY_ID_SECRET: KeyIdSecret = KeyIdSecret::new(
+        &std::env::var(crate::constants::ENV_KEY_ID_SECRET).expect("ENV_KEY_ID_SECRET not set")
+    );
+}
+
+#[cfg(feature = "local")]
+lazy_static! {
+    static ref KEY_ID_SECRET: KeyIdSecret = KeyIdSecret::new(
+        &std::env::var(crate::constants::ENV_KEY_ID_SECRET)
+            .unwrap_or_else(|_| crate::constants::KEY_ID_SECRET_LOCAL.to_string())
+    );
+}
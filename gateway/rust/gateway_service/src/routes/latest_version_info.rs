@@ -1,20 +1,31 @@
-use axum::extract::{Path, State};
-use http::StatusCode;
+use axum::extract::{Path, Query, State};
 
-use crate::{functions, models::Package, Repository};
+use crate::{
+    constants, functions, http_utils::ApiError, models::Package, Repository, VersionRepository,
+};
 
-use super::Dependencies;
+use super::{Dependencies, ResolveQuery};
 
-pub async fn latest_version_info<T>(
+pub async fn latest_version_info<T, A>(
     Path((user, package_and_version)): Path<(String, String)>,
-    State(deps): State<Dependencies<T>>,
-) -> Result<String, StatusCode>
+    Query(ResolveQuery { include_prerelease }): Query<ResolveQuery>,
+    State(deps): State<Dependencies<T, A>>,
+) -> Result<([(&'static str, String); 1], String), ApiError>
 where
-    T: Repository<Package>,
+    T: Repository<Package> + VersionRepository,
 {
-    let Dependencies { package_repo } = deps;
+    let Dependencies { package_repo, .. } = deps;
 
-    let info = functions::latest_version_info(user, package_and_version, &package_repo).await?;
+    let info = functions::latest_version_info(
+        user,
+        package_and_version,
+        include_prerelease,
+        &package_repo,
+    )
+    .await?;
 
-    Ok(info)
+    Ok((
+        [(constants::CANONICAL_ID_HEADER, info.canonical_id)],
+        info.value,
+    ))
 }
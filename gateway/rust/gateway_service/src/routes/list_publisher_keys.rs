@@ -0,0 +1,27 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::{
+    functions,
+    http_utils::ApiError,
+    models::{Package, PublisherKey, PublisherKeys},
+    Repository,
+};
+
+use super::Dependencies;
+
+pub async fn list_publisher_keys<T, A>(
+    Path(user): Path<String>,
+    State(deps): State<Dependencies<T, A>>,
+) -> Result<Json<Vec<PublisherKey>>, ApiError>
+where
+    T: Repository<Package> + Repository<PublisherKeys>,
+{
+    let Dependencies { key_repo, .. } = deps;
+
+    let keys = functions::list_publisher_keys(user, key_repo).await?;
+
+    Ok(Json(keys))
+}
@@ -0,0 +1,31 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+use crate::{
+    functions, http_utils::ApiError, models::Package, Repository, VersionPage, VersionRepository,
+};
+
+use super::Dependencies;
+
+pub async fn list_versions<T, A>(
+    Path((user, package)): Path<(String, String)>,
+    Query(VersionsQuery { after, limit }): Query<VersionsQuery>,
+    State(deps): State<Dependencies<T, A>>,
+) -> Result<Json<VersionPage>, ApiError>
+where
+    T: Repository<Package> + VersionRepository,
+{
+    let Dependencies { package_repo, .. } = deps;
+
+    let page = functions::list_versions(user, package, after, limit, &package_repo).await?;
+
+    Ok(Json(page))
+}
+
+#[derive(serde::Deserialize)]
+pub struct VersionsQuery {
+    pub after: Option<String>,
+    pub limit: Option<usize>,
+}
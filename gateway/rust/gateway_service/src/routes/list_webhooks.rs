@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use http::HeaderMap;
+
+use crate::{
+    debugging::log_error,
+    functions::{self, WebhookInfo},
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package, WebhookSubscriptions},
+    Repository,
+};
+
+use super::{get_org_account_service, Dependencies};
+
+pub async fn list_webhooks<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WebhookInfo>>, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    list(deps, Some((user, package)), headers).await
+}
+
+pub async fn list_registry_webhooks<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WebhookInfo>>, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    list(deps, None, headers).await
+}
+
+async fn list<T, A>(
+    deps: Dependencies<T, A>,
+    user_and_package: Option<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<WebhookInfo>>, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    let Dependencies {
+        webhook_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Maintainer).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    let webhooks =
+        functions::list_webhooks(user_and_package, api_key, webhook_repo, account_service).await?;
+
+    Ok(Json(webhooks))
+}
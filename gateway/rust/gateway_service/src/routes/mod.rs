@@ -13,12 +13,122 @@ pub use latest_version_info::*;
 mod package_info;
 pub use package_info::*;
 
+mod set_dist_tag;
+pub use set_dist_tag::*;
+
+mod remove_dist_tag;
+pub use remove_dist_tag::*;
+
+mod publish_batch;
+pub use publish_batch::*;
+
+mod update_metadata;
+pub use update_metadata::*;
+
+mod update_policy;
+pub use update_policy::*;
+
+mod promote_latest;
+pub use promote_latest::*;
+
+mod unpublish;
+pub use unpublish::*;
+
+mod yank;
+pub use yank::*;
+
+mod add_webhook;
+pub use add_webhook::*;
+
+mod remove_webhook;
+pub use remove_webhook::*;
+
+mod list_webhooks;
+pub use list_webhooks::*;
+
+mod audit_log;
+pub use audit_log::*;
+
+mod list_versions;
+pub use list_versions::*;
+
+mod add_publisher_key;
+pub use add_publisher_key::*;
+
+mod remove_publisher_key;
+pub use remove_publisher_key::*;
+
+mod list_publisher_keys;
+pub use list_publisher_keys::*;
+
+mod transfer;
+pub use transfer::*;
+
+mod organizations;
+pub use organizations::*;
+
+mod redirect;
+pub use redirect::*;
+
+mod account_service;
+use account_service::*;
+
+mod webhook_queue;
+use webhook_queue::*;
+
+mod uri_authorities;
+use uri_authorities::*;
+
+mod reserved_names;
+use reserved_names::*;
+
+mod wrap_fetcher;
+use wrap_fetcher::*;
+
+mod request_id;
+use request_id::*;
+
+mod key_id_secret;
+use key_id_secret::*;
+
+mod idempotency_key;
+use idempotency_key::*;
+
+#[cfg(any(test, feature = "local"))]
+use crate::InMemoryPackageRepository;
 use crate::{models::Package, Repository};
 
 #[derive(Clone)]
-pub struct Dependencies<T>
+pub struct Dependencies<T, A>
 where
     T: Repository<Package>,
 {
     pub package_repo: T,
+    /// The same store as `package_repo`, used for webhook subscriptions
+    pub webhook_repo: T,
+    /// The same store as `package_repo`, used for publisher keys
+    pub key_repo: T,
+    /// The same store as `package_repo`, used for idempotency records
+    pub idempotency_repo: T,
+    /// The same store as `package_repo`, used for organizations
+    pub org_repo: T,
+    /// Stores audit entries, apart from everything else
+    pub audit_repo: A,
+}
+
+#[cfg(any(test, feature = "local"))]
+impl Dependencies<InMemoryPackageRepository, InMemoryPackageRepository> {
+    /// Every store in memory, which is lost when the server stops
+    pub fn in_memory() -> Self {
+        let repo = InMemoryPackageRepository::default();
+
+        Self {
+            package_repo: repo.clone(),
+            webhook_repo: repo.clone(),
+            key_repo: repo.clone(),
+            idempotency_repo: repo.clone(),
+            org_repo: repo.clone(),
+            audit_repo: repo,
+        }
+    }
 }
@@ -0,0 +1,167 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use http::{HeaderMap, StatusCode};
+
+use crate::{
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package},
+    AuditRepository, Repository,
+};
+
+use super::{
+    get_key_id_secret, get_org_account_service, get_reserved_names, get_wrap_account_service,
+    has_user_accounts, Dependencies, RequestId,
+};
+
+/// Members could not authenticate with their own keys
+fn check_user_accounts() -> Result<(), ApiError> {
+    match has_user_accounts() {
+        true => Ok(()),
+        false => Err(ApiError::new(
+            StatusCode::NOT_IMPLEMENTED,
+            "Organizations need an account service for their members, set ACCOUNT_SERVICE_URL",
+        )),
+    }
+}
+
+pub async fn create_organization<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path(org): Path<String>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+    Json(CreateOrganizationBody { owner }): Json<CreateOrganizationBody>,
+) -> Result<Json<Organization>, ApiError>
+where
+    T: Repository<Package> + Repository<Organization>,
+    A: AuditRepository + Send + Sync,
+{
+    check_user_accounts()?;
+
+    let Dependencies {
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    // Only the key of the namespace itself, members don't exist yet
+    let account_service = get_wrap_account_service().await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    let organization = functions::create_organization(
+        org,
+        owner,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        get_reserved_names(),
+        org_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(Json(organization))
+}
+
+pub async fn get_organization<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path(org): Path<String>,
+) -> Result<Json<Organization>, ApiError>
+where
+    T: Repository<Package> + Repository<Organization>,
+{
+    let Dependencies { org_repo, .. } = deps;
+
+    let organization = functions::get_organization(org, org_repo).await?;
+
+    Ok(Json(organization))
+}
+
+pub async fn set_member_role<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((org, username)): Path<(String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+    Json(MemberBody { role }): Json<MemberBody>,
+) -> Result<Json<Organization>, ApiError>
+where
+    T: Repository<Package> + Repository<Organization> + Clone + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    check_user_accounts()?;
+
+    let Dependencies {
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo.clone(), OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    let organization = functions::set_member_role(
+        org,
+        username,
+        role,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        org_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(Json(organization))
+}
+
+pub async fn remove_member<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((org, username)): Path<(String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package> + Repository<Organization> + Clone + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo.clone(), OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::remove_member(
+        org,
+        username,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        org_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(serde::Deserialize)]
+pub struct CreateOrganizationBody {
+    pub owner: String,
+}
+
+#[derive(serde::Deserialize)]
+pub struct MemberBody {
+    pub role: OrgRole,
+}
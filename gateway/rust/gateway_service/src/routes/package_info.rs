@@ -1,20 +1,23 @@
 use axum::extract::{Path, State};
 use http::StatusCode;
 
-use crate::{functions, models::Package, Repository};
+use crate::{constants, functions, models::Package, Repository};
 
 use super::Dependencies;
 
-pub async fn package_info<T>(
+pub async fn package_info<T, A>(
     Path((user, package)): Path<(String, String)>,
-    State(deps): State<Dependencies<T>>,
-) -> Result<String, StatusCode>
+    State(deps): State<Dependencies<T, A>>,
+) -> Result<([(&'static str, String); 1], String), StatusCode>
 where
     T: Repository<Package>,
 {
-    let Dependencies { package_repo } = deps;
+    let Dependencies { package_repo, .. } = deps;
 
     let info = functions::package_info(user, package, &package_repo).await?;
 
-    Ok(info)
+    Ok((
+        [(constants::CANONICAL_ID_HEADER, info.canonical_id)],
+        info.value,
+    ))
 }
@@ -1,76 +1,187 @@
+use std::time::Duration;
+
 use axum::{
-    body::BoxBody,
-    extract::{Path, State},
-    response::Response,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
     Json,
 };
 use http::{HeaderMap, StatusCode};
+use lazy_static::lazy_static;
 
 use crate::{
-    accounts::AccountService, debugging::log_error, http_utils::{extract_api_key_from_headers, internal_server_error},
-    models::Package, Repository,
+    accounts::CachedAccountService,
+    constants,
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, ApiError},
+    idempotency::request_hash,
+    models::{
+        IdempotencyRecord, OrgRole, Organization, Package, PackageMetadata, PublisherKeys,
+        StoredResponse, VersionSignature, WebhookSubscriptions,
+    },
+    publishing::PublishOptions,
+    AuditRepository, Repository, VersionRepository,
 };
-use crate::{accounts::RemoteAccountService, constants, functions};
 
-use super::Dependencies;
+use super::{
+    get_allowed_uri_authorities, get_key_id_secret, get_org_account_service, get_reserved_names,
+    get_webhook_queue, get_wrap_fetcher, Dependencies, IdempotencyKey, RequestId,
+};
 
-pub async fn publish<T>(
-    State(deps): State<Dependencies<T>>,
+pub async fn publish<T, A>(
+    State(deps): State<Dependencies<T, A>>,
     Path((user, package_and_version)): Path<(String, String)>,
+    Query(query): Query<PublishQuery>,
     headers: HeaderMap,
-    Json(UriBody { uri }): Json<UriBody>,
-) -> Result<Response, StatusCode>
+    IdempotencyKey(idempotency_key): IdempotencyKey,
+    RequestId(request_id): RequestId,
+    Json(body): Json<UriBody>,
+) -> Result<Response, ApiError>
 where
-    T: Repository<Package>,
+    T: Repository<Package>
+        + Repository<PublisherKeys>
+        + Repository<WebhookSubscriptions>
+        + Repository<IdempotencyRecord>
+        + Repository<Organization>
+        + VersionRepository
+        + Send
+        + Sync,
+    A: AuditRepository + Send + Sync,
 {
-    let Dependencies { package_repo } = deps;
+    let Dependencies {
+        package_repo,
+        key_repo,
+        webhook_repo,
+        audit_repo,
+        idempotency_repo,
+        org_repo,
+        ..
+    } = deps;
 
-    let account_service = get_wrap_account_service().await;
+    // Idempotent requests check the key before claiming it, and again when publishing
+    let account_service =
+        CachedAccountService::new(get_org_account_service(org_repo, OrgRole::Publisher).await);
 
     let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
 
-    functions::publish(
-        user,
-        package_and_version,
+    // The API key is part of the request, so a response is only replayed to the key that made it.
+    // It's hashed as its key id, so stored hashes can't be matched to a key without the secret
+    let key_id = get_key_id_secret().key_id(&api_key);
+    let request_hash = request_hash(&(&user, &package_and_version, &query, &body, &key_id));
+
+    let PublishQuery { dry_run } = query;
+    let UriBody {
         uri,
-        api_key,
-        package_repo,
-        account_service,
-    )
-    .await?;
+        metadata,
+        allow_major,
+        signature,
+    } = body;
+
+    let publish = {
+        let user = user.clone();
+        let api_key = api_key.clone();
+        let account_service = &account_service;
+
+        async move {
+            let change = functions::publish(
+                user,
+                package_and_version,
+                uri,
+                PublishOptions {
+                    metadata,
+                    allow_major,
+                    signature,
+                    dry_run,
+                },
+                api_key,
+                request_id,
+                get_key_id_secret(),
+                get_allowed_uri_authorities(),
+                get_reserved_names(),
+                get_wrap_fetcher(),
+                package_repo,
+                key_repo,
+                webhook_repo,
+                audit_repo,
+                account_service,
+                get_webhook_queue().await,
+            )
+            .await?;
 
-    let response = Response::builder()
-        .status(StatusCode::OK)
-        .body(BoxBody::default())
-        .map_err(internal_server_error)?;
+            // Dry runs describe the change, since they are made to check a publish without doing it
+            match dry_run {
+                true => StoredResponse::json(StatusCode::OK, &change),
+                false => Ok(StoredResponse::empty(StatusCode::OK)),
+            }
+        }
+    };
 
-    Ok(response)
+    let response = match idempotency_key {
+        Some(idempotency_key) => {
+            functions::idempotent_request(
+                user,
+                idempotency_key,
+                request_hash,
+                get_idempotency_ttl(),
+                &api_key,
+                &account_service,
+                idempotency_repo,
+                publish,
+            )
+            .await?
+        }
+        None => publish.await?,
+    };
+
+    Ok(response.into_response())
 }
 
-#[derive(serde::Deserialize)]
-pub struct UriBody {
-    pub uri: String,
+fn get_idempotency_ttl() -> Duration {
+    *IDEMPOTENCY_TTL
 }
 
-#[cfg(not(feature = "local"))]
-async fn get_wrap_account_service() -> impl AccountService {
-    use crate::{accounts::SingleAccountService, constants::POLYWRAP_USERNAME};
+lazy_static! {
+    static ref IDEMPOTENCY_TTL: Duration = {
+        let hours = match std::env::var(constants::ENV_IDEMPOTENCY_TTL_HOURS) {
+            Ok(hours) => hours.parse().unwrap_or_else(|_| {
+                eprintln!(
+                    "{} must be a number of hours, using the default of {}",
+                    constants::ENV_IDEMPOTENCY_TTL_HOURS,
+                    constants::DEFAULT_IDEMPOTENCY_TTL_HOURS
+                );
+                constants::DEFAULT_IDEMPOTENCY_TTL_HOURS
+            }),
+            Err(_) => constants::DEFAULT_IDEMPOTENCY_TTL_HOURS,
+        };
 
-    SingleAccountService::new(
-        POLYWRAP_USERNAME.parse().unwrap(),
-        std::env::var(constants::ENV_WRAP_USER_KEY).expect("ENV_WRAP_USER_KEY not set"),
-    )
+        match hours.checked_mul(60 * 60) {
+            Some(secs) => Duration::from_secs(secs),
+            None => {
+                eprintln!(
+                    "{} is too large, using the default of {}",
+                    constants::ENV_IDEMPOTENCY_TTL_HOURS,
+                    constants::DEFAULT_IDEMPOTENCY_TTL_HOURS
+                );
+                Duration::from_secs(constants::DEFAULT_IDEMPOTENCY_TTL_HOURS * 60 * 60)
+            }
+        }
+    };
 }
 
-#[cfg(feature = "local")]
-async fn get_wrap_account_service() -> impl AccountService {
-    use crate::AllowAllAccountService;
-
-    AllowAllAccountService {}
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishQuery {
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
-async fn _get_remote_account_service() -> impl AccountService {
-    RemoteAccountService::new(
-        std::env::var(constants::ENV_ACCOUNT_SERVICE_URL).expect("ENV_ACCOUNT_SERVICE_URL not set"),
-    )
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct UriBody {
+    pub uri: String,
+    #[serde(default)]
+    pub metadata: Option<PackageMetadata>,
+    #[serde(default)]
+    pub allow_major: bool,
+    #[serde(default)]
+    pub signature: Option<VersionSignature>,
 }
@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use http::{HeaderMap, StatusCode};
+
+use crate::{
+    debugging::log_error,
+    functions::{self, BatchEntryBody},
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package, PublisherKeys, WebhookSubscriptions},
+    AuditRepository, Repository,
+};
+
+use super::{
+    get_allowed_uri_authorities, get_key_id_secret, get_org_account_service, get_reserved_names,
+    get_webhook_queue, get_wrap_fetcher, Dependencies, RequestId,
+};
+
+pub async fn publish_batch<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path(user): Path<String>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+    Json(BatchBody { entries }): Json<BatchBody>,
+) -> Result<Response, ApiError>
+where
+    T: Repository<Package>
+        + Repository<PublisherKeys>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        package_repo,
+        key_repo,
+        webhook_repo,
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Publisher).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    let result = functions::publish_batch(
+        user,
+        entries,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        get_allowed_uri_authorities(),
+        get_reserved_names(),
+        get_wrap_fetcher(),
+        package_repo,
+        key_repo,
+        webhook_repo,
+        audit_repo,
+        account_service,
+        get_webhook_queue().await,
+    )
+    .await?;
+
+    let status = match result.published {
+        true => StatusCode::OK,
+        false => StatusCode::BAD_REQUEST,
+    };
+
+    Ok((status, Json(result)).into_response())
+}
+
+#[derive(serde::Deserialize)]
+pub struct BatchBody {
+    pub entries: Vec<BatchEntryBody>,
+}
@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use http::{HeaderMap, StatusCode};
+
+use crate::{
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package},
+    AuditRepository, Repository,
+};
+
+use super::{
+    get_key_id_secret, get_org_account_service, get_reserved_names, Dependencies, RequestId,
+};
+
+pub async fn set_redirect<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package)): Path<(String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+    Json(RedirectBody { to }): Json<RedirectBody>,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package> + Repository<Organization> + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        package_repo,
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::set_redirect(
+        user,
+        package,
+        to,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        get_reserved_names(),
+        package_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(StatusCode::OK)
+}
+
+pub async fn remove_redirect<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package)): Path<(String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package> + Repository<Organization> + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        package_repo,
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::remove_redirect(
+        user,
+        package,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        package_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RedirectBody {
+    /// The id of the target package, e.g. `user/package`
+    pub to: String,
+}
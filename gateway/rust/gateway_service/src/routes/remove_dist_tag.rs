@@ -0,0 +1,58 @@
+use axum::{
+    body::BoxBody,
+    extract::{Path, State},
+    response::Response,
+};
+use http::{HeaderMap, StatusCode};
+
+use crate::{
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, internal_server_error, ApiError},
+    models::{OrgRole, Organization, Package},
+    AuditRepository, Repository,
+};
+
+use super::{get_key_id_secret, get_org_account_service, Dependencies, RequestId};
+
+pub async fn remove_dist_tag<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package, tag)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+) -> Result<Response, ApiError>
+where
+    T: Repository<Package> + Repository<Organization> + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        package_repo,
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Maintainer).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::remove_dist_tag(
+        user,
+        package,
+        tag,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        package_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .body(BoxBody::default())
+        .map_err(internal_server_error)?;
+
+    Ok(response)
+}
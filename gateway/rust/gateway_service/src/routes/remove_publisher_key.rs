@@ -0,0 +1,33 @@
+use axum::extract::{Path, State};
+use http::{HeaderMap, StatusCode};
+
+use crate::{
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package, PublisherKeys},
+    Repository,
+};
+
+use super::{get_org_account_service, Dependencies};
+
+pub async fn remove_publisher_key<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package> + Repository<PublisherKeys> + Repository<Organization> + Send + Sync,
+{
+    let Dependencies {
+        key_repo, org_repo, ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::remove_publisher_key(user, id, api_key, key_repo, account_service).await?;
+
+    Ok(StatusCode::OK)
+}
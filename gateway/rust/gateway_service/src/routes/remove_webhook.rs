@@ -0,0 +1,70 @@
+use axum::extract::{Path, State};
+use http::{HeaderMap, StatusCode};
+
+use crate::{
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package, WebhookSubscriptions},
+    Repository,
+};
+
+use super::{get_org_account_service, Dependencies};
+
+pub async fn remove_webhook<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package, id)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    remove(deps, Some((user, package)), id, headers).await
+}
+
+pub async fn remove_registry_webhook<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    remove(deps, None, id, headers).await
+}
+
+async fn remove<T, A>(
+    deps: Dependencies<T, A>,
+    user_and_package: Option<(String, String)>,
+    id: String,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<Organization>
+        + Send
+        + Sync,
+{
+    let Dependencies {
+        webhook_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Maintainer).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::remove_webhook(user_and_package, id, api_key, webhook_repo, account_service).await?;
+
+    Ok(StatusCode::OK)
+}
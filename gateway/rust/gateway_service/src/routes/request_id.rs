@@ -0,0 +1,42 @@
+use std::convert::Infallible;
+
+use axum::{async_trait, extract::FromRequestParts};
+use http::request::Parts;
+use lambda_http::RequestExt;
+
+use crate::constants;
+
+/// Identifies the request in audit entries: the Lambda request id when deployed,
+/// otherwise the `x-request-id` header or a new id.
+/// Ids sent by clients are prefixed with `client:`, so they can't pass for the registry's own.
+pub struct RequestId(pub String);
+
+const CLIENT_REQUEST_ID_PREFIX: &str = "client:";
+const MAX_CLIENT_REQUEST_ID_LENGTH: usize = 64;
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestId {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let request_id = parts
+            .extensions
+            .lambda_context_ref()
+            .map(|context| context.request_id.clone())
+            .or_else(|| {
+                parts
+                    .headers
+                    .get(constants::REQUEST_ID_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .filter(|value| {
+                        !value.is_empty()
+                            && value.len() <= MAX_CLIENT_REQUEST_ID_LENGTH
+                            && value.chars().all(|c| c.is_ascii_graphic())
+                    })
+                    .map(|value| format!("{}{}", CLIENT_REQUEST_ID_PREFIX, value))
+            })
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        Ok(RequestId(request_id))
+    }
+}
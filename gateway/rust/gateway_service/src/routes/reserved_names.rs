@@ -0,0 +1,13 @@
+use crate::{
+    constants::{self, DEFAULT_RESERVED_NAMES, DEFAULT_RESERVED_NAMES_ALLOWLIST},
+    models::ReservedNames,
+};
+
+pub fn get_reserved_names() -> ReservedNames {
+    let names = std::env::var(constants::ENV_RESERVED_NAMES)
+        .unwrap_or_else(|_| DEFAULT_RESERVED_NAMES.to_string());
+    let allowlist = std::env::var(constants::ENV_RESERVED_NAMES_ALLOWLIST)
+        .unwrap_or_else(|_| DEFAULT_RESERVED_NAMES_ALLOWLIST.to_string());
+
+    ReservedNames::new(&names, &allowlist)
+}
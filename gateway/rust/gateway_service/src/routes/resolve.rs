@@ -1,30 +1,51 @@
 use axum::{
     body::BoxBody,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Response,
 };
 use http::StatusCode;
 
-use crate::{constants, debug_println, functions, models::Package, Repository, http_utils::internal_server_error};
+use crate::{
+    constants, functions,
+    http_utils::{internal_server_error, ApiError},
+    models::Package,
+    Repository, VersionRepository,
+};
 
 use super::Dependencies;
 
-pub async fn resolve<T>(
+pub async fn resolve<T, A>(
     Path((user, package_and_version, file_path)): Path<(String, String, String)>,
-    State(deps): State<Dependencies<T>>,
-) -> Result<Response, StatusCode>
+    Query(ResolveQuery { include_prerelease }): Query<ResolveQuery>,
+    State(deps): State<Dependencies<T, A>>,
+) -> Result<Response, ApiError>
 where
-    T: Repository<Package>,
+    T: Repository<Package> + VersionRepository,
 {
-    let Dependencies { package_repo } = deps;
+    let Dependencies { package_repo, .. } = deps;
 
-    let uri = functions::resolve(user, package_and_version, file_path, &package_repo).await?;
+    let resolved = functions::resolve(
+        user,
+        package_and_version,
+        file_path,
+        include_prerelease,
+        &package_repo,
+    )
+    .await?;
 
     let response: Response = Response::builder()
         .status(StatusCode::OK)
-        .header(constants::WRAP_URI_HEADER, uri.to_string())
+        .header(constants::WRAP_URI_HEADER, resolved.value.to_string())
+        .header(constants::CANONICAL_ID_HEADER, resolved.canonical_id)
         .body(BoxBody::default())
         .map_err(internal_server_error)?;
 
     Ok(response)
 }
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveQuery {
+    #[serde(default)]
+    pub include_prerelease: bool,
+}
@@ -0,0 +1,145 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use http::{HeaderMap, StatusCode};
+
+use crate::{
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, ApiError},
+    models::{OrgRole, Organization, Package, WebhookSubscriptions},
+    AuditRepository, MoveRepository, Repository,
+};
+
+use super::{get_key_id_secret, get_org_account_service, Dependencies, RequestId};
+
+/// Offers the package to another user, authenticated as the owner
+pub async fn request_transfer<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package)): Path<(String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+    Json(TransferBody { to }): Json<TransferBody>,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package> + Repository<Organization> + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        package_repo,
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::request_transfer(
+        user,
+        package,
+        to,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        package_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(StatusCode::OK)
+}
+
+pub async fn cancel_transfer<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package)): Path<(String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+) -> Result<StatusCode, ApiError>
+where
+    T: Repository<Package> + Repository<Organization> + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        package_repo,
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::cancel_transfer(
+        user,
+        package,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        package_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Moves the package, authenticated as the user it was offered to
+pub async fn accept_transfer<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package)): Path<(String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+    Json(TransferBody { to }): Json<TransferBody>,
+) -> Result<Json<TransferredPackage>, ApiError>
+where
+    T: Repository<Package>
+        + Repository<Organization>
+        + Repository<WebhookSubscriptions>
+        + MoveRepository
+        + Send
+        + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        package_repo,
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Owner).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    let id = functions::accept_transfer(
+        user,
+        package,
+        to,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        package_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    Ok(Json(TransferredPackage { id }))
+}
+
+/// Both parties name the recipient, so neither confirms a different transfer than the other
+#[derive(serde::Deserialize)]
+pub struct TransferBody {
+    pub to: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct TransferredPackage {
+    pub id: String,
+}
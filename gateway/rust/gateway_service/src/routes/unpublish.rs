@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use axum::{
+    body::BoxBody,
+    extract::{Path, State},
+    response::Response,
+};
+use http::{HeaderMap, StatusCode};
+use lazy_static::lazy_static;
+
+use crate::{
+    constants,
+    debugging::log_error,
+    functions,
+    http_utils::{extract_api_key_from_headers, internal_server_error, ApiError},
+    models::{OrgRole, Organization, Package},
+    AuditRepository, Repository,
+};
+
+use super::{get_key_id_secret, get_org_account_service, Dependencies, RequestId};
+
+pub async fn unpublish<T, A>(
+    State(deps): State<Dependencies<T, A>>,
+    Path((user, package_and_version)): Path<(String, String)>,
+    headers: HeaderMap,
+    RequestId(request_id): RequestId,
+) -> Result<Response, ApiError>
+where
+    T: Repository<Package> + Repository<Organization> + Send + Sync,
+    A: AuditRepository + Send + Sync,
+{
+    let Dependencies {
+        package_repo,
+        audit_repo,
+        org_repo,
+        ..
+    } = deps;
+
+    let account_service = get_org_account_service(org_repo, OrgRole::Maintainer).await;
+
+    let api_key = extract_api_key_from_headers(headers).map_err(log_error)?;
+
+    functions::unpublish(
+        user,
+        package_and_version,
+        api_key,
+        request_id,
+        get_key_id_secret(),
+        get_unpublish_window(),
+        package_repo,
+        audit_repo,
+        account_service,
+    )
+    .await?;
+
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .body(BoxBody::default())
+        .map_err(internal_server_error)?;
+
+    Ok(response)
+}
+
+fn get_unpublish_window() -> Duration {
+    *UNPUBLISH_WINDOW
+}
+
+lazy_static! {
+    static ref UNPUBLISH_WINDOW: Duration = {
+        let hours = match std::env::var(constants::ENV_UNPUBLISH_WINDOW_HOURS) {
+            Ok(hours) => hours.parse().unwrap_or_else(|_| {
+                eprintln!(
+                    "{} must be a number of hours, using the default of {}",
+                    constants::ENV_UNPUBLISH_WINDOW_HOURS,
+                    constants::DEFAULT_UNPUBLISH_WINDOW_HOURS
+                );
+                constants::DEFAULT_UNPUBLISH_WINDOW_HOURS
+            }),
+            Err(_) => constants::DEFAULT_UNPUBLISH_WINDOW_HOURS,
+        };
+
+        match hours.checked_mul(60 * 60) {
+            Some(secs) => Duration::from_secs(secs),
+            None => {
+                eprintln!(
+                    "{} is too large, using the default of {}",
+                    constants::ENV_UNPUBLISH_WINDOW_HOURS,
+                    constants::DEFAULT_UNPUBLISH_WINDOW_HOURS
+                );
+                Duration::from_secs(constants::DEFAULT_UNPUBLISH_WINDOW_HOURS * 60 * 60)
+            }
+        }
+    };
+}
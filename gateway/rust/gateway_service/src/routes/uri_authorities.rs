@@ -0,0 +1,26 @@
+use lazy_static::lazy_static;
+
+use crate::{constants, models::AllowedUriAuthorities};
+
+pub fn get_allowed_uri_authorities() -> AllowedUriAuthorities {
+    ALLOWED_URI_AUTHORITIES.clone()
+}
+
+lazy_static! {
+    static ref ALLOWED_URI_AUTHORITIES: AllowedUriAuthorities =
+        match std::env::var(constants::ENV_ALLOWED_URI_AUTHORITIES) {
+            // An empty list would reject every publish, so it's treated as unset
+            Ok(authorities) if authorities.split(',').any(|a| !a.trim().is_empty()) => {
+                authorities.parse().unwrap_or_else(|e| {
+                    eprintln!(
+                        "{} must be a comma separated list of URI authorities ({}), using the default of {}",
+                        constants::ENV_ALLOWED_URI_AUTHORITIES,
+                        e,
+                        AllowedUriAuthorities::default()
+                    );
+                    AllowedUriAuthorities::default()
+                })
+            }
+            _ => AllowedUriAuthorities::default(),
+        };
+}
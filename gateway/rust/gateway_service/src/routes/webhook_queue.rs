@@ -0,0 +1,29 @@
+use crate::webhooks::WebhookQueue;
+
+/// Deliveries are sent to `WEBHOOK_QUEUE_URL` and delivered by `deliver_webhooks`
+#[cfg(not(feature = "local"))]
+pub async fn get_webhook_queue() -> impl WebhookQueue {
+    use crate::{constants, webhooks::SqsWebhookQueue};
+
+    let client = SQS_CLIENT
+        .get_or_init(|| async { aws_sdk_sqs::Client::new(&aws_config::load_from_env().await) })
+        .await;
+
+    SqsWebhookQueue::new(
+        client.clone(),
+        std::env::var(constants::ENV_WEBHOOK_QUEUE_URL).expect("ENV_WEBHOOK_QUEUE_URL not set"),
+    )
+}
+
+// Loading the config resolves the region and credentials, which only happens on the first publish
+#[cfg(not(feature = "local"))]
+lazy_static::lazy_static! {
+    static ref SQS_CLIENT: tokio::sync::OnceCell<aws_sdk_sqs::Client> = tokio::sync::OnceCell::new();
+}
+
+#[cfg(feature = "local")]
+pub async fn get_webhook_queue() -> impl WebhookQueue {
+    use crate::webhooks::{HttpWebhookDelivery, ImmediateWebhookQueue};
+
+    ImmediateWebhookQueue::new(HttpWebhookDelivery::new())
+}
@@ -0,0 +1,23 @@
+use crate::{
+    constants,
+    fetching::{FallbackWrapFetcher, HttpWrapFetcher, IpfsGatewayWrapFetcher, WrapFetcher},
+};
+
+/// Manifests are only verified on publish when `VERIFY_WRAP_MANIFESTS` is `true`
+pub fn get_wrap_fetcher() -> Option<impl WrapFetcher> {
+    let verify = std::env::var(constants::ENV_VERIFY_WRAP_MANIFESTS)
+        .map(|verify| verify == "true")
+        .unwrap_or(false);
+
+    if !verify {
+        return None;
+    }
+
+    let ipfs_gateway_url = std::env::var(constants::ENV_IPFS_GATEWAY_URL)
+        .unwrap_or_else(|_| constants::DEFAULT_IPFS_GATEWAY_URL.to_string());
+
+    Some(FallbackWrapFetcher::new(
+        HttpWrapFetcher::new(),
+        IpfsGatewayWrapFetcher::new(ipfs_gateway_url),
+    ))
+}
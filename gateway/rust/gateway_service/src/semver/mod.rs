@@ -0,0 +1,164 @@
+mod range;
+pub use range::*;
+
+mod version;
+pub use version::*;
+
+pub trait IVersion {
+    /// The semantic version, or `None` if the version isn't a semantic version (e.g. `latest`).
+    fn semver(&self) -> Option<&SemVer>;
+}
+
+pub fn get_latest<'a, T: IVersion>(
+    range: &VersionRange,
+    versions: &'a [T],
+    include_prerelease: bool,
+) -> Option<&'a T> {
+    versions
+        .iter()
+        .filter(
+            |v| matches!(v.semver(), Some(sem_ver) if range.satisfies(sem_ver, include_prerelease)),
+        )
+        .max_by_key(|v| v.semver())
+}
+
+/// Sorts versions in ascending order of precedence, non-semantic versions first.
+pub fn sort_versions<T: IVersion>(versions: &mut [T]) {
+    versions.sort_by(|a, b| a.semver().cmp(&b.semver()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Version {
+        name: String,
+        semver: Option<SemVer>,
+    }
+
+    impl IVersion for Version {
+        fn semver(&self) -> Option<&SemVer> {
+            self.semver.as_ref()
+        }
+    }
+
+    fn versions(names: &[&str]) -> Vec<Version> {
+        names
+            .iter()
+            .map(|name| Version {
+                name: name.to_string(),
+                semver: name.parse().ok(),
+            })
+            .collect()
+    }
+
+    fn names(versions: &[Version]) -> Vec<&str> {
+        versions.iter().map(|v| v.name.as_str()).collect()
+    }
+
+    #[test]
+    fn parse_should_return_ok_for_valid_versions_and_err_for_invalid_versions() {
+        assert!("1.2.3".parse::<SemVer>().is_ok());
+        assert!("1.2.3-alpha".parse::<SemVer>().is_ok());
+        assert!("1.2".parse::<SemVer>().is_err());
+        assert!("1.2.3-alpha.1".parse::<SemVer>().is_ok());
+        assert!("a.b.c".parse::<SemVer>().is_err());
+        assert!("1.2.3-".parse::<SemVer>().is_err());
+        assert!("1.2.3+".parse::<SemVer>().is_err());
+    }
+
+    #[test]
+    fn get_latest_should_return_the_latest_version_object_for_a_given_version_prefix() {
+        let version_objects = versions(&["1.0.0", "1.1.0", "1.2.0", "2.0.0", "2.1.0", "2.1.1"]);
+
+        let get_latest = |range: &str| {
+            get_latest(&range.parse().unwrap(), &version_objects, false).map(|v| v.name.as_str())
+        };
+
+        assert_eq!(get_latest("1").unwrap(), "1.2.0");
+        assert_eq!(get_latest("2.1").unwrap(), "2.1.1");
+        assert!(get_latest("3").is_none());
+    }
+
+    #[test]
+    fn get_latest_should_return_the_latest_version_object_satisfying_a_range() {
+        let version_objects =
+            versions(&["1.0.0", "1.2.0", "1.3.0-beta", "2.0.0", "2.1.0", "3.0.0"]);
+
+        let get_latest = |range: &str| {
+            get_latest(&range.parse().unwrap(), &version_objects, false).map(|v| v.name.as_str())
+        };
+
+        assert_eq!(get_latest("^1.0.0").unwrap(), "1.2.0");
+        assert_eq!(get_latest("~1.2").unwrap(), "1.2.0");
+        assert_eq!(get_latest(">=1.0.0 <2.0.0").unwrap(), "1.2.0");
+        assert_eq!(get_latest("1.3.0-beta").unwrap(), "1.3.0-beta");
+        assert_eq!(get_latest("2.x").unwrap(), "2.1.0");
+        assert_eq!(get_latest("*").unwrap(), "3.0.0");
+        assert_eq!(get_latest("1.0.0 || 2.0.0").unwrap(), "2.0.0");
+        assert!(get_latest(">3.0.0").is_none());
+    }
+
+    #[test]
+    fn get_latest_should_compare_numeric_prerelease_identifiers_numerically() {
+        let version_objects = versions(&["1.0.0-beta.2", "1.0.0-beta.10", "1.0.0-beta.9"]);
+
+        let latest = get_latest(
+            &"1.0.0-beta.1 - 1.0.0-beta.99".parse().unwrap(),
+            &version_objects,
+            false,
+        );
+
+        assert_eq!(latest.unwrap().name, "1.0.0-beta.10");
+    }
+
+    #[test]
+    fn get_latest_should_include_prereleases_when_requested() {
+        let version_objects = versions(&["1.0.0", "1.1.0-rc.1", "2.0.0-alpha"]);
+
+        let get_latest = |range: &str| {
+            get_latest(&range.parse().unwrap(), &version_objects, true).map(|v| v.name.as_str())
+        };
+
+        assert_eq!(get_latest("1").unwrap(), "1.1.0-rc.1");
+        assert_eq!(get_latest("*").unwrap(), "2.0.0-alpha");
+    }
+
+    #[test]
+    fn sort_versions_should_return_an_array_of_version_objects_sorted_in_ascending_order() {
+        let mut unordered = versions(&["1.0.0", "2.0.0", "1.2.0", "2.1.0", "1.1.0", "2.1.1"]);
+
+        sort_versions(&mut unordered);
+
+        assert_eq!(
+            names(&unordered),
+            ["1.0.0", "1.1.0", "1.2.0", "2.0.0", "2.1.0", "2.1.1"]
+        );
+    }
+
+    #[test]
+    fn sort_versions_should_follow_semver_precedence() {
+        let mut unordered = versions(&[
+            "1.0.0",
+            "1.0.0-beta.10",
+            "latest",
+            "1.0.0-beta.2",
+            "1.0.0-alpha",
+            "1.0.0-beta",
+        ]);
+
+        sort_versions(&mut unordered);
+
+        assert_eq!(
+            names(&unordered),
+            [
+                "latest",
+                "1.0.0-alpha",
+                "1.0.0-beta",
+                "1.0.0-beta.2",
+                "1.0.0-beta.10",
+                "1.0.0"
+            ]
+        );
+    }
+}
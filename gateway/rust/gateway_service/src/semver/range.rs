@@ -0,0 +1,510 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use super::{Identifier, SemVer};
+
+/// An npm-style version range, e.g. `^1.2.0`, `~1.2`, `>=1.0.0 <2.0.0`, `1.x`, `*` or `1.x || 3.x`.
+///
+/// The range is stored as a union (`||`) of comparator sets; a version satisfies the range
+/// if it satisfies every comparator of at least one set.
+///
+/// Like npm, prereleases are excluded unless a comparator of the same set names a prerelease
+/// of the same `major.minor.patch` (e.g. `>=1.2.3-beta` matches `1.2.3-rc` but not `1.2.4-rc`),
+/// or unless prereleases are explicitly included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionRange {
+    comparator_sets: Vec<Vec<Comparator>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    operator: Operator,
+    version: SemVer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Exact,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+/// A version where the minor and patch parts may be omitted or wildcards (`x`, `X`, `*`).
+#[derive(Debug, Clone, PartialEq)]
+struct PartialSemVer {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    prerelease: Vec<Identifier>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VersionRangeParseError {
+    range: String,
+}
+
+impl Display for VersionRangeParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid version range: {}", self.range)
+    }
+}
+
+impl std::error::Error for VersionRangeParseError {}
+
+impl VersionRange {
+    pub fn satisfies(&self, version: &SemVer, include_prerelease: bool) -> bool {
+        self.comparator_sets.iter().any(|set| {
+            set.iter().all(|comparator| comparator.matches(version))
+                && (include_prerelease
+                    || !version.is_prerelease()
+                    || set
+                        .iter()
+                        .any(|comparator| comparator.allows_prerelease_of(version)))
+        })
+    }
+}
+
+impl FromStr for VersionRange {
+    type Err = VersionRangeParseError;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let error = || VersionRangeParseError {
+            range: range.to_string(),
+        };
+
+        let comparator_sets = range
+            .split("||")
+            .map(|set| parse_comparator_set(set.trim()).ok_or_else(error))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { comparator_sets })
+    }
+}
+
+impl Comparator {
+    fn new(operator: Operator, version: SemVer) -> Self {
+        Self { operator, version }
+    }
+
+    fn matches(&self, version: &SemVer) -> bool {
+        match self.operator {
+            Operator::Exact => version == &self.version,
+            Operator::Greater => version > &self.version,
+            Operator::GreaterOrEqual => version >= &self.version,
+            Operator::Less => version < &self.version,
+            Operator::LessOrEqual => version <= &self.version,
+        }
+    }
+
+    fn allows_prerelease_of(&self, version: &SemVer) -> bool {
+        self.version.is_prerelease()
+            && self.version.major == version.major
+            && self.version.minor == version.minor
+            && self.version.patch == version.patch
+    }
+}
+
+fn parse_comparator_set(set: &str) -> Option<Vec<Comparator>> {
+    if let Some((from, to)) = set.split_once(" - ") {
+        return parse_hyphen_range(from.trim(), to.trim());
+    }
+
+    let mut comparators = vec![];
+    let mut tokens = set.split_whitespace();
+
+    while let Some(token) = tokens.next() {
+        // Allow whitespace between an operator and its version, e.g. `>= 1.2.3`
+        let token = if is_operator(token) {
+            format!("{}{}", token, tokens.next()?)
+        } else {
+            token.to_string()
+        };
+
+        comparators.extend(parse_comparator(&token)?);
+    }
+
+    Some(comparators)
+}
+
+fn is_operator(token: &str) -> bool {
+    matches!(token, "^" | "~" | "~>" | ">" | ">=" | "<" | "<=" | "=")
+}
+
+fn parse_comparator(token: &str) -> Option<Vec<Comparator>> {
+    // Order matters: the two-character operators must be checked first
+    let operators = ["~>", ">=", "<=", "^", "~", ">", "<", "="];
+
+    let (operator, version) = operators
+        .iter()
+        .find_map(|operator| {
+            token
+                .strip_prefix(operator)
+                .map(|version| (*operator, version))
+        })
+        .unwrap_or(("", token));
+
+    let partial = parse_partial(version)?;
+
+    match operator {
+        "^" => caret(&partial),
+        "~" | "~>" => tilde(&partial),
+        ">" => greater_than(&partial),
+        ">=" => Some(lower_bound(&partial).into_iter().collect()),
+        "<" => less_than(&partial),
+        "<=" => Some(upper_bound(&partial)?.into_iter().collect()),
+        _ => x_range(&partial),
+    }
+}
+
+fn parse_partial(version: &str) -> Option<PartialSemVer> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+
+    // Build metadata never affects matching
+    let version = version
+        .split_once('+')
+        .map_or(version, |(version, _)| version);
+
+    let (version, prerelease) = match version.split_once('-') {
+        Some((version, prerelease)) => (version, Some(prerelease)),
+        None => (version, None),
+    };
+
+    let mut parts = version.split('.').map(parse_part);
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(None))?;
+    let patch = parts.next().unwrap_or(Some(None))?;
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    // Wildcards must only be followed by wildcards, e.g. `1.x.3` is invalid
+    if (major.is_none() && minor.is_some()) || (minor.is_none() && patch.is_some()) {
+        return None;
+    }
+
+    let prerelease = match prerelease {
+        // A prerelease is only allowed on a full version, e.g. `1.2.3-beta`
+        Some(prerelease) => {
+            format!("{}.{}.{}-{}", major?, minor?, patch?, prerelease)
+                .parse::<SemVer>()
+                .ok()?
+                .prerelease
+        }
+        None => vec![],
+    };
+
+    Some(PartialSemVer {
+        major,
+        minor,
+        patch,
+        prerelease,
+    })
+}
+
+/// Parses a single version part, returning `Some(None)` for wildcards and `None` for invalid parts.
+fn parse_part(part: &str) -> Option<Option<u64>> {
+    match part {
+        "x" | "X" | "*" => Some(None),
+        _ if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) => {
+            part.parse().ok().map(Some)
+        }
+        _ => None,
+    }
+}
+
+fn parse_hyphen_range(from: &str, to: &str) -> Option<Vec<Comparator>> {
+    let from = parse_partial(from)?;
+    let to = parse_partial(to)?;
+
+    let mut comparators: Vec<Comparator> = lower_bound(&from).into_iter().collect();
+    comparators.extend(upper_bound(&to)?);
+
+    Some(comparators)
+}
+
+/// `>=` comparator for the lowest version matching the partial, `None` if it matches everything.
+fn lower_bound(partial: &PartialSemVer) -> Option<Comparator> {
+    let major = partial.major?;
+
+    Some(Comparator::new(
+        Operator::GreaterOrEqual,
+        SemVer {
+            prerelease: partial.prerelease.clone(),
+            ..SemVer::new(
+                major,
+                partial.minor.unwrap_or(0),
+                partial.patch.unwrap_or(0),
+            )
+        },
+    ))
+}
+
+/// `<=` comparator (or an equivalent `<` one) for the highest version matching the partial.
+fn upper_bound(partial: &PartialSemVer) -> Option<Option<Comparator>> {
+    Some(match (partial.major, partial.minor, partial.patch) {
+        (None, _, _) => None,
+        (Some(major), None, _) => Some(below(major.checked_add(1)?, 0, 0)),
+        (Some(major), Some(minor), None) => Some(below(major, minor.checked_add(1)?, 0)),
+        (Some(_), Some(_), Some(_)) => Some(Comparator::new(
+            Operator::LessOrEqual,
+            full_version(partial),
+        )),
+    })
+}
+
+/// `<` comparator which excludes the given version and its prereleases.
+fn below(major: u64, minor: u64, patch: u64) -> Comparator {
+    Comparator::new(
+        Operator::Less,
+        SemVer {
+            prerelease: vec![Identifier::Numeric(0)],
+            ..SemVer::new(major, minor, patch)
+        },
+    )
+}
+
+fn x_range(partial: &PartialSemVer) -> Option<Vec<Comparator>> {
+    if partial.patch.is_some() {
+        return Some(vec![Comparator::new(
+            Operator::Exact,
+            full_version(partial),
+        )]);
+    }
+
+    let mut comparators: Vec<Comparator> = lower_bound(partial).into_iter().collect();
+    comparators.extend(upper_bound(partial)?);
+
+    Some(comparators)
+}
+
+fn caret(partial: &PartialSemVer) -> Option<Vec<Comparator>> {
+    let Some(major) = partial.major else {
+        return Some(vec![]);
+    };
+
+    let upper = match (major, partial.minor, partial.patch) {
+        (0, Some(0), Some(patch)) => below(0, 0, patch.checked_add(1)?),
+        (0, Some(minor), _) => below(0, minor.checked_add(1)?, 0),
+        _ => below(major.checked_add(1)?, 0, 0),
+    };
+
+    Some(lower_bound(partial).into_iter().chain([upper]).collect())
+}
+
+fn tilde(partial: &PartialSemVer) -> Option<Vec<Comparator>> {
+    let Some(major) = partial.major else {
+        return Some(vec![]);
+    };
+
+    let upper = match partial.minor {
+        Some(minor) => below(major, minor.checked_add(1)?, 0),
+        None => below(major.checked_add(1)?, 0, 0),
+    };
+
+    Some(lower_bound(partial).into_iter().chain([upper]).collect())
+}
+
+fn greater_than(partial: &PartialSemVer) -> Option<Vec<Comparator>> {
+    Some(match (partial.major, partial.minor, partial.patch) {
+        // Nothing is greater than every version
+        (None, _, _) => vec![below(0, 0, 0)],
+        (Some(major), None, _) => vec![at_least(major.checked_add(1)?, 0, 0)],
+        (Some(major), Some(minor), None) => vec![at_least(major, minor.checked_add(1)?, 0)],
+        (Some(_), Some(_), Some(_)) => {
+            vec![Comparator::new(Operator::Greater, full_version(partial))]
+        }
+    })
+}
+
+fn less_than(partial: &PartialSemVer) -> Option<Vec<Comparator>> {
+    Some(match (partial.major, partial.minor, partial.patch) {
+        // Nothing is less than every version
+        (None, _, _) => vec![below(0, 0, 0)],
+        (Some(_), _, _) if !partial.prerelease.is_empty() => {
+            vec![Comparator::new(Operator::Less, full_version(partial))]
+        }
+        (Some(major), minor, patch) => {
+            vec![below(major, minor.unwrap_or(0), patch.unwrap_or(0))]
+        }
+    })
+}
+
+fn at_least(major: u64, minor: u64, patch: u64) -> Comparator {
+    Comparator::new(Operator::GreaterOrEqual, SemVer::new(major, minor, patch))
+}
+
+fn full_version(partial: &PartialSemVer) -> SemVer {
+    SemVer {
+        prerelease: partial.prerelease.clone(),
+        ..SemVer::new(
+            partial.major.unwrap_or(0),
+            partial.minor.unwrap_or(0),
+            partial.patch.unwrap_or(0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn satisfies(range: &str, version: &str) -> bool {
+        range
+            .parse::<VersionRange>()
+            .unwrap()
+            .satisfies(&version.parse().unwrap(), false)
+    }
+
+    fn satisfies_including_prerelease(range: &str, version: &str) -> bool {
+        range
+            .parse::<VersionRange>()
+            .unwrap()
+            .satisfies(&version.parse().unwrap(), true)
+    }
+
+    #[test]
+    fn parses_valid_ranges() {
+        for range in [
+            "1",
+            "1.2",
+            "1.2.3",
+            "v1.2.3",
+            "1.2.3-beta.1",
+            "^1.2.0",
+            "~1.2",
+            "~>1.2",
+            ">=1.0.0 <2.0.0",
+            ">= 1.0.0 < 2.0.0",
+            "1.x",
+            "1.2.X",
+            "*",
+            "",
+            "1.x || >=3.0.0",
+            "1.2 - 2.3.4",
+        ] {
+            assert!(range.parse::<VersionRange>().is_ok(), "{}", range);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        for range in [
+            "a.b.c",
+            "latest",
+            "1.2.3.4",
+            "1.x.3",
+            "^1.2-beta",
+            ">=",
+            "1.2.3-",
+            "1..2",
+            "1.2 -",
+            "18446744073709551616",
+        ] {
+            assert_eq!(
+                range.parse::<VersionRange>(),
+                Err(VersionRangeParseError {
+                    range: range.to_string()
+                }),
+                "{}",
+                range
+            );
+        }
+    }
+
+    #[test]
+    fn matches_partial_and_x_ranges() {
+        assert!(satisfies("1", "1.0.0"));
+        assert!(satisfies("1", "1.9.9"));
+        assert!(!satisfies("1", "2.0.0"));
+        assert!(satisfies("1.2", "1.2.5"));
+        assert!(!satisfies("1.2", "1.3.0"));
+        assert!(satisfies("1.x", "1.3.0"));
+        assert!(satisfies("1.2.x", "1.2.0"));
+        assert!(satisfies("*", "5.0.0"));
+        assert!(satisfies("", "5.0.0"));
+        assert!(satisfies("1.2.3", "1.2.3"));
+        assert!(!satisfies("1.2.3", "1.2.4"));
+        assert!(satisfies("1.2.3-beta.1", "1.2.3-beta.1"));
+        assert!(!satisfies("1.2.3", "1.2.3-beta.1"));
+    }
+
+    #[test]
+    fn matches_caret_ranges() {
+        assert!(satisfies("^1.2.0", "1.2.0"));
+        assert!(satisfies("^1.2.0", "1.9.0"));
+        assert!(!satisfies("^1.2.0", "1.1.9"));
+        assert!(!satisfies("^1.2.0", "2.0.0"));
+        assert!(satisfies("^0.2.3", "0.2.9"));
+        assert!(!satisfies("^0.2.3", "0.3.0"));
+        assert!(satisfies("^0.0.3", "0.0.3"));
+        assert!(!satisfies("^0.0.3", "0.0.4"));
+        assert!(satisfies("^1", "1.5.0"));
+        assert!(!satisfies("^1", "2.0.0"));
+    }
+
+    #[test]
+    fn matches_tilde_ranges() {
+        assert!(satisfies("~1.2", "1.2.9"));
+        assert!(!satisfies("~1.2", "1.3.0"));
+        assert!(satisfies("~1.2.3", "1.2.5"));
+        assert!(!satisfies("~1.2.3", "1.2.2"));
+        assert!(satisfies("~1", "1.9.0"));
+        assert!(!satisfies("~1", "2.0.0"));
+    }
+
+    #[test]
+    fn matches_comparator_ranges() {
+        assert!(satisfies(">=1.0.0 <2.0.0", "1.5.0"));
+        assert!(!satisfies(">=1.0.0 <2.0.0", "2.0.0"));
+        assert!(!satisfies(">=1.0.0 <2.0.0", "0.9.0"));
+        assert!(satisfies(">1.2", "1.3.0"));
+        assert!(!satisfies(">1.2", "1.2.9"));
+        assert!(satisfies("<=1.2", "1.2.9"));
+        assert!(!satisfies("<=1.2", "1.3.0"));
+        assert!(satisfies("<1.2", "1.1.9"));
+        assert!(!satisfies("<1.2", "1.2.0"));
+        assert!(!satisfies(">*", "1.0.0"));
+    }
+
+    #[test]
+    fn matches_hyphen_ranges() {
+        assert!(satisfies("1.2 - 2.3.4", "1.2.0"));
+        assert!(satisfies("1.2 - 2.3.4", "2.3.4"));
+        assert!(!satisfies("1.2 - 2.3.4", "2.3.5"));
+        assert!(satisfies("1.2.3 - 2", "2.9.9"));
+        assert!(!satisfies("1.2.3 - 2", "3.0.0"));
+    }
+
+    #[test]
+    fn matches_unions() {
+        assert!(satisfies("1.x || 3.x", "1.0.0"));
+        assert!(satisfies("1.x || 3.x", "3.1.0"));
+        assert!(!satisfies("1.x || 3.x", "2.0.0"));
+    }
+
+    #[test]
+    fn excludes_prereleases_unless_named_by_the_range() {
+        assert!(!satisfies("1", "1.2.0-beta"));
+        assert!(!satisfies("^1.0.0", "1.2.0-beta"));
+        assert!(!satisfies("*", "1.0.0-rc.1"));
+        assert!(satisfies("1.2.0-beta", "1.2.0-beta"));
+        assert!(satisfies(">=1.2.0-beta <2.0.0", "1.2.0-rc.1"));
+        assert!(!satisfies(">=1.2.0-beta <2.0.0", "1.3.0-rc.1"));
+        assert!(satisfies(">=1.2.0-beta <2.0.0", "1.3.0"));
+        assert!(satisfies("1.x || >=1.2.0-beta <1.3.0", "1.2.0-rc.1"));
+    }
+
+    #[test]
+    fn includes_prereleases_when_requested() {
+        assert!(satisfies_including_prerelease("1", "1.2.0-beta"));
+        assert!(satisfies_including_prerelease("^1.0.0", "1.2.0-beta"));
+        assert!(satisfies_including_prerelease("*", "1.0.0-rc.1"));
+        assert!(!satisfies_including_prerelease("^1.0.0", "2.0.0-beta"));
+        assert!(!satisfies_including_prerelease("1.2.3", "1.2.3-beta"));
+    }
+}
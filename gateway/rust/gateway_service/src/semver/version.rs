@@ -0,0 +1,399 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A SemVer 2.0 version (https://semver.org).
+///
+/// Equality and ordering follow SemVer precedence, so build metadata is ignored:
+/// `1.0.0+a == 1.0.0+b`, but both keep their metadata when displayed.
+#[derive(Debug, Clone)]
+pub struct SemVer {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub prerelease: Vec<Identifier>,
+    pub build: Vec<String>,
+}
+
+/// A dot-separated prerelease identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Identifier {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SemVerParseError {
+    InvalidFormat,
+    InvalidNumber,
+    LeadingZero,
+    EmptyIdentifier,
+    InvalidCharacter,
+}
+
+impl Display for SemVerParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SemVerParseError::InvalidFormat => {
+                write!(f, "Version must be in the format MAJOR.MINOR.PATCH")
+            }
+            SemVerParseError::InvalidNumber => write!(f, "Invalid version number"),
+            SemVerParseError::LeadingZero => {
+                write!(f, "Numeric identifiers must not have leading zeros")
+            }
+            SemVerParseError::EmptyIdentifier => write!(f, "Identifiers must not be empty"),
+            SemVerParseError::InvalidCharacter => {
+                write!(f, "Identifiers must only contain [0-9A-Za-z-]")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SemVerParseError {}
+
+impl SemVer {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            prerelease: vec![],
+            build: vec![],
+        }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.prerelease.is_empty()
+    }
+
+    /// The prerelease as written, e.g. `beta.1` for `1.0.0-beta.1`
+    pub fn prerelease_tag(&self) -> String {
+        join(&self.prerelease)
+    }
+
+    /// Parses a version stored before versions were validated strictly, which may have numbers
+    /// with leading zeros, e.g. `01.2.3-beta.01`. Those are read as `1.2.3-beta.01`, with the
+    /// prerelease identifier kept as written.
+    pub fn parse_legacy(version: &str) -> Result<Self, SemVerParseError> {
+        parse(version, Leniency::Legacy)
+    }
+}
+
+impl PartialEq for SemVer {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SemVer {}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.major
+            .cmp(&other.major)
+            .then_with(|| self.minor.cmp(&other.minor))
+            .then_with(|| self.patch.cmp(&other.patch))
+            .then_with(|| {
+                // A version without a prerelease has a higher precedence than one with a prerelease
+                match (self.is_prerelease(), other.is_prerelease()) {
+                    (false, true) => Ordering::Greater,
+                    (true, false) => Ordering::Less,
+                    _ => self.prerelease.cmp(&other.prerelease),
+                }
+            })
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::AlphaNumeric(a), Identifier::AlphaNumeric(b)) => a.cmp(b),
+            // Numeric identifiers always have a lower precedence than alphanumeric ones
+            (Identifier::Numeric(_), Identifier::AlphaNumeric(_)) => Ordering::Less,
+            (Identifier::AlphaNumeric(_), Identifier::Numeric(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::AlphaNumeric(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl Display for SemVer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+
+        if self.is_prerelease() {
+            write!(f, "-{}", join(&self.prerelease))?;
+        }
+
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build.join("."))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SemVer {
+    type Err = SemVerParseError;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        parse(version, Leniency::Strict)
+    }
+}
+
+impl Serialize for SemVer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for SemVer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = String::deserialize(deserializer)?;
+        SemVer::parse_legacy(&version).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Leniency {
+    Strict,
+    /// Allows leading zeros, as versions were only checked against a pattern before
+    Legacy,
+}
+
+fn parse(version: &str, leniency: Leniency) -> Result<SemVer, SemVerParseError> {
+    let (version, build) = match version.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (version, None),
+    };
+
+    let (version, prerelease) = match version.split_once('-') {
+        Some((version, prerelease)) => (version, Some(prerelease)),
+        None => (version, None),
+    };
+
+    let parts = version.split('.').collect::<Vec<_>>();
+    let [major, minor, patch] = parts.as_slice() else {
+        return Err(SemVerParseError::InvalidFormat);
+    };
+
+    let prerelease = match prerelease {
+        Some(prerelease) => split_identifiers(prerelease)?
+            .into_iter()
+            .map(|identifier| parse_prerelease_identifier(identifier, leniency))
+            .collect::<Result<_, _>>()?,
+        None => vec![],
+    };
+
+    let build = match build {
+        Some(build) => split_identifiers(build)?
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        None => vec![],
+    };
+
+    Ok(SemVer {
+        major: parse_number(major, leniency)?,
+        minor: parse_number(minor, leniency)?,
+        patch: parse_number(patch, leniency)?,
+        prerelease,
+        build,
+    })
+}
+
+fn join(identifiers: &[Identifier]) -> String {
+    identifiers
+        .iter()
+        .map(|identifier| identifier.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn split_identifiers(identifiers: &str) -> Result<Vec<&str>, SemVerParseError> {
+    identifiers
+        .split('.')
+        .map(|identifier| {
+            if identifier.is_empty() {
+                Err(SemVerParseError::EmptyIdentifier)
+            } else if !identifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+            {
+                Err(SemVerParseError::InvalidCharacter)
+            } else {
+                Ok(identifier)
+            }
+        })
+        .collect()
+}
+
+fn parse_number(number: &str, leniency: Leniency) -> Result<u64, SemVerParseError> {
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return Err(SemVerParseError::InvalidNumber);
+    }
+
+    if leniency == Leniency::Strict && has_leading_zero(number) {
+        return Err(SemVerParseError::LeadingZero);
+    }
+
+    number.parse().map_err(|_| SemVerParseError::InvalidNumber)
+}
+
+fn has_leading_zero(number: &str) -> bool {
+    number.len() > 1 && number.starts_with('0')
+}
+
+fn parse_prerelease_identifier(
+    identifier: &str,
+    leniency: Leniency,
+) -> Result<Identifier, SemVerParseError> {
+    let is_numeric = identifier.chars().all(|c| c.is_ascii_digit());
+
+    if is_numeric && !(leniency == Leniency::Legacy && has_leading_zero(identifier)) {
+        parse_number(identifier, leniency).map(Identifier::Numeric)
+    } else {
+        Ok(Identifier::AlphaNumeric(identifier.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(version: &str) -> SemVer {
+        version.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_valid_versions() {
+        let version = parse("1.2.3-beta.10+build.5");
+
+        assert_eq!(version.major, 1);
+        assert_eq!(version.minor, 2);
+        assert_eq!(version.patch, 3);
+        assert_eq!(
+            version.prerelease,
+            vec![
+                Identifier::AlphaNumeric("beta".into()),
+                Identifier::Numeric(10)
+            ]
+        );
+        assert_eq!(version.build, vec!["build".to_string(), "5".to_string()]);
+        assert_eq!(version.to_string(), "1.2.3-beta.10+build.5");
+
+        assert_eq!(parse("0.0.0").to_string(), "0.0.0");
+        assert_eq!(parse("1.0.0-x-y.0a").to_string(), "1.0.0-x-y.0a");
+        assert_eq!(parse("1.0.0+001").to_string(), "1.0.0+001");
+    }
+
+    #[test]
+    fn rejects_invalid_versions() {
+        let parse = |version: &str| version.parse::<SemVer>();
+
+        assert_eq!(parse("1.2"), Err(SemVerParseError::InvalidFormat));
+        assert_eq!(parse("1.2.3.4"), Err(SemVerParseError::InvalidFormat));
+        assert_eq!(parse("a.b.c"), Err(SemVerParseError::InvalidNumber));
+        assert_eq!(parse("1.2.3a"), Err(SemVerParseError::InvalidNumber));
+        assert_eq!(parse("01.2.3"), Err(SemVerParseError::LeadingZero));
+        assert_eq!(parse("1.2.3-beta.01"), Err(SemVerParseError::LeadingZero));
+        assert_eq!(parse("1.2.3-"), Err(SemVerParseError::EmptyIdentifier));
+        assert_eq!(parse("1.2.3+"), Err(SemVerParseError::EmptyIdentifier));
+        assert_eq!(parse("1.2.3-a..b"), Err(SemVerParseError::EmptyIdentifier));
+        assert_eq!(
+            parse("1.2.3-beta_1"),
+            Err(SemVerParseError::InvalidCharacter)
+        );
+        assert_eq!(
+            parse("18446744073709551616.0.0"),
+            Err(SemVerParseError::InvalidNumber)
+        );
+    }
+
+    #[test]
+    fn follows_semver_precedence() {
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.10",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.1.0",
+            "1.10.0",
+            "2.0.0",
+        ];
+
+        for pair in ordered.windows(2) {
+            assert!(parse(pair[0]) < parse(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn ignores_build_metadata_for_precedence() {
+        assert_eq!(parse("1.0.0+a"), parse("1.0.0+b"));
+        assert_eq!(
+            parse("1.0.0-beta+a").cmp(&parse("1.0.0-beta")),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn serializes_as_a_string() {
+        let version = parse("1.0.0-beta.2");
+
+        assert_eq!(serde_json::to_string(&version).unwrap(), "\"1.0.0-beta.2\"");
+        assert_eq!(
+            serde_json::from_str::<SemVer>("\"1.0.0-beta.2\"").unwrap(),
+            version
+        );
+        assert!(serde_json::from_str::<SemVer>("\"1.0\"").is_err());
+    }
+
+    #[test]
+    fn reads_legacy_versions_with_leading_zeros() {
+        let version = serde_json::from_str::<SemVer>("\"01.2.03-beta.01\"").unwrap();
+
+        assert_eq!(version.to_string(), "1.2.3-beta.01");
+        assert_eq!(
+            version.prerelease,
+            vec![
+                Identifier::AlphaNumeric("beta".into()),
+                Identifier::AlphaNumeric("01".into())
+            ]
+        );
+        assert_eq!(
+            "01.2.3".parse::<SemVer>(),
+            Err(SemVerParseError::LeadingZero)
+        );
+        assert!(SemVer::parse_legacy("1.2").is_err());
+    }
+}
@@ -1,22 +1,80 @@
 use aws_sdk_dynamodb::Client;
 
 use axum::{
-    routing::{get, post},
-    Router,
+    body::HttpBody,
+    routing::{delete, get, post, put},
+    BoxError, Router,
 };
-use http::Method;
 use lambda_http::{run, Error as HttpError};
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::CorsLayer;
 
 use crate::{
     constants,
+    models::{IdempotencyRecord, Organization, Package, PublisherKeys, WebhookSubscriptions},
     routes::{self, Dependencies},
-    setup_logging, PackageRepository,
+    setup_logging, AuditRepository, DynamoDbAuditRepository, DynamoDbRepository, MoveRepository,
+    Repository, VersionRepository,
 };
 
 pub async fn setup_routes() -> Result<(), HttpError> {
     setup_logging();
 
+    let route_prefix = {
+        #[cfg(not(feature = "local"))]
+        {
+            "/".to_string() + &std::env::var(constants::ENV_STAGE).expect("ENV_STAGE not set")
+        }
+        #[cfg(feature = "local")]
+        {
+            "".to_string()
+        }
+    };
+
+    #[cfg(not(feature = "local"))]
+    let app = create_router(route_prefix, get_dynamodb_dependencies().await);
+
+    // Keeping everything in memory is only for running the server locally
+    #[cfg(feature = "local")]
+    let app = {
+        let in_memory = std::env::var(constants::ENV_STORAGE)
+            .map(|storage| storage == constants::IN_MEMORY_STORAGE)
+            .unwrap_or(false);
+
+        match in_memory {
+            true => create_router(route_prefix, Dependencies::in_memory()),
+            false => create_router(route_prefix, get_dynamodb_dependencies().await),
+        }
+    };
+
+    #[cfg(not(feature = "local"))]
+    {
+        run(app).await
+    }
+
+    #[cfg(feature = "local")]
+    {
+        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 3001));
+        axum::Server::bind(&addr)
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "local"))]
+async fn get_dynamodb_client() -> Client {
+    let config = aws_config::load_from_env().await;
+    Client::new(&config)
+}
+
+#[cfg(feature = "local")]
+async fn get_dynamodb_client() -> Client {
+    crate::db::local_db::get_dynamodb_client().await
+}
+
+async fn get_dynamodb_dependencies() -> Dependencies<DynamoDbRepository, DynamoDbAuditRepository> {
     #[cfg(feature = "local")]
     {
         // TODO: placeholder until there's local env vars
@@ -37,23 +95,54 @@ pub async fn setup_routes() -> Result<(), HttpError> {
         }
     };
 
-    let package_repo = PackageRepository::new(dynamodb_client, table_name.to_owned());
-    let deps = Dependencies {
-        package_repo: package_repo.clone(),
-    };
-
-    let route_prefix = {
+    let audit_table_name = {
         #[cfg(not(feature = "local"))]
         {
-            "/".to_string() + &std::env::var(constants::ENV_STAGE).expect("ENV_STAGE not set")
+            std::env::var(constants::ENV_AUDIT_TABLE).expect("ENV_AUDIT_TABLE not set")
         }
         #[cfg(feature = "local")]
         {
-            "".to_string()
+            constants::AUDIT_TABLE_LOCAL
         }
     };
 
-    let app = Router::new()
+    let mut repo = DynamoDbRepository::new(dynamodb_client.clone(), table_name.to_owned());
+    if let Ok(legacy_table_name) = std::env::var(constants::ENV_LEGACY_PACKAGES_TABLE) {
+        repo = repo.with_legacy_table(legacy_table_name);
+    }
+    Dependencies {
+        package_repo: repo.clone(),
+        webhook_repo: repo.clone(),
+        key_repo: repo.clone(),
+        idempotency_repo: repo.clone(),
+        org_repo: repo,
+        audit_repo: DynamoDbAuditRepository::new(dynamodb_client, audit_table_name.to_owned()),
+    }
+}
+
+/// All routes, served under `route_prefix`
+pub(crate) fn create_router<T, A, B>(
+    route_prefix: String,
+    deps: Dependencies<T, A>,
+) -> Router<(), B>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    T: Repository<Package>
+        + Repository<WebhookSubscriptions>
+        + Repository<PublisherKeys>
+        + Repository<IdempotencyRecord>
+        + Repository<Organization>
+        + VersionRepository
+        + MoveRepository
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    A: AuditRepository + Clone + Send + Sync + 'static,
+{
+    Router::new()
         .route(
             &(route_prefix.clone() + "/"),
             get(routes::home).with_state(deps.clone()),
@@ -70,35 +159,237 @@ pub async fn setup_routes() -> Result<(), HttpError> {
             &(route_prefix.clone() + "/v/:user/:package"),
             get(routes::package_info).with_state(deps.clone()),
         )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/tags/:tag"),
+            put(routes::set_dist_tag)
+                .delete(routes::remove_dist_tag)
+                .with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/batch/:user"),
+            post(routes::publish_batch).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/metadata"),
+            put(routes::update_metadata).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/policy"),
+            put(routes::update_policy).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/versions"),
+            get(routes::list_versions).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/audit"),
+            get(routes::audit_log).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/promote"),
+            post(routes::promote_latest).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/transfer"),
+            put(routes::request_transfer)
+                .delete(routes::cancel_transfer)
+                .with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/transfer/accept"),
+            post(routes::accept_transfer).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/redirect"),
+            put(routes::set_redirect)
+                .delete(routes::remove_redirect)
+                .with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/webhooks"),
+            get(routes::list_webhooks)
+                .post(routes::add_webhook)
+                .with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/webhooks/:id"),
+            delete(routes::remove_webhook).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/keys/:user"),
+            get(routes::list_publisher_keys)
+                .post(routes::add_publisher_key)
+                .with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/keys/:user/:id"),
+            delete(routes::remove_publisher_key).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/orgs/:org"),
+            get(routes::get_organization)
+                .post(routes::create_organization)
+                .with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/orgs/:org/members/:username"),
+            put(routes::set_member_role)
+                .delete(routes::remove_member)
+                .with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/webhooks"),
+            get(routes::list_registry_webhooks)
+                .post(routes::add_registry_webhook)
+                .with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/webhooks/:id"),
+            delete(routes::remove_registry_webhook).with_state(deps.clone()),
+        )
+        .route(
+            &(route_prefix.clone() + "/v/:user/:package/versions/:version/yank"),
+            post(routes::yank).with_state(deps.clone()),
+        )
         .route(
             &(route_prefix + "/r/:user/:packageAndVersion"),
-            post(routes::publish).with_state(deps),
-        ).layer(CorsLayer::permissive());
+            post(routes::publish)
+                .delete(routes::unpublish)
+                .with_state(deps),
+        )
+        .layer(CorsLayer::permissive())
+}
 
-    #[cfg(not(feature = "local"))]
-    {
-        run(app).await
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, Router};
+    use base64::Engine;
+    use http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    use crate::{constants, routes::Dependencies};
+
+    use super::create_router;
+
+    const WRAP_USER_KEY: &str = "test-key";
+    const KEY_ID_SECRET: &str = "test-secret";
+    const WEBHOOK_QUEUE_URL: &str = "https://sqs.us-east-1.amazonaws.com/000000000000/test-queue";
+    const URI: &str = "wrap://ipfs/QmcT1aYdNwtbaGNJRbE8qkb9aPnkAU8P4Mf2xZZRxdm8yN";
+
+    fn app() -> Router {
+        std::env::set_var(constants::ENV_WRAP_USER_KEY, WRAP_USER_KEY);
+        std::env::set_var(constants::ENV_KEY_ID_SECRET, KEY_ID_SECRET);
+        std::env::set_var(constants::ENV_WEBHOOK_QUEUE_URL, WEBHOOK_QUEUE_URL);
+
+        create_router("".to_string(), Dependencies::in_memory())
     }
 
-    #[cfg(feature = "local")]
-    {
-        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 3001));
-        axum::Server::bind(&addr)
-            .serve(app.into_make_service())
+    fn publish_request(path: &str, api_key: &str) -> Request<Body> {
+        let api_key = base64::engine::general_purpose::STANDARD.encode(api_key);
+
+        Request::post(path)
+            .header("authorization", format!("Bearer {}", api_key))
+            .header("content-type", "application/json")
+            .body(Body::from(format!(r#"{{"uri":"{}"}}"#, URI)))
+            .unwrap()
+    }
+
+    fn get_request(path: &str) -> Request<Body> {
+        Request::get(path).body(Body::empty()).unwrap()
+    }
+
+    async fn body_json(response: http::Response<axum::body::BoxBody>) -> serde_json::Value {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn publishes_and_reads_a_package() {
+        let app = app();
+
+        let response = app
+            .clone()
+            .oneshot(publish_request("/r/polywrap/Test@1.0.0", WRAP_USER_KEY))
             .await
             .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
 
-        Ok(())
+        // Lookups are case-insensitive and return the canonical id
+        let response = app
+            .clone()
+            .oneshot(get_request("/v/Polywrap/test"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[constants::CANONICAL_ID_HEADER],
+            "polywrap/test"
+        );
+
+        let package = body_json(response).await;
+        assert_eq!(package["name"], "Test");
+        assert_eq!(package["versions"][0]["name"], "1.0.0");
+        assert_eq!(package["versions"][0]["uri"], URI);
     }
-}
 
-#[cfg(not(feature = "local"))]
-async fn get_dynamodb_client() -> Client {
-    let config = aws_config::load_from_env().await;
-    Client::new(&config)
-}
+    #[tokio::test]
+    async fn returns_not_found_for_missing_packages() {
+        let response = app()
+            .oneshot(get_request("/v/polywrap/missing"))
+            .await
+            .unwrap();
 
-#[cfg(feature = "local")]
-async fn get_dynamodb_client() -> Client {
-    crate::db::local_db::get_dynamodb_client().await
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn lists_versions_a_page_at_a_time() {
+        let app = app();
+
+        for version in ["1.0.0", "1.1.0", "1.2.0"] {
+            let path = format!("/r/polywrap/test@{}", version);
+            let response = app
+                .clone()
+                .oneshot(publish_request(&path, WRAP_USER_KEY))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(get_request("/v/polywrap/test/versions?limit=2"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let page = body_json(response).await;
+        assert_eq!(page["versions"].as_array().unwrap().len(), 2);
+        assert_eq!(page["next"], "1.1.0");
+
+        let response = app
+            .oneshot(get_request("/v/polywrap/test/versions?limit=2&after=1.1.0"))
+            .await
+            .unwrap();
+
+        let page = body_json(response).await;
+        assert_eq!(page["versions"][0]["name"], "1.2.0");
+        assert_eq!(page["next"], serde_json::Value::Null);
+    }
+
+    #[cfg(not(feature = "local"))]
+    #[tokio::test]
+    async fn rejects_publishes_with_the_wrong_key() {
+        let app = app();
+
+        let response = app
+            .clone()
+            .oneshot(publish_request("/r/polywrap/test@1.0.0", "wrong-key"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.oneshot(get_request("/v/polywrap/test")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
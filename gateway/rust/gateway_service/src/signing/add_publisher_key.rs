@@ -0,0 +1,93 @@
+use base64::Engine;
+
+use crate::{
+    models::{PublisherKey, PublisherKeys, Username},
+    Repository, RepositoryError,
+};
+
+use super::{error::SigningError, verify_publish_signature::decode_public_key};
+
+pub async fn add_publisher_key(
+    user: &Username,
+    public_key: &str,
+    key_repo: impl Repository<PublisherKeys>,
+) -> Result<PublisherKey, SigningError> {
+    // Re-encoded so the same key always gets the same id
+    let public_key =
+        base64::engine::general_purpose::STANDARD.encode(decode_public_key(public_key)?.as_bytes());
+
+    let mut keys = match key_repo.read(&PublisherKeys::key(user)).await {
+        Ok(keys) => keys,
+        Err(RepositoryError::NotFound) => PublisherKeys::new(user),
+        Err(e) => return Err(SigningError::RepositoryError(e.to_string())),
+    };
+
+    let key = PublisherKey::new(public_key);
+
+    keys.keys.retain(|existing| existing.id != key.id);
+    keys.keys.push(key.clone());
+
+    key_repo.update(&keys).await.map_err(|e| match e {
+        RepositoryError::Conflict => SigningError::Conflict,
+        e => SigningError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use ed25519_dalek::SigningKey;
+    use mockall::predicate::eq;
+
+    use crate::{
+        mocks::MockPublisherKeyRepository,
+        signing::{add_publisher_key, SigningError},
+        RepositoryError,
+    };
+
+    #[tokio::test]
+    async fn adds_key() {
+        let public_key = base64::engine::general_purpose::STANDARD
+            .encode(SigningKey::from_bytes(&[7; 32]).verifying_key().as_bytes());
+
+        let mut key_repo = MockPublisherKeyRepository::new();
+
+        key_repo
+            .expect_read()
+            .with(eq("keys:user1".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+        {
+            let public_key = public_key.clone();
+            key_repo
+                .expect_update()
+                .withf(move |keys| {
+                    keys.id == "keys:user1"
+                        && keys.keys.len() == 1
+                        && keys.keys[0].public_key == public_key
+                })
+                .return_once(|_| Ok(()));
+        }
+
+        let key = add_publisher_key(&"user1".parse().unwrap(), &public_key, key_repo)
+            .await
+            .unwrap();
+
+        assert_eq!(key.id.len(), 16);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_keys() {
+        for public_key in ["not a key", "AAAA", ""] {
+            let mut key_repo = MockPublisherKeyRepository::new();
+
+            key_repo.expect_read().never();
+            key_repo.expect_update().never();
+
+            let result = add_publisher_key(&"user1".parse().unwrap(), public_key, key_repo).await;
+
+            assert_eq!(result, Err(SigningError::InvalidPublicKey));
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum SigningError {
+    /// The user registered publisher keys, so every publish has to be signed
+    SignatureRequired,
+    UnknownKey(String),
+    InvalidSignature,
+    InvalidPublicKey,
+    Conflict,
+    RepositoryError(String),
+}
+
+impl Display for SigningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SigningError::SignatureRequired => {
+                write!(f, "A signature is required to publish as this user")
+            }
+            SigningError::UnknownKey(key_id) => write!(f, "Unknown publisher key `{}`", key_id),
+            SigningError::InvalidSignature => write!(f, "Invalid signature"),
+            SigningError::InvalidPublicKey => write!(
+                f,
+                "Invalid public key: expected a base64 encoded 32 byte Ed25519 key"
+            ),
+            SigningError::Conflict => {
+                write!(f, "Publisher keys were changed by a concurrent update")
+            }
+            SigningError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use crate::{
+    models::{PublisherKey, PublisherKeys, Username},
+    Repository, RepositoryError,
+};
+
+use super::error::SigningError;
+
+pub async fn get_publisher_keys(
+    user: &Username,
+    key_repo: &impl Repository<PublisherKeys>,
+) -> Result<Vec<PublisherKey>, SigningError> {
+    match key_repo.read(&PublisherKeys::key(user)).await {
+        Ok(keys) => Ok(keys.keys),
+        Err(RepositoryError::NotFound) => Ok(vec![]),
+        Err(e) => Err(SigningError::RepositoryError(e.to_string())),
+    }
+}
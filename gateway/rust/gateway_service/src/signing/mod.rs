@@ -0,0 +1,17 @@
+pub mod error;
+pub use error::*;
+
+mod signed_message;
+pub use signed_message::signed_message;
+
+mod verify_publish_signature;
+pub use verify_publish_signature::verify_publish_signature;
+
+mod get_publisher_keys;
+pub use get_publisher_keys::get_publisher_keys;
+
+mod add_publisher_key;
+pub use add_publisher_key::add_publisher_key;
+
+mod remove_publisher_key;
+pub use remove_publisher_key::remove_publisher_key;
@@ -0,0 +1,34 @@
+use crate::{
+    models::{PublisherKeys, Username},
+    Repository, RepositoryError,
+};
+
+use super::error::SigningError;
+
+pub async fn remove_publisher_key(
+    user: &Username,
+    id: &str,
+    key_repo: impl Repository<PublisherKeys>,
+) -> Result<(), SigningError> {
+    let mut keys = key_repo
+        .read(&PublisherKeys::key(user))
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => SigningError::UnknownKey(id.to_string()),
+            error => SigningError::RepositoryError(error.to_string()),
+        })?;
+
+    let count = keys.keys.len();
+    keys.keys.retain(|key| key.id != id);
+
+    if keys.keys.len() == count {
+        return Err(SigningError::UnknownKey(id.to_string()));
+    }
+
+    key_repo.update(&keys).await.map_err(|e| match e {
+        RepositoryError::Conflict => SigningError::Conflict,
+        e => SigningError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
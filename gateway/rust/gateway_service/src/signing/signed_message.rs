@@ -0,0 +1,36 @@
+use crate::models::{PackageName, Username, VersionName, WrapUri};
+
+/// The message a publisher signs for a version, one field per line:
+/// `{user}\n{package}\n{version}\n{uri}`, with the URI in its `wrap://` form.
+/// None of the fields can contain a line break, so every tuple has a single message.
+pub fn signed_message(
+    user: &Username,
+    package_name: &PackageName,
+    version: &VersionName,
+    uri: &WrapUri,
+) -> String {
+    format!("{}\n{}\n{}\n{}", user, package_name, version, uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::signed_message;
+
+    #[test]
+    fn uses_canonical_uri() {
+        let message = |uri: &str| {
+            signed_message(
+                &"user1".parse().unwrap(),
+                &"package1".parse().unwrap(),
+                &"1.0.0".parse().unwrap(),
+                &uri.parse().unwrap(),
+            )
+        };
+
+        assert_eq!(
+            message("ens/wraps.eth"),
+            "user1\npackage1\n1.0.0\nwrap://ens/wraps.eth"
+        );
+        assert_eq!(message("wrap://ens/wraps.eth"), message("ens/wraps.eth"));
+    }
+}
@@ -0,0 +1,136 @@
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+
+use crate::models::{PublisherKey, VersionSignature};
+
+use super::error::SigningError;
+
+/// Checks the signature of a publish against the keys of the publisher.
+/// Publishers without keys can publish unsigned, once a key is registered every publish has to be signed.
+pub fn verify_publish_signature(
+    keys: &[PublisherKey],
+    message: &str,
+    signature: Option<&VersionSignature>,
+) -> Result<(), SigningError> {
+    let signature = match signature {
+        Some(signature) => signature,
+        None if keys.is_empty() => return Ok(()),
+        None => return Err(SigningError::SignatureRequired),
+    };
+
+    // Only versions that moved have an origin, publishes are signed as their own package
+    if signature.origin.is_some() {
+        return Err(SigningError::InvalidSignature);
+    }
+
+    let key = keys
+        .iter()
+        .find(|key| key.id == signature.key_id)
+        .ok_or_else(|| SigningError::UnknownKey(signature.key_id.clone()))?;
+
+    let verifying_key = decode_public_key(&key.public_key)?;
+
+    let signature = base64::engine::general_purpose::STANDARD
+        .decode(&signature.signature)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or(SigningError::InvalidSignature)?;
+
+    verifying_key
+        .verify_strict(message.as_bytes(), &signature)
+        .map_err(|_| SigningError::InvalidSignature)
+}
+
+pub(super) fn decode_public_key(public_key: &str) -> Result<VerifyingKey, SigningError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(public_key)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or(SigningError::InvalidPublicKey)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use crate::{
+        models::{PublisherKey, SignatureOrigin, VersionSignature},
+        signing::{verify_publish_signature, SigningError},
+    };
+
+    fn encode(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn publisher_key() -> PublisherKey {
+        PublisherKey::new(encode(signing_key().verifying_key().as_bytes()))
+    }
+
+    fn sign(message: &str) -> VersionSignature {
+        VersionSignature {
+            key_id: publisher_key().id,
+            signature: encode(&signing_key().sign(message.as_bytes()).to_bytes()),
+            origin: None,
+        }
+    }
+
+    #[test]
+    fn accepts_valid_signature() {
+        assert_eq!(
+            verify_publish_signature(&[publisher_key()], "message", Some(&sign("message"))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn signature_is_only_required_with_keys() {
+        assert_eq!(verify_publish_signature(&[], "message", None), Ok(()));
+        assert_eq!(
+            verify_publish_signature(&[publisher_key()], "message", None),
+            Err(SigningError::SignatureRequired)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_signatures() {
+        assert_eq!(
+            verify_publish_signature(&[publisher_key()], "other message", Some(&sign("message"))),
+            Err(SigningError::InvalidSignature)
+        );
+        assert_eq!(
+            verify_publish_signature(
+                &[publisher_key()],
+                "message",
+                Some(&VersionSignature {
+                    signature: "not a signature".into(),
+                    ..sign("message")
+                })
+            ),
+            Err(SigningError::InvalidSignature)
+        );
+        assert_eq!(
+            verify_publish_signature(
+                &[publisher_key()],
+                "message",
+                Some(&VersionSignature {
+                    origin: Some(SignatureOrigin {
+                        user: "user2".parse().unwrap(),
+                        package: "package1".parse().unwrap(),
+                    }),
+                    ..sign("message")
+                })
+            ),
+            Err(SigningError::InvalidSignature)
+        );
+        assert_eq!(
+            verify_publish_signature(&[], "message", Some(&sign("message"))),
+            Err(SigningError::UnknownKey(publisher_key().id))
+        );
+    }
+}
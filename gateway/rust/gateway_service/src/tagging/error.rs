@@ -0,0 +1,27 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum DistTagError {
+    PackageNotFound,
+    VersionNotFound,
+    TagNotFound,
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for DistTagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DistTagError::PackageNotFound => write!(f, "Package not found"),
+            DistTagError::VersionNotFound => write!(f, "Version not found"),
+            DistTagError::TagNotFound => write!(f, "Dist-tag not found"),
+            DistTagError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            DistTagError::Conflict => write!(f, "Package was changed by a concurrent update"),
+            DistTagError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
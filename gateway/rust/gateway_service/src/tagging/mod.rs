@@ -0,0 +1,8 @@
+pub mod error;
+pub use error::*;
+
+mod set_dist_tag;
+pub use set_dist_tag::set_dist_tag;
+
+mod remove_dist_tag;
+pub use remove_dist_tag::remove_dist_tag;
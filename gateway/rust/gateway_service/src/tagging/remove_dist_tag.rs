@@ -0,0 +1,115 @@
+use crate::{
+    models::{DistTag, Package, PackageName, Username},
+    read_package_by_name, Repository, RepositoryError,
+};
+
+use super::error::DistTagError;
+
+pub async fn remove_dist_tag(
+    user: &Username,
+    package_name: &PackageName,
+    tag: &DistTag,
+    package_repo: impl Repository<Package>,
+) -> Result<(), DistTagError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => DistTagError::PackageNotFound,
+            e => DistTagError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(DistTagError::ConfusableName)?;
+
+    package
+        .dist_tags
+        .remove(tag)
+        .ok_or(DistTagError::TagNotFound)?;
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => DistTagError::Conflict,
+        e => DistTagError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tagging::{remove_dist_tag, DistTagError},
+        InMemoryPackageRepository, Package, Repository, Version,
+    };
+
+    fn package() -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![Version::new(
+            "1.0.0".parse().unwrap(),
+            "test/uri1".parse().unwrap(),
+        )];
+        package.dist_tags = [("stable".parse().unwrap(), "1.0.0".parse().unwrap())].into();
+        package
+    }
+
+    #[tokio::test]
+    async fn can_remove_dist_tag() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = remove_dist_tag(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"stable".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert!(package.dist_tags.is_empty());
+        assert_eq!(package.versions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fails_to_remove_non_existent_dist_tag() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = remove_dist_tag(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"beta".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(DistTagError::TagNotFound));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+
+    #[tokio::test]
+    async fn forbids_removing_dist_tag_as_differently_cased_user() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = remove_dist_tag(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"stable".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(DistTagError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    models::{DistTag, Package, PackageName, Username},
+    read_package_by_name, Repository, RepositoryError, SemVer,
+};
+
+use super::error::DistTagError;
+
+pub async fn set_dist_tag(
+    user: &Username,
+    package_name: &PackageName,
+    tag: DistTag,
+    version: SemVer,
+    package_repo: impl Repository<Package>,
+) -> Result<(), DistTagError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => DistTagError::PackageNotFound,
+            e => DistTagError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(DistTagError::ConfusableName)?;
+
+    let is_published = package
+        .versions
+        .iter()
+        .any(|v| v.name.semver() == Some(&version));
+    if !is_published {
+        return Err(DistTagError::VersionNotFound);
+    }
+
+    if package.dist_tags.get(&tag) == Some(&version) {
+        return Ok(());
+    }
+
+    package.dist_tags.insert(tag, version);
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => DistTagError::Conflict,
+        e => DistTagError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tagging::{set_dist_tag, DistTagError},
+        InMemoryPackageRepository, Package, Repository, RepositoryError, Version,
+    };
+
+    fn package() -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![
+            Version::new("1.0.0".parse().unwrap(), "test/uri1".parse().unwrap()),
+            Version::new(
+                "2.0.0-beta.1".parse().unwrap(),
+                "test/uri2".parse().unwrap(),
+            ),
+        ];
+        package.dist_tags = [("beta".parse().unwrap(), "1.0.0".parse().unwrap())].into();
+        package
+    }
+
+    #[tokio::test]
+    async fn can_move_dist_tag() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = set_dist_tag(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "beta".parse().unwrap(),
+            "2.0.0-beta.1".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(
+            package.dist_tags.get(&"beta".parse().unwrap()),
+            Some(&"2.0.0-beta.1".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn forbids_tagging_unpublished_version() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = set_dist_tag(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "next".parse().unwrap(),
+            "3.0.0".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(DistTagError::VersionNotFound));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+
+    #[tokio::test]
+    async fn forbids_tagging_version_of_non_existent_package() {
+        let package_repo = InMemoryPackageRepository::default();
+
+        let result = set_dist_tag(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "next".parse().unwrap(),
+            "1.0.0".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(DistTagError::PackageNotFound));
+        assert!(matches!(
+            Repository::<Package>::read(&package_repo, "user1/package1").await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn forbids_tagging_as_differently_cased_user() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = set_dist_tag(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            "beta".parse().unwrap(),
+            "2.0.0-beta.1".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(DistTagError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}
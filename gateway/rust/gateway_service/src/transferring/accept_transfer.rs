@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    models::{
+        AuditEntry, Package, PackageMetadata, PackageName, PublishPolicy, Username, WebhookScope,
+        WebhookSubscriptions,
+    },
+    read_package_by_name, AuditRepository, MoveRepository, Repository, RepositoryError,
+};
+
+use super::error::TransferError;
+
+/// Moves a package to the user it was offered to, keeping every version.
+/// The old id is left as a redirect, so it keeps resolving to the package.
+/// Both ids and the webhook subscriptions of the package are written in one transaction, a
+/// package is never lost or duplicated. Its audit history is copied to the new id beforehand.
+pub async fn accept_transfer(
+    user: &Username,
+    package_name: &PackageName,
+    recipient: &Username,
+    package_repo: impl Repository<Package> + Repository<WebhookSubscriptions> + MoveRepository,
+    audit_repo: &impl AuditRepository,
+) -> Result<Package, TransferError> {
+    let package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => TransferError::PackageNotFound,
+            e => TransferError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(TransferError::ConfusableName)?;
+
+    if package.pending_transfer.as_ref() != Some(recipient) {
+        return Err(TransferError::TransferNotFound);
+    }
+
+    // Versions keep the signatures of the old user, made over the old user's name
+    let moved_package = Package {
+        id: Package::id_of(recipient, package_name),
+        user: recipient.clone(),
+        versions: package
+            .versions
+            .iter()
+            .map(|version| version.clone().moved_from(user, package_name))
+            .collect(),
+        pending_transfer: None,
+        revision: 0,
+        ..package.clone()
+    };
+
+    match read_package_by_name(recipient, package_name, &package_repo).await {
+        Ok(_) => return Err(TransferError::TargetExists(moved_package.id)),
+        Err(RepositoryError::NotFound) => {}
+        Err(e) => return Err(TransferError::RepositoryError(e.to_string())),
+    }
+
+    let subscriptions = move_subscriptions(user, recipient, package_name, &package_repo).await?;
+
+    copy_audit_history(&package.id, &moved_package.id, audit_repo).await?;
+
+    let redirect = Package {
+        versions: vec![],
+        metadata: PackageMetadata::default(),
+        dist_tags: BTreeMap::new(),
+        policy: PublishPolicy::default(),
+        unpublished_versions: BTreeMap::new(),
+        pending_transfer: None,
+        redirect: Some(moved_package.id.clone()),
+        ..package
+    };
+
+    package_repo
+        .update_with_subscriptions(&[moved_package.clone(), redirect], &subscriptions)
+        .await
+        .map_err(|e| match e {
+            RepositoryError::Conflict => TransferError::Conflict,
+            e => TransferError::RepositoryError(e.to_string()),
+        })?;
+
+    Ok(moved_package)
+}
+
+/// The subscriptions of the package under the new id, and none left under the old one
+async fn move_subscriptions(
+    user: &Username,
+    recipient: &Username,
+    package_name: &PackageName,
+    webhook_repo: &impl Repository<WebhookSubscriptions>,
+) -> Result<Vec<WebhookSubscriptions>, TransferError> {
+    let read = |scope: WebhookScope| async move {
+        match webhook_repo.read(&scope.key()).await {
+            Ok(subscriptions) => Ok(Some(subscriptions)),
+            Err(RepositoryError::NotFound) => Ok(None),
+            Err(e) => Err(TransferError::RepositoryError(e.to_string())),
+        }
+    };
+
+    let Some(mut old) = read(WebhookScope::Package {
+        user: user.clone(),
+        package: package_name.clone(),
+    })
+    .await?
+    else {
+        return Ok(vec![]);
+    };
+
+    let new_scope = WebhookScope::Package {
+        user: recipient.clone(),
+        package: package_name.clone(),
+    };
+    let mut new = read(new_scope.clone())
+        .await?
+        .unwrap_or_else(|| WebhookSubscriptions {
+            id: new_scope.key(),
+            subscriptions: vec![],
+            revision: 0,
+        });
+
+    for subscription in old.subscriptions.drain(..) {
+        if !new.subscriptions.iter().any(|s| s.id == subscription.id) {
+            new.subscriptions.push(subscription);
+        }
+    }
+
+    Ok(vec![new, old])
+}
+
+/// Copies the entries the new id doesn't have yet, so a retried transfer doesn't copy them twice
+async fn copy_audit_history(
+    old_id: &str,
+    new_id: &str,
+    audit_repo: &impl AuditRepository,
+) -> Result<(), TransferError> {
+    let to_error = |e: RepositoryError| TransferError::RepositoryError(e.to_string());
+
+    let copied = audit_repo.list(new_id).await.map_err(to_error)?;
+
+    for entry in audit_repo.list(old_id).await.map_err(to_error)? {
+        let entry = AuditEntry {
+            package_id: new_id.to_string(),
+            ..entry
+        };
+
+        if !copied.iter().any(|e| e.entry_id() == entry.entry_id()) {
+            audit_repo.append(&entry).await.map_err(to_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{
+            AuditAction, AuditContext, VersionSignature, WebhookScope, WebhookSubscription,
+            WebhookSubscriptions,
+        },
+        transferring::{accept_transfer, TransferError},
+        AuditRepository, InMemoryPackageRepository, Package, Repository, Version,
+    };
+
+    fn package() -> Package {
+        let mut signed_version =
+            Version::new("1.0.1".parse().unwrap(), "test/uri2".parse().unwrap());
+        signed_version.signature = Some(VersionSignature {
+            key_id: "key1".into(),
+            signature: "signature1".into(),
+            origin: None,
+        });
+
+        Package {
+            id: "user1/package1".into(),
+            name: "package1".parse().unwrap(),
+            user: "user1".parse().unwrap(),
+            versions: vec![
+                Version::new("1.0.0".parse().unwrap(), "test/uri1".parse().unwrap()),
+                signed_version,
+            ],
+            created_on: 0,
+            metadata: Default::default(),
+            dist_tags: [("stable".parse().unwrap(), "1.0.1".parse().unwrap())].into(),
+            unpublished_versions: Default::default(),
+            pending_transfer: Some("user2".parse().unwrap()),
+            redirect: None,
+            revision: 0,
+            policy: Default::default(),
+        }
+    }
+
+    fn webhooks(user: &str) -> String {
+        WebhookScope::Package {
+            user: user.parse().unwrap(),
+            package: "package1".parse().unwrap(),
+        }
+        .key()
+    }
+
+    async fn repo_with_package() -> InMemoryPackageRepository {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        package_repo
+    }
+
+    #[tokio::test]
+    async fn moves_package_and_leaves_redirect() {
+        let package_repo = repo_with_package().await;
+
+        let result = accept_transfer(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            package_repo.clone(),
+            &InMemoryPackageRepository::default(),
+        )
+        .await;
+
+        assert_eq!(result.unwrap().id, "user2/package1");
+
+        let moved: Package = package_repo.read("user2/package1").await.unwrap();
+        assert_eq!(moved.user, "user2".parse().unwrap());
+        assert_eq!(moved.versions.len(), 2);
+        assert_eq!(moved.dist_tags.len(), 1);
+        assert_eq!(moved.pending_transfer, None);
+
+        let redirect: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(redirect.redirect, Some("user2/package1".into()));
+        assert!(redirect.versions.is_empty());
+        assert_eq!(redirect.pending_transfer, None);
+    }
+
+    #[tokio::test]
+    async fn signatures_name_the_old_user() {
+        let package_repo = repo_with_package().await;
+
+        let moved = accept_transfer(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            package_repo,
+            &InMemoryPackageRepository::default(),
+        )
+        .await
+        .unwrap();
+
+        assert!(moved.versions[0].signature.is_none());
+
+        let origin = moved.versions[1]
+            .signature
+            .as_ref()
+            .and_then(|signature| signature.origin.clone())
+            .unwrap();
+        assert_eq!(origin.user, "user1".parse().unwrap());
+        assert_eq!(origin.package, "package1".parse().unwrap());
+    }
+
+    #[tokio::test]
+    async fn moves_webhooks_and_audit_history() {
+        let package_repo = repo_with_package().await;
+        let subscription =
+            WebhookSubscription::new("https://example.com/hook".into(), "secret1".into());
+        package_repo
+            .update(&WebhookSubscriptions {
+                id: webhooks("user1"),
+                subscriptions: vec![subscription.clone()],
+                revision: 0,
+            })
+            .await
+            .unwrap();
+
+        let audit_repo = InMemoryPackageRepository::default();
+        let entry = AuditContext::new("user1".parse().unwrap(), "key1".into(), "request1".into())
+            .entry(
+                "user1/package1".into(),
+                AuditAction::Publish,
+                "1.0.0".parse().unwrap(),
+                None,
+                Some("test/uri1".parse().unwrap()),
+            );
+        audit_repo.append(&entry).await.unwrap();
+
+        accept_transfer(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            package_repo.clone(),
+            &audit_repo,
+        )
+        .await
+        .unwrap();
+
+        let old: WebhookSubscriptions = package_repo.read(&webhooks("user1")).await.unwrap();
+        let new: WebhookSubscriptions = package_repo.read(&webhooks("user2")).await.unwrap();
+        assert!(old.subscriptions.is_empty());
+        assert_eq!(new.subscriptions, vec![subscription]);
+
+        let history = audit_repo.list("user2/package1").await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].action, AuditAction::Publish);
+        assert_eq!(audit_repo.list("user1/package1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn requires_transfer_to_recipient() {
+        let result = accept_transfer(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user3".parse().unwrap(),
+            repo_with_package().await,
+            &InMemoryPackageRepository::default(),
+        )
+        .await;
+
+        assert_eq!(result, Err(TransferError::TransferNotFound));
+    }
+
+    #[tokio::test]
+    async fn cannot_replace_existing_package() {
+        let package_repo = repo_with_package().await;
+        package_repo
+            .update(&Package::new(
+                "package1".parse().unwrap(),
+                "user2".parse().unwrap(),
+            ))
+            .await
+            .unwrap();
+
+        let result = accept_transfer(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            package_repo,
+            &InMemoryPackageRepository::default(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(TransferError::TargetExists("user2/package1".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn forbids_accepting_from_differently_cased_user() {
+        let package_repo = repo_with_package().await;
+
+        let result = accept_transfer(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            package_repo.clone(),
+            &InMemoryPackageRepository::default(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(TransferError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.pending_transfer, Some("user2".parse().unwrap()));
+    }
+}
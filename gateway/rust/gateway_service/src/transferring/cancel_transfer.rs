@@ -0,0 +1,67 @@
+use crate::{
+    models::{Package, PackageName, Username},
+    read_package_by_name, Repository, RepositoryError,
+};
+
+use super::error::TransferError;
+
+/// Withdraws the pending offer of a package
+pub async fn cancel_transfer(
+    user: &Username,
+    package_name: &PackageName,
+    package_repo: impl Repository<Package>,
+) -> Result<(), TransferError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => TransferError::PackageNotFound,
+            e => TransferError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(TransferError::ConfusableName)?;
+
+    if package.pending_transfer.take().is_none() {
+        return Err(TransferError::TransferNotFound);
+    }
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => TransferError::Conflict,
+        e => TransferError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        transferring::{cancel_transfer, TransferError},
+        InMemoryPackageRepository, Package, Repository,
+    };
+
+    #[tokio::test]
+    async fn forbids_cancelling_as_differently_cased_user() {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.pending_transfer = Some("user2".parse().unwrap());
+
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package).await.unwrap();
+
+        let result = cancel_transfer(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(TransferError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}
@@ -0,0 +1,29 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum TransferError {
+    PackageNotFound,
+    SameUser,
+    TransferNotFound,
+    TargetExists(String),
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for TransferError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransferError::PackageNotFound => write!(f, "Package not found"),
+            TransferError::SameUser => write!(f, "Package already belongs to the user"),
+            TransferError::TransferNotFound => write!(f, "No pending transfer"),
+            TransferError::TargetExists(id) => write!(f, "Package `{}` already exists", id),
+            TransferError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            TransferError::Conflict => write!(f, "Package was changed during the transfer"),
+            TransferError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
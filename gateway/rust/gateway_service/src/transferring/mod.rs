@@ -0,0 +1,11 @@
+pub mod error;
+pub use error::*;
+
+mod request_transfer;
+pub use request_transfer::request_transfer;
+
+mod cancel_transfer;
+pub use cancel_transfer::cancel_transfer;
+
+mod accept_transfer;
+pub use accept_transfer::accept_transfer;
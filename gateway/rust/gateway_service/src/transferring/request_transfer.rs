@@ -0,0 +1,158 @@
+use crate::{
+    models::{Package, PackageName, Username},
+    read_package_by_name, Repository, RepositoryError,
+};
+
+use super::error::TransferError;
+
+/// Offers a package to another user, replacing any earlier offer.
+/// Nothing moves until the recipient accepts.
+pub async fn request_transfer(
+    user: &Username,
+    package_name: &PackageName,
+    to: &Username,
+    package_repo: impl Repository<Package>,
+) -> Result<(), TransferError> {
+    if user == to {
+        return Err(TransferError::SameUser);
+    }
+
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => TransferError::PackageNotFound,
+            e => TransferError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(TransferError::ConfusableName)?;
+
+    if package.redirect.is_some() {
+        return Err(TransferError::PackageNotFound);
+    }
+
+    if package.pending_transfer.as_ref() == Some(to) {
+        return Ok(());
+    }
+
+    package.pending_transfer = Some(to.clone());
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => TransferError::Conflict,
+        e => TransferError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use crate::{
+        mocks::MockPackageRepository,
+        transferring::{request_transfer, TransferError},
+        Package,
+    };
+
+    #[tokio::test]
+    async fn can_request_transfer() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user1/package1".to_string()))
+            .return_once(|_| {
+                Ok(Package::new(
+                    "package1".parse().unwrap(),
+                    "user1".parse().unwrap(),
+                ))
+            });
+
+        package_repo
+            .expect_update()
+            .withf(|p| {
+                p.id == "user1/package1"
+                    && p.pending_transfer == Some("user2".parse().unwrap())
+                    && p.redirect.is_none()
+            })
+            .times(1)
+            .return_once(|_| Ok(()));
+
+        let result = request_transfer(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn cannot_transfer_to_owner() {
+        let package_repo = MockPackageRepository::new();
+
+        let result = request_transfer(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user1".parse().unwrap(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(TransferError::SameUser));
+    }
+
+    #[tokio::test]
+    async fn cannot_transfer_moved_package() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo.expect_read().return_once(|_| {
+            let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+            package.redirect = Some("user3/package1".into());
+            Ok(package)
+        });
+
+        let result = request_transfer(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(result, Err(TransferError::PackageNotFound));
+    }
+
+    #[tokio::test]
+    async fn forbids_requesting_as_differently_cased_user() {
+        let mut package_repo = MockPackageRepository::new();
+
+        package_repo
+            .expect_read()
+            .with(eq("user1/package1".to_string()))
+            .return_once(|_| {
+                Ok(Package::new(
+                    "package1".parse().unwrap(),
+                    "user1".parse().unwrap(),
+                ))
+            });
+        package_repo.expect_update().never();
+
+        let result = request_transfer(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"user2".parse().unwrap(),
+            package_repo,
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(TransferError::ConfusableName("user1/package1".into()))
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    models::{WebhookScope, WebhookSubscription, WebhookSubscriptions},
+    Repository, RepositoryError,
+};
+
+use super::error::WebhookError;
+
+pub async fn add_subscription(
+    scope: &WebhookScope,
+    url: String,
+    secret: String,
+    webhook_repo: impl Repository<WebhookSubscriptions>,
+) -> Result<WebhookSubscription, WebhookError> {
+    let mut subscriptions = match webhook_repo.read(&scope.key()).await {
+        Ok(subscriptions) => subscriptions,
+        Err(RepositoryError::NotFound) => WebhookSubscriptions::new(scope),
+        Err(e) => return Err(WebhookError::RepositoryError(e.to_string())),
+    };
+
+    let subscription = WebhookSubscription::new(url, secret);
+
+    subscriptions
+        .subscriptions
+        .retain(|existing| existing.id != subscription.id);
+    subscriptions.subscriptions.push(subscription.clone());
+
+    webhook_repo
+        .update(&subscriptions)
+        .await
+        .map_err(|e| match e {
+            RepositoryError::Conflict => WebhookError::Conflict,
+            e => WebhookError::RepositoryError(e.to_string()),
+        })?;
+
+    Ok(subscription)
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use crate::{
+        mocks::MockWebhookRepository,
+        models::{WebhookScope, WebhookSubscription, WebhookSubscriptions},
+        webhooks::add_subscription,
+    };
+
+    #[tokio::test]
+    async fn replaces_subscription_with_same_url() {
+        let scope = WebhookScope::Package {
+            user: "user1".parse().unwrap(),
+            package: "package1".parse().unwrap(),
+        };
+
+        let existing = WebhookSubscription::new("https://example.com/hook".into(), "old".into());
+
+        let mut webhook_repo = MockWebhookRepository::new();
+
+        {
+            let existing = existing.clone();
+            webhook_repo
+                .expect_read()
+                .with(eq("webhooks:user1/package1".to_string()))
+                .return_once(move |_| {
+                    Ok(WebhookSubscriptions {
+                        id: "webhooks:user1/package1".into(),
+                        subscriptions: vec![existing],
+                        revision: 1,
+                    })
+                });
+        }
+
+        webhook_repo
+            .expect_update()
+            .withf(|s| {
+                s.revision == 1 && s.subscriptions.len() == 1 && s.subscriptions[0].secret == "new"
+            })
+            .return_once(|_| Ok(()));
+
+        let subscription = add_subscription(
+            &scope,
+            "https://example.com/hook".into(),
+            "new".into(),
+            webhook_repo,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(subscription.id, existing.id);
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::SystemTime;
+
+use crate::{
+    models::{WebhookSubscription, WebhookSubscriptions},
+    Repository, RepositoryError,
+};
+
+use super::{error::WebhookError, sign, QueuedDelivery, WebhookDelivery};
+
+/// Delivers a queued event to its subscriber. Subscriptions removed since it was queued are
+/// skipped. A failed delivery is returned, so the queue can retry it.
+pub async fn deliver_queued(
+    queued: &QueuedDelivery,
+    webhook_repo: &impl Repository<WebhookSubscriptions>,
+    delivery: &impl WebhookDelivery,
+) -> Result<(), WebhookError> {
+    let subscriptions = match webhook_repo.read(&queued.scope_key).await {
+        Ok(subscriptions) => subscriptions.subscriptions,
+        Err(RepositoryError::NotFound) => return Ok(()),
+        Err(e) => return Err(WebhookError::RepositoryError(e.to_string())),
+    };
+
+    match subscriptions
+        .iter()
+        .find(|subscription| subscription.id == queued.subscription_id)
+    {
+        Some(subscription) => deliver_signed(subscription, &queued.body, delivery)
+            .await
+            .map_err(WebhookError::DeliveryError),
+        None => Ok(()),
+    }
+}
+
+/// Signs the body when it's sent, so the timestamp subscribers check is that of the attempt
+pub(super) async fn deliver_signed(
+    subscription: &WebhookSubscription,
+    body: &str,
+    delivery: &impl WebhookDelivery,
+) -> Result<(), String> {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+    let signature = sign(&subscription.secret, timestamp, body);
+
+    delivery
+        .deliver(&subscription.url, body, timestamp, &signature)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use crate::{
+        mocks::{MockWebhookDelivery, MockWebhookRepository},
+        models::{WebhookSubscription, WebhookSubscriptions},
+        webhooks::{deliver_queued, sign, QueuedDelivery, WebhookError},
+    };
+
+    fn subscriptions() -> WebhookSubscriptions {
+        WebhookSubscriptions {
+            id: "webhooks".into(),
+            subscriptions: vec![WebhookSubscription::new(
+                "https://indexer.example.com".into(),
+                "secret".into(),
+            )],
+            revision: 1,
+        }
+    }
+
+    fn queued(subscription_id: &str) -> QueuedDelivery {
+        QueuedDelivery {
+            scope_key: "webhooks".into(),
+            subscription_id: subscription_id.into(),
+            body: r#"{"event":"publish"}"#.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_event_to_the_subscription() {
+        let mut webhook_repo = MockWebhookRepository::new();
+        let mut delivery = MockWebhookDelivery::new();
+
+        let subscription_id = subscriptions().subscriptions[0].id.clone();
+        webhook_repo
+            .expect_read()
+            .with(eq("webhooks".to_string()))
+            .return_once(|_| Ok(subscriptions()));
+        delivery
+            .expect_deliver()
+            .withf(|url, body, timestamp, signature| {
+                url == "https://indexer.example.com"
+                    && body == r#"{"event":"publish"}"#
+                    && signature == sign("secret", *timestamp, body)
+            })
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let result = deliver_queued(&queued(&subscription_id), &webhook_repo, &delivery).await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn skips_removed_subscriptions() {
+        let mut webhook_repo = MockWebhookRepository::new();
+
+        webhook_repo
+            .expect_read()
+            .return_once(|_| Ok(subscriptions()));
+
+        // Any delivery fails the test
+        let result = deliver_queued(
+            &queued("removed"),
+            &webhook_repo,
+            &MockWebhookDelivery::new(),
+        )
+        .await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn returns_failed_deliveries_to_be_retried() {
+        let mut webhook_repo = MockWebhookRepository::new();
+        let mut delivery = MockWebhookDelivery::new();
+
+        let subscription_id = subscriptions().subscriptions[0].id.clone();
+        webhook_repo
+            .expect_read()
+            .return_once(|_| Ok(subscriptions()));
+        delivery
+            .expect_deliver()
+            .return_once(|_, _, _, _| Err("Service unavailable".into()));
+
+        let result = deliver_queued(&queued(&subscription_id), &webhook_repo, &delivery).await;
+
+        assert_eq!(
+            result,
+            Err(WebhookError::DeliveryError("Service unavailable".into()))
+        );
+    }
+}
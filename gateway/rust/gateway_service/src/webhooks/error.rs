@@ -0,0 +1,23 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum WebhookError {
+    SubscriptionNotFound,
+    Conflict,
+    RepositoryError(String),
+    QueueError(String),
+    DeliveryError(String),
+}
+impl Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookError::SubscriptionNotFound => write!(f, "Subscription not found"),
+            WebhookError::Conflict => {
+                write!(f, "Subscriptions were changed by a concurrent update")
+            }
+            WebhookError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+            WebhookError::QueueError(e) => write!(f, "Failed to queue deliveries: {}", e),
+            WebhookError::DeliveryError(e) => write!(f, "Delivery failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::{
+    constants,
+    http_utils::{check_public_url, public_client_builder},
+};
+
+use super::WebhookDelivery;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delivers to public addresses only, subscribers can't make the registry call internal services
+pub struct HttpWebhookDelivery {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookDelivery {
+    pub fn new() -> Self {
+        Self {
+            client: public_client_builder()
+                .build()
+                .expect("Webhook client is always valid"),
+        }
+    }
+}
+
+impl Default for HttpWebhookDelivery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WebhookDelivery for HttpWebhookDelivery {
+    async fn deliver(
+        &self,
+        url: &str,
+        body: &str,
+        timestamp: u64,
+        signature: &str,
+    ) -> Result<(), String> {
+        // Subscriptions added before their URLs were checked are never delivered to either
+        let url = check_public_url(url)?;
+
+        let response = self
+            .client
+            .post(url)
+            .timeout(REQUEST_TIMEOUT)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header(constants::WEBHOOK_TIMESTAMP_HEADER, timestamp)
+            .header(constants::WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Subscriber responded with {}", response.status()))
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+
+use crate::models::{WebhookScope, WebhookSubscription};
+
+use super::{deliver_queued::deliver_signed, WebhookDelivery, WebhookQueue};
+
+/// Delivers right away instead of queueing, for running the server locally without SQS.
+/// Every subscriber gets a single attempt.
+pub struct ImmediateWebhookQueue<D: WebhookDelivery> {
+    delivery: D,
+}
+
+impl<D: WebhookDelivery> ImmediateWebhookQueue<D> {
+    pub fn new(delivery: D) -> Self {
+        Self { delivery }
+    }
+}
+
+#[async_trait]
+impl<D: WebhookDelivery + Send + Sync> WebhookQueue for ImmediateWebhookQueue<D> {
+    async fn enqueue(
+        &self,
+        body: &str,
+        subscriptions: &[(WebhookScope, WebhookSubscription)],
+    ) -> Result<(), String> {
+        for (_, subscription) in subscriptions {
+            if let Err(e) = deliver_signed(subscription, body, &self.delivery).await {
+                eprintln!("Failed to deliver webhook to {}: {}", subscription.url, e);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use crate::{
+    models::{WebhookScope, WebhookSubscription, WebhookSubscriptions},
+    Repository, RepositoryError,
+};
+
+use super::error::WebhookError;
+
+pub async fn list_subscriptions(
+    scope: &WebhookScope,
+    webhook_repo: &impl Repository<WebhookSubscriptions>,
+) -> Result<Vec<WebhookSubscription>, WebhookError> {
+    match webhook_repo.read(&scope.key()).await {
+        Ok(subscriptions) => Ok(subscriptions.subscriptions),
+        Err(RepositoryError::NotFound) => Ok(vec![]),
+        Err(e) => Err(WebhookError::RepositoryError(e.to_string())),
+    }
+}
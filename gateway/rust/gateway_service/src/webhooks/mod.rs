@@ -0,0 +1,42 @@
+pub mod error;
+pub use error::*;
+
+mod publish_event;
+pub use publish_event::PublishEvent;
+
+mod signature;
+pub use signature::sign;
+
+mod webhook_delivery;
+pub use webhook_delivery::*;
+
+mod http_webhook_delivery;
+pub use http_webhook_delivery::HttpWebhookDelivery;
+
+mod webhook_queue;
+pub use webhook_queue::*;
+
+#[cfg(not(feature = "local"))]
+mod sqs_webhook_queue;
+#[cfg(not(feature = "local"))]
+pub use sqs_webhook_queue::SqsWebhookQueue;
+
+#[cfg(feature = "local")]
+mod immediate_webhook_queue;
+#[cfg(feature = "local")]
+pub use immediate_webhook_queue::ImmediateWebhookQueue;
+
+mod add_subscription;
+pub use add_subscription::add_subscription;
+
+mod remove_subscription;
+pub use remove_subscription::remove_subscription;
+
+mod list_subscriptions;
+pub use list_subscriptions::list_subscriptions;
+
+mod notify_subscribers;
+pub use notify_subscribers::*;
+
+mod deliver_queued;
+pub use deliver_queued::deliver_queued;
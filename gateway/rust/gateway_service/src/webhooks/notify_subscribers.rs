@@ -0,0 +1,154 @@
+use crate::{
+    models::{WebhookScope, WebhookSubscriptions},
+    Repository,
+};
+
+use super::{error::WebhookError, list_subscriptions, PublishEvent, WebhookQueue};
+
+/// Queues the event for the registry-wide subscribers and for the subscribers of the package, it's
+/// delivered after the request by `deliver_queued`. The publish itself already succeeded, so
+/// subscribers that can't be notified are only reported.
+pub async fn notify_subscribers(
+    event: &PublishEvent,
+    webhook_repo: &impl Repository<WebhookSubscriptions>,
+    queue: &impl WebhookQueue,
+) -> Result<(), WebhookError> {
+    let package_scope = WebhookScope::Package {
+        user: event.user.clone(),
+        package: event.package.clone(),
+    };
+
+    let mut subscriptions = vec![];
+    for scope in [WebhookScope::Registry, package_scope] {
+        let scope_subscriptions = list_subscriptions(&scope, webhook_repo).await?;
+        subscriptions.extend(
+            scope_subscriptions
+                .into_iter()
+                .map(|subscription| (scope.clone(), subscription)),
+        );
+    }
+
+    if subscriptions.is_empty() {
+        return Ok(());
+    }
+
+    let body = serde_json::to_string(event).expect("Publish events always serialize");
+
+    queue
+        .enqueue(&body, &subscriptions)
+        .await
+        .map_err(WebhookError::QueueError)
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::predicate::eq;
+
+    use crate::{
+        mocks::{MockWebhookQueue, MockWebhookRepository},
+        models::{WebhookScope, WebhookSubscription, WebhookSubscriptions},
+        webhooks::{notify_subscribers, PublishEvent, WebhookError},
+        RepositoryError, Version,
+    };
+
+    fn event() -> PublishEvent {
+        PublishEvent::new(
+            "user1".parse().unwrap(),
+            "package1".parse().unwrap(),
+            Version {
+                name: "1.0.0".parse().unwrap(),
+                uri: "test/uri1".parse().unwrap(),
+                created_on: 0,
+                yanked: None,
+                manifest: None,
+                signature: None,
+            },
+        )
+    }
+
+    fn subscriptions(key: &str, url: &str) -> WebhookSubscriptions {
+        WebhookSubscriptions {
+            id: key.into(),
+            subscriptions: vec![WebhookSubscription::new(url.into(), "secret".into())],
+            revision: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn queues_event_for_registry_and_package_subscribers() {
+        let mut webhook_repo = MockWebhookRepository::new();
+        let mut queue = MockWebhookQueue::new();
+
+        webhook_repo
+            .expect_read()
+            .with(eq("webhooks".to_string()))
+            .return_once(|key| Ok(subscriptions(key, "https://indexer.example.com")));
+        webhook_repo
+            .expect_read()
+            .with(eq("webhooks:user1/package1".to_string()))
+            .return_once(|key| Ok(subscriptions(key, "https://cdn.example.com")));
+
+        queue
+            .expect_enqueue()
+            .withf(|body, subscriptions| {
+                body == r#"{"event":"publish","user":"user1","package":"package1","version":"1.0.0","uri":"wrap://test/uri1","created_on":0}"#
+                    && subscriptions
+                        .iter()
+                        .map(|(scope, subscription)| (scope.key(), subscription.url.as_str()))
+                        .eq([
+                            ("webhooks".to_string(), "https://indexer.example.com"),
+                            (
+                                "webhooks:user1/package1".to_string(),
+                                "https://cdn.example.com",
+                            ),
+                        ])
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let result = notify_subscribers(&event(), &webhook_repo, &queue).await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn queues_nothing_without_subscribers() {
+        let mut webhook_repo = MockWebhookRepository::new();
+
+        webhook_repo
+            .expect_read()
+            .times(2)
+            .returning(|_| Err(RepositoryError::NotFound));
+
+        // Any call to the queue fails the test
+        let result = notify_subscribers(&event(), &webhook_repo, &MockWebhookQueue::new()).await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn reports_events_that_could_not_be_queued() {
+        let mut webhook_repo = MockWebhookRepository::new();
+        let mut queue = MockWebhookQueue::new();
+
+        webhook_repo
+            .expect_read()
+            .with(eq(WebhookScope::Registry.key()))
+            .return_once(|key| Ok(subscriptions(key, "https://indexer.example.com")));
+        webhook_repo
+            .expect_read()
+            .with(eq("webhooks:user1/package1".to_string()))
+            .return_once(|_| Err(RepositoryError::NotFound));
+
+        queue
+            .expect_enqueue()
+            .return_once(|_, _| Err("Queue unavailable".into()));
+
+        let result = notify_subscribers(&event(), &webhook_repo, &queue).await;
+
+        assert_eq!(
+            result,
+            Err(WebhookError::QueueError("Queue unavailable".into()))
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+use crate::models::{PackageName, Username, Version, VersionName, WrapUri};
+
+/// The JSON body sent to subscribers when a version is published.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct PublishEvent {
+    pub event: &'static str,
+    pub user: Username,
+    pub package: PackageName,
+    pub version: VersionName,
+    pub uri: WrapUri,
+    pub created_on: u128,
+}
+
+impl PublishEvent {
+    pub fn new(user: Username, package: PackageName, version: Version) -> Self {
+        Self {
+            event: "publish",
+            user,
+            package,
+            version: version.name,
+            uri: version.uri,
+            created_on: version.created_on,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{
+    models::{WebhookScope, WebhookSubscriptions},
+    Repository, RepositoryError,
+};
+
+use super::error::WebhookError;
+
+pub async fn remove_subscription(
+    scope: &WebhookScope,
+    id: &str,
+    webhook_repo: impl Repository<WebhookSubscriptions>,
+) -> Result<(), WebhookError> {
+    let mut subscriptions = webhook_repo
+        .read(&scope.key())
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => WebhookError::SubscriptionNotFound,
+            error => WebhookError::RepositoryError(error.to_string()),
+        })?;
+
+    let count = subscriptions.subscriptions.len();
+    subscriptions
+        .subscriptions
+        .retain(|subscription| subscription.id != id);
+
+    if subscriptions.subscriptions.len() == count {
+        return Err(WebhookError::SubscriptionNotFound);
+    }
+
+    webhook_repo
+        .update(&subscriptions)
+        .await
+        .map_err(|e| match e {
+            RepositoryError::Conflict => WebhookError::Conflict,
+            e => WebhookError::RepositoryError(e.to_string()),
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Signs the timestamp and the body with the subscription secret, so subscribers can verify an
+/// event came from the registry and reject old events that are sent again.
+/// The input is `<timestamp>.<body>`, the result is sent as `sha256=<hex encoded HMAC-SHA256>`.
+pub fn sign(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sign;
+
+    #[test]
+    fn signs_timestamp_and_body_with_hmac_sha256() {
+        // The key and data of test case 2 of RFC 4231, with the timestamp prefixed to the data
+        assert_eq!(
+            sign("Jefe", 1700000000, "what do ya want for nothing?"),
+            "sha256=1cdd0650c8be1cb0974b1788d458b1e781206cfef59b85faafc582d2e182c57e"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use aws_sdk_sqs::{types::SendMessageBatchRequestEntry, Client};
+
+use crate::models::{WebhookScope, WebhookSubscription};
+
+use super::{QueuedDelivery, WebhookQueue};
+
+/// SQS accepts at most 10 messages per batch
+const MAX_BATCH_MESSAGES: usize = 10;
+
+/// Sends a message per subscriber to the queue `deliver_webhooks` consumes, which retries failed
+/// deliveries by receiving them again.
+pub struct SqsWebhookQueue {
+    client: Client,
+    queue_url: String,
+}
+
+impl SqsWebhookQueue {
+    pub fn new(client: Client, queue_url: String) -> Self {
+        Self { client, queue_url }
+    }
+}
+
+#[async_trait]
+impl WebhookQueue for SqsWebhookQueue {
+    async fn enqueue(
+        &self,
+        body: &str,
+        subscriptions: &[(WebhookScope, WebhookSubscription)],
+    ) -> Result<(), String> {
+        let deliveries = subscriptions
+            .iter()
+            .map(|(scope, subscription)| QueuedDelivery::new(scope, subscription, body))
+            .collect::<Vec<_>>();
+
+        for batch in deliveries.chunks(MAX_BATCH_MESSAGES) {
+            let entries = batch
+                .iter()
+                .enumerate()
+                .map(|(i, delivery)| {
+                    SendMessageBatchRequestEntry::builder()
+                        .id(i.to_string())
+                        .message_body(
+                            serde_json::to_string(delivery)
+                                .expect("Queued deliveries always serialize"),
+                        )
+                        .build()
+                })
+                .collect::<Vec<_>>();
+
+            let output = self
+                .client
+                .send_message_batch()
+                .queue_url(&self.queue_url)
+                .set_entries(Some(entries))
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if let Some(failed) = output.failed().filter(|failed| !failed.is_empty()) {
+                return Err(format!(
+                    "Failed to queue {} webhook deliveries: {}",
+                    failed.len(),
+                    failed
+                        .iter()
+                        .filter_map(|entry| entry.message())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+/// Sends a signed event to a subscriber, `timestamp` is when it was signed in seconds since the epoch.
+#[async_trait]
+pub trait WebhookDelivery {
+    async fn deliver(
+        &self,
+        url: &str,
+        body: &str,
+        timestamp: u64,
+        signature: &str,
+    ) -> Result<(), String>;
+}
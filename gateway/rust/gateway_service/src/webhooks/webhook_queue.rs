@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{WebhookScope, WebhookSubscription};
+
+/// An event to deliver to a single subscriber. It only names the subscription, the secret stays
+/// in the store and a subscription removed in the meantime is never delivered to.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct QueuedDelivery {
+    /// The key of the subscriptions, see `WebhookScope::key`
+    pub scope_key: String,
+    pub subscription_id: String,
+    pub body: String,
+}
+
+impl QueuedDelivery {
+    pub fn new(scope: &WebhookScope, subscription: &WebhookSubscription, body: &str) -> Self {
+        Self {
+            scope_key: scope.key(),
+            subscription_id: subscription.id.clone(),
+            body: body.to_string(),
+        }
+    }
+}
+
+/// Hands events over to be delivered after the request, so a publish never waits for subscribers.
+#[async_trait]
+pub trait WebhookQueue {
+    async fn enqueue(
+        &self,
+        body: &str,
+        subscriptions: &[(WebhookScope, WebhookSubscription)],
+    ) -> Result<(), String>;
+}
@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum YankError {
+    PackageNotFound,
+    VersionNotFound,
+    ConfusableName(String),
+    Conflict,
+    RepositoryError(String),
+}
+impl Display for YankError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            YankError::PackageNotFound => write!(f, "Package not found"),
+            YankError::VersionNotFound => write!(f, "Version not found"),
+            YankError::ConfusableName(id) => write!(
+                f,
+                "Package is published as `{}`, names can not differ only in casing",
+                id
+            ),
+            YankError::Conflict => write!(f, "Package was changed by a concurrent update"),
+            YankError::RepositoryError(e) => write!(f, "Repository error: {}", e),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod error;
+pub use error::*;
+
+mod yank_version;
+pub use yank_version::yank_version;
@@ -0,0 +1,163 @@
+use crate::{
+    models::{Package, PackageName, Username, WrapUri, Yank},
+    read_package_by_name, Repository, RepositoryError, SemVer,
+};
+
+use super::error::YankError;
+
+/// Returns the URI of the yanked version
+pub async fn yank_version(
+    user: &Username,
+    package_name: &PackageName,
+    version: &SemVer,
+    reason: String,
+    package_repo: impl Repository<Package>,
+) -> Result<WrapUri, YankError> {
+    let mut package = read_package_by_name(user, package_name, &package_repo)
+        .await
+        .map_err(|error| match error {
+            RepositoryError::NotFound => YankError::PackageNotFound,
+            e => YankError::RepositoryError(e.to_string()),
+        })?;
+
+    package
+        .check_owner(user, package_name)
+        .map_err(YankError::ConfusableName)?;
+
+    let existing_version = package
+        .versions
+        .iter_mut()
+        .find(|v| v.name.semver() == Some(version))
+        .ok_or(YankError::VersionNotFound)?;
+    let uri = existing_version.uri.clone();
+
+    match &mut existing_version.yanked {
+        // Yanking again only updates the reason, the version stays yanked since the first time
+        Some(yank) if yank.reason == reason => return Ok(uri),
+        Some(yank) => yank.reason = reason,
+        None => existing_version.yanked = Some(Yank::new(reason)),
+    }
+
+    package_repo.update(&package).await.map_err(|e| match e {
+        RepositoryError::Conflict => YankError::Conflict,
+        e => YankError::RepositoryError(e.to_string()),
+    })?;
+
+    Ok(uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::Yank,
+        yanking::{yank_version, YankError},
+        InMemoryPackageRepository, Package, Repository, Version,
+    };
+
+    fn package() -> Package {
+        let mut package = Package::new("package1".parse().unwrap(), "user1".parse().unwrap());
+        package.versions = vec![
+            Version::new("1.0.0".parse().unwrap(), "test/uri1".parse().unwrap()),
+            Version {
+                yanked: Some(Yank {
+                    reason: "Broken build".into(),
+                    yanked_on: 1,
+                }),
+                ..Version::new("1.0.1".parse().unwrap(), "test/uri2".parse().unwrap())
+            },
+        ];
+        package
+    }
+
+    #[tokio::test]
+    async fn can_yank_version() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = yank_version(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"1.0.0".parse().unwrap(),
+            "Security issue".into(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Ok("test/uri1".parse().unwrap()));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.versions.len(), 2);
+        assert!(
+            matches!(&package.versions[0].yanked, Some(yank) if yank.reason == "Security issue")
+        );
+    }
+
+    #[tokio::test]
+    async fn yanking_again_keeps_original_yank_date() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = yank_version(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"1.0.1".parse().unwrap(),
+            "Security issue".into(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Ok("test/uri2".parse().unwrap()));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(
+            package.versions[1].yanked,
+            Some(Yank {
+                reason: "Security issue".into(),
+                yanked_on: 1,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_to_yank_non_existent_version() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = yank_version(
+            &"user1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"2.0.0".parse().unwrap(),
+            "Security issue".into(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(result, Err(YankError::VersionNotFound));
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+
+    #[tokio::test]
+    async fn forbids_yanking_as_differently_cased_user() {
+        let package_repo = InMemoryPackageRepository::default();
+        package_repo.update(&package()).await.unwrap();
+
+        let result = yank_version(
+            &"User1".parse().unwrap(),
+            &"package1".parse().unwrap(),
+            &"1.0.0".parse().unwrap(),
+            "Security issue".into(),
+            package_repo.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(YankError::ConfusableName("user1/package1".into()))
+        );
+
+        let package: Package = package_repo.read("user1/package1").await.unwrap();
+        assert_eq!(package.revision, 1);
+    }
+}